@@ -13,9 +13,9 @@ else
   EVOIDEA=(cargo run --quiet --)
 fi
 
-# This repo's Rust CLI may not include the legacy `run` subcommand anymore.
+# Older builds of the Rust CLI may not include the `run` subcommand.
 if "${EVOIDEA[@]}" --help | grep -qE '^[[:space:]]+run[[:space:]]'; then
-  "${EVOIDEA[@]}" run --mode codex --prompt "${PROMPT}"
+  "${EVOIDEA[@]}" run --prompt "${PROMPT}"
   exit 0
 fi
 
@@ -0,0 +1,491 @@
+use anyhow::Result;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashSet};
+
+use crate::config::tokenize;
+use crate::data::{Facets, Idea};
+use crate::search;
+use crate::similarity::similar_pairs;
+use crate::storage::Storage;
+
+/// Config keys that differ between every run and say nothing about the setup
+const IGNORED_CONFIG_KEYS: [&str; 2] = ["run_id", "created_at"];
+
+/// Distribution of the overall scores of every scored idea in a run
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ScoreSummary {
+    pub count: usize,
+    pub min: Option<f32>,
+    pub median: Option<f32>,
+    pub mean: Option<f32>,
+    pub max: Option<f32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RunSummary {
+    pub run_id: String,
+    pub prompt: String,
+    pub rounds_used: u32,
+    pub max_rounds: u32,
+    pub stop_reason: Option<String>,
+    pub best_idea_id: Option<String>,
+    pub best_score: Option<f32>,
+    pub scores: ScoreSummary,
+}
+
+/// Ideas of `a` that have a near-duplicate in `b`, and the other way round
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Overlap {
+    pub a: String,
+    pub b: String,
+    pub a_matched: usize,
+    pub a_total: usize,
+    pub b_matched: usize,
+    pub b_total: usize,
+}
+
+/// One facet of every winner, with the mean pairwise word overlap (Jaccard)
+#[derive(Debug, Clone, Serialize)]
+pub struct WinnerFacet {
+    pub values: Vec<Option<String>>,
+    pub similarity: Option<f32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Comparison {
+    pub runs: Vec<RunSummary>,
+    /// Flattened config key -> value in each run, for keys that differ
+    pub config_differences: BTreeMap<String, Vec<Value>>,
+    pub threshold: f64,
+    pub overlap: Vec<Overlap>,
+    pub winner_facets: BTreeMap<String, WinnerFacet>,
+}
+
+/// Distribution of overall scores; eliminated ideas are left out, since their
+/// forced score of 0 says nothing about how the critic judged the run
+pub fn score_summary(ideas: &[Idea]) -> ScoreSummary {
+    let mut scores: Vec<f32> = ideas
+        .iter()
+        .filter(|i| !i.status.is_eliminated())
+        .filter_map(|i| i.overall_score)
+        .collect();
+    scores.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    let n = scores.len();
+    let median = match n {
+        0 => None,
+        _ if n % 2 == 1 => Some(scores[n / 2]),
+        _ => Some((scores[n / 2 - 1] + scores[n / 2]) / 2.0),
+    };
+    ScoreSummary {
+        count: n,
+        min: scores.first().copied(),
+        median,
+        mean: (n > 0).then(|| scores.iter().sum::<f32>() / n as f32),
+        max: scores.last().copied(),
+    }
+}
+
+/// Flatten nested objects into dotted keys (`scoring_weights.feasibility`)
+fn flatten(prefix: &str, value: &Value, out: &mut BTreeMap<String, Value>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                let key = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                flatten(&key, value, out);
+            }
+        }
+        _ => {
+            out.insert(prefix.to_string(), value.clone());
+        }
+    }
+}
+
+/// Flattened keys whose values are not the same in every config
+pub fn config_differences(configs: &[Value]) -> BTreeMap<String, Vec<Value>> {
+    let flat: Vec<BTreeMap<String, Value>> = configs
+        .iter()
+        .map(|config| {
+            let mut out = BTreeMap::new();
+            flatten("", config, &mut out);
+            out
+        })
+        .collect();
+    let keys: HashSet<&String> = flat.iter().flat_map(|f| f.keys()).collect();
+
+    keys.into_iter()
+        .filter(|key| !IGNORED_CONFIG_KEYS.contains(&key.as_str()))
+        .filter_map(|key| {
+            let values: Vec<Value> = flat
+                .iter()
+                .map(|f| f.get(key).cloned().unwrap_or(Value::Null))
+                .collect();
+            values
+                .iter()
+                .any(|v| *v != values[0])
+                .then(|| (key.clone(), values))
+        })
+        .collect()
+}
+
+fn jaccard(a: &str, b: &str) -> Option<f32> {
+    let (a, b): (HashSet<String>, HashSet<String>) = (
+        tokenize(a).into_iter().collect(),
+        tokenize(b).into_iter().collect(),
+    );
+    let union = a.union(&b).count();
+    (union > 0).then(|| a.intersection(&b).count() as f32 / union as f32)
+}
+
+/// Facets of each winner side by side, with how much wording they share
+pub fn winner_facets(winners: &[Option<&Idea>]) -> BTreeMap<String, WinnerFacet> {
+    Facets::NAMES
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let values: Vec<Option<&str>> = winners
+                .iter()
+                .map(|w| w.map(|idea| idea.facets.fields()[i].1))
+                .collect();
+            let present: Vec<&str> = values.iter().flatten().copied().collect();
+            let mut total = 0.0;
+            let mut pairs = 0;
+            for (j, a) in present.iter().enumerate() {
+                for b in &present[j + 1..] {
+                    if let Some(sim) = jaccard(a, b) {
+                        total += sim;
+                        pairs += 1;
+                    }
+                }
+            }
+            let facet = WinnerFacet {
+                values: values.iter().map(|v| v.map(str::to_string)).collect(),
+                similarity: (pairs > 0).then(|| total / pairs as f32),
+            };
+            (name.to_string(), facet)
+        })
+        .collect()
+}
+
+/// Collect the comparison of several runs
+pub fn compare(storage: &dyn Storage, run_ids: &[String], threshold: f64) -> Result<Comparison> {
+    if run_ids.len() < 2 {
+        anyhow::bail!("Pass at least two --run-id values to compare");
+    }
+    if !(0.0..=1.0).contains(&threshold) {
+        anyhow::bail!("--threshold must be between 0 and 1, got {}", threshold);
+    }
+    // A run compared with itself would report its own ideas as cross-run overlap
+    let mut seen = HashSet::new();
+    if let Some(run_id) = run_ids.iter().find(|id| !seen.insert(id.as_str())) {
+        anyhow::bail!("Run {} was passed more than once", run_id);
+    }
+
+    let mut runs = Vec::new();
+    let mut configs = Vec::new();
+    let mut states = Vec::new();
+    let mut winners = Vec::new();
+    for run_id in run_ids {
+        if !storage.has_state(run_id)? {
+            anyhow::bail!("Run {} not found", run_id);
+        }
+        let config = storage.load_config(run_id)?;
+        let state = storage.load_state(run_id)?;
+        let result = match storage.has_final(run_id)? {
+            true => Some(storage.load_final(run_id)?),
+            false => None,
+        };
+        let winner = match &result {
+            Some(result) => Some(result.best_idea.clone()),
+            None => state
+                .best_idea_id
+                .as_deref()
+                .and_then(|id| state.idea(id))
+                .cloned(),
+        };
+
+        runs.push(RunSummary {
+            run_id: run_id.clone(),
+            prompt: config.prompt.clone(),
+            rounds_used: state.iteration,
+            max_rounds: config.max_rounds,
+            stop_reason: result.as_ref().map(|r| r.stop_reason.clone()),
+            best_idea_id: winner.as_ref().map(|w| w.id.clone()),
+            best_score: state.best_score,
+            scores: score_summary(&state.ideas),
+        });
+        configs.push(serde_json::to_value(&config)?);
+        winners.push(winner);
+        states.push(state);
+    }
+
+    // Every idea of every run shares one TF-IDF space; only pairs that cross
+    // runs count as overlap
+    let documents: Vec<search::Document> = run_ids
+        .iter()
+        .zip(&states)
+        .flat_map(|(run_id, state)| search::run_documents(run_id, Some(state), None))
+        .collect();
+    let pairs = similar_pairs(&documents, threshold, |_| true);
+    let mut overlap = Vec::new();
+    for (i, a) in run_ids.iter().enumerate() {
+        for b in &run_ids[i + 1..] {
+            let mut matched: HashSet<usize> = HashSet::new();
+            for &(x, y, _) in &pairs {
+                let (rx, ry) = (&documents[x].run_id, &documents[y].run_id);
+                if (rx == a && ry == b) || (rx == b && ry == a) {
+                    matched.insert(x);
+                    matched.insert(y);
+                }
+            }
+            let count = |run: &String| {
+                matched
+                    .iter()
+                    .filter(|&&d| documents[d].run_id == *run)
+                    .count()
+            };
+            let total = |run: &String| documents.iter().filter(|d| d.run_id == *run).count();
+            overlap.push(Overlap {
+                a: a.clone(),
+                b: b.clone(),
+                a_matched: count(a),
+                a_total: total(a),
+                b_matched: count(b),
+                b_total: total(b),
+            });
+        }
+    }
+
+    let winner_refs: Vec<Option<&Idea>> = winners.iter().map(Option::as_ref).collect();
+    Ok(Comparison {
+        runs,
+        config_differences: config_differences(&configs),
+        threshold,
+        overlap,
+        winner_facets: winner_facets(&winner_refs),
+    })
+}
+
+fn cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+fn num(value: Option<f32>) -> String {
+    value
+        .map(|v| format!("{:.2}", v))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+fn row(label: &str, values: impl IntoIterator<Item = String>) -> String {
+    let values: Vec<String> = values.into_iter().map(|v| cell(&v)).collect();
+    format!("| {} | {} |\n", label, values.join(" | "))
+}
+
+/// Markdown report of a comparison
+pub fn render_markdown(comparison: &Comparison) -> String {
+    let runs = &comparison.runs;
+    let header = |first: &str| {
+        let ids: Vec<&str> = runs.iter().map(|r| r.run_id.as_str()).collect();
+        format!(
+            "| {} | {} |\n|---|{}\n",
+            first,
+            ids.join(" | "),
+            "---|".repeat(ids.len())
+        )
+    };
+
+    let mut out = String::from("# Run comparison\n\n");
+    out.push_str(&header("run"));
+    out.push_str(&row("prompt", runs.iter().map(|r| r.prompt.clone())));
+    out.push_str(&row(
+        "rounds used",
+        runs.iter()
+            .map(|r| format!("{} / {}", r.rounds_used, r.max_rounds)),
+    ));
+    out.push_str(&row(
+        "stop reason",
+        runs.iter().map(|r| {
+            r.stop_reason
+                .clone()
+                .unwrap_or_else(|| "(not finished)".into())
+        }),
+    ));
+    out.push_str(&row(
+        "best score",
+        runs.iter().map(|r| match &r.best_idea_id {
+            Some(id) => format!("{} ({})", num(r.best_score), id),
+            None => num(r.best_score),
+        }),
+    ));
+    out.push_str(&row(
+        "scored ideas",
+        runs.iter().map(|r| r.scores.count.to_string()),
+    ));
+    out.push_str(&row(
+        "min / median / max",
+        runs.iter().map(|r| {
+            format!(
+                "{} / {} / {}",
+                num(r.scores.min),
+                num(r.scores.median),
+                num(r.scores.max)
+            )
+        }),
+    ));
+    out.push_str(&row("mean", runs.iter().map(|r| num(r.scores.mean))));
+
+    out.push_str("\n## Config differences\n\n");
+    if comparison.config_differences.is_empty() {
+        out.push_str("The configs are identical (apart from run_id and created_at).\n");
+    } else {
+        out.push_str(&header("key"));
+        for (key, values) in &comparison.config_differences {
+            out.push_str(&row(
+                &format!("`{}`", key),
+                values.iter().map(|v| match v {
+                    Value::String(s) => s.clone(),
+                    Value::Null => "-".to_string(),
+                    v => v.to_string(),
+                }),
+            ));
+        }
+    }
+
+    out.push_str(&format!(
+        "\n## Idea overlap (similarity ≥ {:.2})\n\n| runs | matched in A | matched in B |\n|---|---:|---:|\n",
+        comparison.threshold
+    ));
+    for o in &comparison.overlap {
+        out.push_str(&format!(
+            "| {} ↔ {} | {}/{} | {}/{} |\n",
+            o.a, o.b, o.a_matched, o.a_total, o.b_matched, o.b_total
+        ));
+    }
+
+    out.push_str("\n## Winner facets\n\n");
+    let ids: Vec<&str> = runs.iter().map(|r| r.run_id.as_str()).collect();
+    out.push_str(&format!(
+        "| facet | {} | shared words |\n|---|{}---:|\n",
+        ids.join(" | "),
+        "---|".repeat(ids.len())
+    ));
+    for name in Facets::NAMES {
+        let facet = &comparison.winner_facets[name];
+        let values: Vec<String> = facet
+            .values
+            .iter()
+            .map(|v| cell(v.as_deref().filter(|v| !v.is_empty()).unwrap_or("-")))
+            .collect();
+        let shared = facet
+            .similarity
+            .map(|s| format!("{:.0}%", s * 100.0))
+            .unwrap_or_else(|| "-".to_string());
+        out.push_str(&format!(
+            "| {} | {} | {} |\n",
+            name,
+            values.join(" | "),
+            shared
+        ));
+    }
+    out
+}
+
+/// Print a comparison of several runs
+pub fn compare_runs(
+    storage: &dyn Storage,
+    run_ids: &[String],
+    threshold: f64,
+    format: &str,
+) -> Result<()> {
+    if !["markdown", "json"].contains(&format) {
+        anyhow::bail!("Unknown format: {} (supported: markdown, json)", format);
+    }
+    let comparison = compare(storage, run_ids, threshold)?;
+    match format {
+        "json" => println!("{}", serde_json::to_string_pretty(&comparison)?),
+        _ => print!("{}", render_markdown(&comparison)),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RunConfig;
+    use crate::evolution::tests::mock_run_config;
+    use crate::storage::FileStorage;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_score_summary_and_config_differences() {
+        let ideas: Vec<Idea> = serde_json::from_value(serde_json::json!([
+            {"id": "idea-1", "origin": "generated", "title": "A", "status": "active", "overall_score": 4.0},
+            {"id": "idea-2", "origin": "generated", "title": "B", "status": "active", "overall_score": 8.0},
+            {"id": "idea-3", "origin": "generated", "title": "C", "status": "active", "overall_score": 6.0},
+            {"id": "idea-4", "origin": "generated", "title": "D", "status": "active", "overall_score": 7.0},
+            {"id": "idea-5", "origin": "generated", "title": "E", "status": "active"},
+            {"id": "idea-6", "origin": "generated", "title": "F", "status": "eliminated", "overall_score": 0.0}
+        ]))
+        .unwrap();
+        let summary = score_summary(&ideas);
+        assert_eq!(summary.count, 4);
+        assert_eq!(summary.median, Some(6.5));
+        assert_eq!(summary.mean, Some(6.25));
+        assert_eq!((summary.min, summary.max), (Some(4.0), Some(8.0)));
+
+        let configs = [
+            serde_json::json!({"run_id": "a", "population_size": 12, "scoring_weights": {"moats": 1.0, "risk": 1.0}}),
+            serde_json::json!({"run_id": "b", "population_size": 24, "scoring_weights": {"moats": 1.0, "risk": 2.0},
+                               "constraints": {"forbidden": ["ads"]}}),
+        ];
+        let diffs = config_differences(&configs);
+        assert_eq!(
+            diffs.keys().collect::<Vec<_>>(),
+            vec![
+                "constraints.forbidden",
+                "population_size",
+                "scoring_weights.risk"
+            ]
+        );
+        assert_eq!(diffs["constraints.forbidden"][0], Value::Null);
+    }
+
+    #[test]
+    fn test_compare_runs_with_different_population_sizes() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = FileStorage::new(temp_dir.path());
+        for (run_id, population_size) in [("run-a", 8), ("run-b", 12)] {
+            let config = RunConfig {
+                population_size,
+                ..mock_run_config(run_id, 2)
+            };
+            crate::evolution::evolve(config, &storage).unwrap();
+        }
+
+        let run_ids = vec!["run-a".to_string(), "run-b".to_string()];
+        let twice = vec!["run-a".to_string(), "run-a".to_string()];
+        let err = compare(&storage, &twice, 0.8).unwrap_err();
+        assert!(err.to_string().contains("more than once"), "{}", err);
+        let comparison = compare(&storage, &run_ids, 0.8).unwrap();
+        assert_eq!(
+            comparison.config_differences["population_size"],
+            vec![Value::from(8), Value::from(12)]
+        );
+        assert_eq!(comparison.overlap.len(), 1);
+        // The mock generator produces the same ideas from the same prompt
+        assert!(comparison.overlap[0].a_matched > 0);
+        assert!(comparison.runs.iter().all(|r| r.best_idea_id.is_some()));
+        assert!(comparison.winner_facets["audience"].similarity.is_some());
+
+        let md = render_markdown(&comparison);
+        assert!(md.contains("| run | run-a | run-b |"));
+        assert!(md.contains("| `population_size` | 8 | 12 |"));
+        assert!(md.contains("## Winner facets"));
+
+        assert!(compare(&storage, &run_ids[..1], 0.8).is_err());
+    }
+}
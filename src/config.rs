@@ -1,8 +1,11 @@
+use anyhow::Context;
 use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::data::{ConstraintViolation, Idea};
 
 /// Scoring weights for overall score calculation
-#[allow(dead_code)]
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ScoringWeights {
     pub feasibility: f32,
     pub speed_to_value: f32,
@@ -29,6 +32,250 @@ impl Default for ScoringWeights {
     }
 }
 
+/// Which backend executes the LLM stages of a run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LlmMode {
+    #[default]
+    Mock,
+    Command,
+    Http,
+}
+
+impl std::str::FromStr for LlmMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "mock" => Ok(LlmMode::Mock),
+            "command" => Ok(LlmMode::Command),
+            "http" => Ok(LlmMode::Http),
+            _ => anyhow::bail!("Unknown mode: {} (supported: mock, command, http)", s),
+        }
+    }
+}
+
+/// Backend settings, persisted with the run so it can be resumed with the same model
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LlmConfig {
+    pub mode: LlmMode,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+}
+
+/// Hard constraints on ideas; violators are eliminated before they are scored
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Constraints {
+    /// Terms an idea must not mention
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub forbidden: Vec<String>,
+    /// Terms an idea must mention
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub must_include: Vec<String>,
+}
+
+impl Constraints {
+    pub fn is_empty(&self) -> bool {
+        self.forbidden.is_empty() && self.must_include.is_empty()
+    }
+
+    /// Check the title, summary and facets of an idea. Terms match whole words,
+    /// case-insensitively, so "ai" does not match "maintain".
+    pub fn check(&self, idea: &Idea) -> Vec<ConstraintViolation> {
+        let f = &idea.facets;
+        let text = [
+            &idea.title,
+            &idea.summary,
+            &f.audience,
+            &f.jtbd,
+            &f.differentiator,
+            &f.monetization,
+            &f.distribution,
+            &f.risks,
+        ]
+        .map(|s| s.as_str())
+        .join(" ");
+        let words = tokenize(&text);
+
+        let forbidden = self
+            .forbidden
+            .iter()
+            .filter(|term| mentions(&words, term))
+            .map(|term| ConstraintViolation {
+                constraint: "forbidden".to_string(),
+                detail: format!("mentions '{}'", term),
+            });
+        let missing = self
+            .must_include
+            .iter()
+            .filter(|term| !mentions(&words, term))
+            .map(|term| ConstraintViolation {
+                constraint: "must_include".to_string(),
+                detail: format!("does not mention '{}'", term),
+            });
+
+        forbidden.chain(missing).collect()
+    }
+}
+
+pub(crate) fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Whether `term` (possibly several words) appears as a word sequence in `words`
+fn mentions(words: &[String], term: &str) -> bool {
+    let term = tokenize(term);
+    !term.is_empty() && words.windows(term.len()).any(|w| w == term.as_slice())
+}
+
+/// Parameters of a single evolution run, persisted to config.json.
+/// Missing fields fall back to the `run` defaults so configs written by the skill still load.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct RunConfig {
+    pub run_id: String,
+    pub prompt: String,
+    pub max_rounds: u32,
+    pub population_size: u32,
+    pub elite_count: u32,
+    pub mutation_count: u32,
+    pub crossover_count: u32,
+    pub stagnation_patience: u32,
+    pub score_threshold: f32,
+    pub scoring_weights: ScoringWeights,
+    #[serde(skip_serializing_if = "Constraints::is_empty")]
+    pub constraints: Constraints,
+    pub llm: LlmConfig,
+    pub created_at: Option<String>,
+}
+
+impl Default for RunConfig {
+    fn default() -> Self {
+        Self {
+            run_id: String::new(),
+            prompt: String::new(),
+            max_rounds: 6,
+            population_size: 12,
+            elite_count: 4,
+            mutation_count: 4,
+            crossover_count: 4,
+            stagnation_patience: 2,
+            score_threshold: 8.7,
+            scoring_weights: ScoringWeights::default(),
+            constraints: Constraints::default(),
+            llm: LlmConfig::default(),
+            created_at: None,
+        }
+    }
+}
+
+impl RunConfig {
+    /// Check that the loop parameters are internally consistent
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.prompt.trim().is_empty() {
+            anyhow::bail!("Prompt must not be empty");
+        }
+        if self.max_rounds == 0 {
+            anyhow::bail!("max_rounds must be at least 1");
+        }
+        if self.population_size < 2 {
+            anyhow::bail!("population must be at least 2");
+        }
+        if self.elite_count == 0 || self.elite_count > self.population_size {
+            anyhow::bail!(
+                "elite must be between 1 and population ({})",
+                self.population_size
+            );
+        }
+        if self.mutation_count > self.population_size {
+            anyhow::bail!(
+                "mutations must be at most population ({})",
+                self.population_size
+            );
+        }
+        if self.crossover_count > self.population_size {
+            anyhow::bail!(
+                "crossovers must be at most population ({})",
+                self.population_size
+            );
+        }
+        // 0 would stop every run after its first round
+        if self.stagnation_patience == 0 {
+            anyhow::bail!("stagnation must be at least 1");
+        }
+        if !(0.0..=10.0).contains(&self.score_threshold) {
+            anyhow::bail!("threshold must be between 0 and 10");
+        }
+        match self.llm.mode {
+            LlmMode::Command if self.llm.command.is_none() => {
+                anyhow::bail!("--mode command requires --llm-command")
+            }
+            LlmMode::Http if self.llm.url.is_none() => {
+                anyhow::bail!("--mode http requires --llm-url")
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/// Environment variable that overrides the runs directory
+pub const RUNS_DIR_ENV: &str = "EVOIDEA_RUNS_DIR";
+
+/// Per-project settings file, looked up from the current directory upwards
+pub const PROJECT_CONFIG: &str = ".evoidea.json";
+
+/// Settings read from `.evoidea.json`
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ProjectConfig {
+    runs_dir: Option<PathBuf>,
+}
+
+/// Resolve the runs directory: `--runs-dir`, then `EVOIDEA_RUNS_DIR`, then the
+/// nearest `.evoidea.json`, then `./runs`
+pub fn resolve_runs_dir(flag: Option<&str>) -> anyhow::Result<PathBuf> {
+    let env = std::env::var(RUNS_DIR_ENV).ok();
+    let cwd = std::env::current_dir().context("Cannot determine current directory")?;
+    resolve_runs_dir_from(flag, env.as_deref(), &cwd)
+}
+
+fn resolve_runs_dir_from(
+    flag: Option<&str>,
+    env: Option<&str>,
+    cwd: &Path,
+) -> anyhow::Result<PathBuf> {
+    if let Some(dir) = flag.or(env).filter(|d| !d.is_empty()) {
+        return Ok(cwd.join(dir));
+    }
+
+    for dir in cwd.ancestors() {
+        let path = dir.join(PROJECT_CONFIG);
+        if !path.is_file() {
+            continue;
+        }
+        let raw = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read: {:?}", path))?;
+        let project: ProjectConfig =
+            serde_json::from_str(&raw).with_context(|| format!("Invalid {:?}", path))?;
+        // Relative paths in .evoidea.json are relative to the file, not the cwd
+        if let Some(runs_dir) = project.runs_dir {
+            return Ok(dir.join(runs_dir));
+        }
+        break;
+    }
+
+    Ok(cwd.join("runs"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -45,4 +292,145 @@ mod tests {
         assert_eq!(weights.risk, 1.0);
         assert_eq!(weights.clarity, 1.0);
     }
+
+    fn sample_config() -> RunConfig {
+        RunConfig {
+            run_id: "run-1".to_string(),
+            prompt: "Developer tools".to_string(),
+            max_rounds: 3,
+            population_size: 6,
+            elite_count: 2,
+            mutation_count: 2,
+            crossover_count: 2,
+            stagnation_patience: 2,
+            score_threshold: 9.0,
+            scoring_weights: ScoringWeights::default(),
+            constraints: Constraints::default(),
+            llm: LlmConfig::default(),
+            created_at: None,
+        }
+    }
+
+    #[test]
+    fn test_run_config_validate_accepts_defaults() {
+        assert!(sample_config().validate().is_ok());
+    }
+
+    #[test]
+    fn test_run_config_validate_rejects_elite_above_population() {
+        let mut config = sample_config();
+        config.elite_count = 7;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_run_config_validate_rejects_zero_stagnation() {
+        let mut config = sample_config();
+        config.stagnation_patience = 0;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_run_config_validate_bounds_offspring_by_population() {
+        let mut config = sample_config();
+        config.mutation_count = 7;
+        assert!(config.validate().is_err());
+
+        let mut config = sample_config();
+        config.crossover_count = 7;
+        assert!(config.validate().is_err());
+
+        let mut config = sample_config();
+        config.mutation_count = 6;
+        config.crossover_count = 6;
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_run_config_validate_requires_backend_settings() {
+        let mut config = sample_config();
+        config.llm.mode = LlmMode::Http;
+        assert!(config.validate().is_err());
+
+        config.llm.url = Some("http://localhost:8080/v1".to_string());
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_run_config_loads_skill_written_config() {
+        let config: RunConfig = serde_json::from_value(serde_json::json!({
+            "prompt": "Developer tools",
+            "discovery": { "budget": "low" }
+        }))
+        .unwrap();
+        assert_eq!(config.prompt, "Developer tools");
+        assert_eq!(config.max_rounds, RunConfig::default().max_rounds);
+    }
+
+    #[test]
+    fn test_constraints_match_whole_words() {
+        let idea: Idea = serde_json::from_value(serde_json::json!({
+            "id": "idea-001",
+            "origin": "generated",
+            "title": "Maintainer dashboard",
+            "summary": "Uses an LLM to triage issues for API teams",
+            "status": "active"
+        }))
+        .unwrap();
+
+        let constraints = Constraints {
+            forbidden: vec!["ai".to_string(), "llm".to_string()],
+            must_include: vec!["api".to_string(), "open source".to_string()],
+        };
+        let violations = constraints.check(&idea);
+        let details: Vec<String> = violations.iter().map(|v| v.to_string()).collect();
+        assert_eq!(
+            details,
+            vec![
+                "forbidden: mentions 'llm'",
+                "must_include: does not mention 'open source'"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_resolve_runs_dir_precedence() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let project = temp_dir.path();
+        let nested = project.join("src/deep");
+        std::fs::create_dir_all(&nested).unwrap();
+
+        assert_eq!(
+            resolve_runs_dir_from(None, None, &nested).unwrap(),
+            nested.join("runs")
+        );
+
+        std::fs::write(
+            project.join(PROJECT_CONFIG),
+            r#"{"runs_dir": "shared/runs"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            resolve_runs_dir_from(None, None, &nested).unwrap(),
+            project.join("shared/runs")
+        );
+        assert_eq!(
+            resolve_runs_dir_from(None, Some("/mnt/runs"), &nested).unwrap(),
+            PathBuf::from("/mnt/runs")
+        );
+        assert_eq!(
+            resolve_runs_dir_from(Some("local"), Some("/mnt/runs"), &nested).unwrap(),
+            nested.join("local")
+        );
+
+        std::fs::write(project.join(PROJECT_CONFIG), r#"{"runs": "x"}"#).unwrap();
+        let err = resolve_runs_dir_from(None, None, &nested).unwrap_err();
+        assert!(format!("{:#}", err).contains(PROJECT_CONFIG), "{:#}", err);
+    }
+
+    #[test]
+    fn test_llm_mode_parse() {
+        assert_eq!("command".parse::<LlmMode>().unwrap(), LlmMode::Command);
+        assert!("codex".parse::<LlmMode>().is_err());
+    }
 }
@@ -1,7 +1,8 @@
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
 
-#[allow(dead_code)]
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "snake_case")]
 pub enum Origin {
     Generated,
@@ -10,16 +11,89 @@ pub enum Origin {
     Refined,
 }
 
-#[allow(dead_code)]
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-#[serde(rename_all = "snake_case")]
+impl Origin {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Origin::Generated => "generated",
+            Origin::Crossover => "crossover",
+            Origin::Mutated => "mutated",
+            Origin::Refined => "refined",
+        }
+    }
+}
+
+/// Lifecycle of an idea. Serialized as its tag (`"eliminated"`); the violations of an
+/// eliminated idea are stored next to it in the idea's `violations` field.
+#[derive(Debug, Clone, PartialEq)]
 pub enum IdeaStatus {
     Active,
     Archived,
+    Eliminated {
+        violations: Vec<ConstraintViolation>,
+    },
 }
 
-#[allow(dead_code)]
+impl IdeaStatus {
+    const TAGS: &'static [&'static str] = &["active", "archived", "eliminated"];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IdeaStatus::Active => "active",
+            IdeaStatus::Archived => "archived",
+            IdeaStatus::Eliminated { .. } => "eliminated",
+        }
+    }
+
+    pub fn is_eliminated(&self) -> bool {
+        matches!(self, IdeaStatus::Eliminated { .. })
+    }
+}
+
+impl Serialize for IdeaStatus {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for IdeaStatus {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let tag = String::deserialize(deserializer)?;
+        match tag.as_str() {
+            "active" => Ok(IdeaStatus::Active),
+            "archived" => Ok(IdeaStatus::Archived),
+            "eliminated" => Ok(IdeaStatus::Eliminated {
+                violations: Vec::new(),
+            }),
+            other => Err(serde::de::Error::unknown_variant(other, Self::TAGS)),
+        }
+    }
+}
+
+/// A breached constraint that got an idea eliminated
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ConstraintViolation {
+    /// Constraint name from config.json, e.g. "forbidden" or "must_include"
+    pub constraint: String,
+    pub detail: String,
+}
+
+impl std::fmt::Display for ConstraintViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.constraint, self.detail)
+    }
+}
+
+/// Render violations as "constraint: detail; constraint: detail"
+pub fn join_violations(violations: &[ConstraintViolation]) -> String {
+    violations
+        .iter()
+        .map(|v| v.to_string())
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
 pub struct Facets {
     pub audience: String,
     pub jtbd: String,
@@ -29,7 +103,31 @@ pub struct Facets {
     pub risks: String,
 }
 
-#[allow(dead_code)]
+impl Facets {
+    /// Facet names in canonical order
+    pub const NAMES: [&'static str; 6] = [
+        "audience",
+        "jtbd",
+        "differentiator",
+        "monetization",
+        "distribution",
+        "risks",
+    ];
+
+    /// Facet names in canonical order, paired with their text
+    pub fn fields(&self) -> [(&'static str, &str); 6] {
+        let values = [
+            &self.audience,
+            &self.jtbd,
+            &self.differentiator,
+            &self.monetization,
+            &self.distribution,
+            &self.risks,
+        ];
+        std::array::from_fn(|i| (Self::NAMES[i], values[i].as_str()))
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Scores {
     pub feasibility: f32,
@@ -57,6 +155,503 @@ impl Default for Scores {
     }
 }
 
+impl Scores {
+    /// Criterion names in canonical order
+    pub const NAMES: [&'static str; 8] = [
+        "feasibility",
+        "speed_to_value",
+        "differentiation",
+        "market_size",
+        "distribution",
+        "moats",
+        "risk",
+        "clarity",
+    ];
+
+    /// Criterion names in canonical order, paired with their values
+    pub fn criteria(&self) -> [(&'static str, f32); 8] {
+        let values = [
+            self.feasibility,
+            self.speed_to_value,
+            self.differentiation,
+            self.market_size,
+            self.distribution,
+            self.moats,
+            self.risk,
+            self.clarity,
+        ];
+        std::array::from_fn(|i| (Self::NAMES[i], values[i]))
+    }
+}
+
+/// A single idea in the population, as stored in state.json
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(try_from = "IdeaRecord", into = "IdeaRecord")]
+pub struct Idea {
+    pub id: String,
+    pub gen: u32,
+    pub origin: Origin,
+    pub parents: Vec<String>,
+    pub title: String,
+    pub summary: String,
+    pub facets: Facets,
+    pub scores: Option<Scores>,
+    pub overall_score: Option<f32>,
+    pub judge_notes: Option<String>,
+    pub status: IdeaStatus,
+}
+
+/// On-disk layout of an idea: status tag plus a sibling `violations` list
+#[derive(Serialize, Deserialize)]
+struct IdeaRecord {
+    id: String,
+    #[serde(default)]
+    gen: u32,
+    origin: Origin,
+    #[serde(default)]
+    parents: Vec<String>,
+    title: String,
+    #[serde(default)]
+    summary: String,
+    #[serde(default)]
+    facets: Facets,
+    #[serde(default)]
+    scores: Option<Scores>,
+    #[serde(default)]
+    overall_score: Option<f32>,
+    #[serde(default)]
+    judge_notes: Option<String>,
+    status: IdeaStatus,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    violations: Vec<ConstraintViolation>,
+}
+
+impl TryFrom<IdeaRecord> for Idea {
+    type Error = String;
+
+    fn try_from(record: IdeaRecord) -> Result<Self, Self::Error> {
+        let status = match record.status {
+            IdeaStatus::Eliminated { .. } => IdeaStatus::Eliminated {
+                violations: record.violations,
+            },
+            status if record.violations.is_empty() => status,
+            status => {
+                return Err(format!(
+                    "idea {} is {} but records constraint violations",
+                    record.id,
+                    status.as_str()
+                ))
+            }
+        };
+
+        Ok(Idea {
+            id: record.id,
+            gen: record.gen,
+            origin: record.origin,
+            parents: record.parents,
+            title: record.title,
+            summary: record.summary,
+            facets: record.facets,
+            scores: record.scores,
+            overall_score: record.overall_score,
+            judge_notes: record.judge_notes,
+            status,
+        })
+    }
+}
+
+impl From<Idea> for IdeaRecord {
+    fn from(idea: Idea) -> Self {
+        let (status, violations) = match idea.status {
+            IdeaStatus::Eliminated { violations } => (
+                IdeaStatus::Eliminated {
+                    violations: Vec::new(),
+                },
+                violations,
+            ),
+            status => (status, Vec::new()),
+        };
+
+        IdeaRecord {
+            id: idea.id,
+            gen: idea.gen,
+            origin: idea.origin,
+            parents: idea.parents,
+            title: idea.title,
+            summary: idea.summary,
+            facets: idea.facets,
+            scores: idea.scores,
+            overall_score: idea.overall_score,
+            judge_notes: idea.judge_notes,
+            status,
+            violations,
+        }
+    }
+}
+
+/// Population snapshot persisted to state.json after every phase
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RunState {
+    pub run_id: String,
+    pub iteration: u32,
+    pub ideas: Vec<Idea>,
+    #[serde(default)]
+    pub best_idea_id: Option<String>,
+    #[serde(default)]
+    pub best_score: Option<f32>,
+    #[serde(default)]
+    pub stagnation_counter: u32,
+}
+
+impl RunState {
+    pub fn new(run_id: &str) -> Self {
+        Self {
+            run_id: run_id.to_string(),
+            iteration: 0,
+            ideas: Vec::new(),
+            best_idea_id: None,
+            best_score: None,
+            stagnation_counter: 0,
+        }
+    }
+
+    pub fn idea(&self, id: &str) -> Option<&Idea> {
+        self.ideas.iter().find(|idea| idea.id == id)
+    }
+
+    pub fn active_ideas(&self) -> impl Iterator<Item = &Idea> {
+        self.ideas
+            .iter()
+            .filter(|idea| idea.status == IdeaStatus::Active)
+    }
+
+    pub fn eliminated_ideas(&self) -> impl Iterator<Item = &Idea> {
+        self.ideas.iter().filter(|idea| idea.status.is_eliminated())
+    }
+
+    /// Highest-scoring idea other than the best one, ignoring eliminated ideas
+    pub fn runner_up(&self) -> Option<&Idea> {
+        self.ideas
+            .iter()
+            .filter(|idea| {
+                Some(&idea.id) != self.best_idea_id.as_ref()
+                    && idea.overall_score.is_some()
+                    && !idea.status.is_eliminated()
+            })
+            .max_by(|a, b| {
+                a.overall_score
+                    .partial_cmp(&b.overall_score)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+    }
+}
+
+/// Winner summary written to final.json when a run stops
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FinalResult {
+    pub run_id: String,
+    pub best_idea: Idea,
+    #[serde(default)]
+    pub runner_up: Option<Idea>,
+    #[serde(default)]
+    pub iterations_completed: u32,
+    #[serde(default)]
+    pub stop_reason: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub why_won: Vec<String>,
+}
+
+impl FinalResult {
+    /// Parse final.json, accepting both the `best_idea` layout and the legacy
+    /// `best`/`runners_up` layout of `schemas/final.output.schema.json`
+    pub fn from_value(value: serde_json::Value) -> anyhow::Result<Self> {
+        if value.get("best_idea").is_some() {
+            from_value(value)
+        } else if value.get("best").is_some() {
+            Ok(from_value::<LegacyFinal>(value)?.into())
+        } else {
+            anyhow::bail!("missing both 'best_idea' and legacy 'best'")
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct LegacyFinal {
+    run_id: String,
+    best: LegacyBest,
+    #[serde(default)]
+    runners_up: Vec<LegacyRunnerUp>,
+}
+
+#[derive(Deserialize)]
+struct LegacyBest {
+    idea_id: String,
+    title: String,
+    #[serde(default)]
+    summary: String,
+    #[serde(default)]
+    facets: Facets,
+    #[serde(default)]
+    scores: Option<Scores>,
+    #[serde(default)]
+    overall_score: Option<f32>,
+    #[serde(default)]
+    why_won: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct LegacyRunnerUp {
+    idea_id: String,
+    title: String,
+    #[serde(default)]
+    overall_score: Option<f32>,
+}
+
+/// Legacy files carry no lineage, so converted ideas are reported as generated and active
+impl From<LegacyFinal> for FinalResult {
+    fn from(legacy: LegacyFinal) -> Self {
+        let idea = |id: String, title: String| Idea {
+            id,
+            gen: 0,
+            origin: Origin::Generated,
+            parents: Vec::new(),
+            title,
+            summary: String::new(),
+            facets: Facets::default(),
+            scores: None,
+            overall_score: None,
+            judge_notes: None,
+            status: IdeaStatus::Active,
+        };
+
+        let best = legacy.best;
+        let best_idea = Idea {
+            summary: best.summary,
+            facets: best.facets,
+            scores: best.scores,
+            overall_score: best.overall_score,
+            ..idea(best.idea_id, best.title)
+        };
+        let runner_up = legacy.runners_up.into_iter().next().map(|r| Idea {
+            overall_score: r.overall_score,
+            ..idea(r.idea_id, r.title)
+        });
+
+        Self {
+            run_id: legacy.run_id,
+            best_idea,
+            runner_up,
+            iterations_completed: 0,
+            stop_reason: String::new(),
+            why_won: best.why_won,
+        }
+    }
+}
+
+/// How a pairwise comparison was decided
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    /// One idea won; `winner` names it
+    #[default]
+    Decided,
+    /// A draw between the two ideas
+    Tie,
+    /// Neither idea was acceptable
+    Neither,
+}
+
+impl Outcome {
+    pub fn is_decided(&self) -> bool {
+        *self == Outcome::Decided
+    }
+}
+
+/// One pairwise tournament decision
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Comparison {
+    /// Idea presented as option A (shown first)
+    pub idea_a: String,
+    /// Idea presented as option B
+    pub idea_b: String,
+    /// Preferred idea of a decided comparison, empty for a tie or "neither"
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub winner: String,
+    #[serde(default, skip_serializing_if = "Outcome::is_decided")]
+    pub outcome: Outcome,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rationale: Option<String>,
+    /// Judged without scores or Elo on screen, with A and B assigned at
+    /// random, so a preference for A over B measures position bias
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub blind: bool,
+}
+
+impl Comparison {
+    fn new(idea_a: &str, idea_b: &str, winner: &str, outcome: Outcome) -> Self {
+        Self {
+            idea_a: idea_a.to_string(),
+            idea_b: idea_b.to_string(),
+            winner: winner.to_string(),
+            outcome,
+            rationale: None,
+            blind: false,
+        }
+    }
+
+    /// `winner` (one of the two ideas) preferred over the other
+    pub fn decided(idea_a: &str, idea_b: &str, winner: &str) -> Self {
+        Self::new(idea_a, idea_b, winner, Outcome::Decided)
+    }
+
+    pub fn tie(idea_a: &str, idea_b: &str) -> Self {
+        Self::new(idea_a, idea_b, "", Outcome::Tie)
+    }
+
+    pub fn neither(idea_a: &str, idea_b: &str) -> Self {
+        Self::new(idea_a, idea_b, "", Outcome::Neither)
+    }
+
+    pub fn is_neither(&self) -> bool {
+        self.outcome == Outcome::Neither
+    }
+
+    /// What A got out of the comparison: 1.0 for a win, 0.5 for a tie and
+    /// 0.0 for a loss; `None` for "neither" or a winner outside the pair
+    pub fn score_a(&self) -> Option<f64> {
+        match self.outcome {
+            Outcome::Tie => Some(0.5),
+            Outcome::Neither => None,
+            Outcome::Decided if self.winner == self.idea_a => Some(1.0),
+            Outcome::Decided if self.winner == self.idea_b => Some(0.0),
+            Outcome::Decided => None,
+        }
+    }
+
+    /// Human-readable result, e.g. "idea-2 won" or "tie"
+    pub fn describe(&self) -> String {
+        match self.outcome {
+            Outcome::Decided => format!("{} won", self.winner),
+            Outcome::Tie => "tie".to_string(),
+            Outcome::Neither => "neither".to_string(),
+        }
+    }
+
+    /// Reject a comparison whose `winner` does not fit its outcome, such as
+    /// a decided winner outside the pair
+    pub fn check(&self) -> anyhow::Result<()> {
+        let consistent = match self.outcome {
+            Outcome::Decided => self.winner == self.idea_a || self.winner == self.idea_b,
+            Outcome::Tie | Outcome::Neither => self.winner.is_empty(),
+        };
+        if !consistent {
+            anyhow::bail!(
+                "comparison {} vs {} has winner {:?} with outcome {:?}",
+                self.idea_a,
+                self.idea_b,
+                self.winner,
+                self.outcome
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Tournament results persisted to preferences.json
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Preferences {
+    #[serde(default)]
+    pub comparisons: Vec<Comparison>,
+    #[serde(default)]
+    pub elo_ratings: BTreeMap<String, f64>,
+}
+
+impl Preferences {
+    /// Ideas flagged as unacceptable by a "neither" answer
+    pub fn rejected(&self) -> BTreeSet<&str> {
+        self.comparisons
+            .iter()
+            .filter(|c| c.is_neither())
+            .flat_map(|c| [c.idea_a.as_str(), c.idea_b.as_str()])
+            .collect()
+    }
+}
+
+/// Kind of a history.ndjson event, one per phase of the loop
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventType {
+    Generated,
+    Scored,
+    Selected,
+    Crossover,
+    Mutated,
+    Refined,
+    Stopped,
+}
+
+impl EventType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventType::Generated => "generated",
+            EventType::Scored => "scored",
+            EventType::Selected => "selected",
+            EventType::Crossover => "crossover",
+            EventType::Mutated => "mutated",
+            EventType::Refined => "refined",
+            EventType::Stopped => "stopped",
+        }
+    }
+
+    /// Whether events of this type add new ideas (listed in `payload.ideas`)
+    pub fn creates_ideas(&self) -> bool {
+        matches!(
+            self,
+            EventType::Generated | EventType::Crossover | EventType::Mutated | EventType::Refined
+        )
+    }
+}
+
+/// One line of history.ndjson
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HistoryEvent {
+    pub ts: String,
+    pub iteration: u32,
+    #[serde(rename = "type")]
+    pub event_type: EventType,
+    pub payload: serde_json::Value,
+}
+
+impl HistoryEvent {
+    pub fn new(iteration: u32, event_type: EventType, payload: serde_json::Value) -> Self {
+        Self {
+            ts: chrono::Utc::now().to_rfc3339(),
+            iteration,
+            event_type,
+            payload,
+        }
+    }
+
+    /// Ids of the ideas this event created, if it is a creation event
+    pub fn created_ids(&self) -> Vec<&str> {
+        if !self.event_type.creates_ideas() {
+            return Vec::new();
+        }
+        let ideas = self.payload.get("ideas").and_then(|i| i.as_array());
+        ideas
+            .into_iter()
+            .flatten()
+            .filter_map(|idea| idea.get("id").and_then(|id| id.as_str()))
+            .collect()
+    }
+}
+
+/// Deserialize a JSON value, reporting the path of the offending field on error
+/// (e.g. `ideas[3].status: unknown variant ...`)
+pub fn from_value<T: DeserializeOwned>(value: serde_json::Value) -> anyhow::Result<T> {
+    serde_path_to_error::deserialize(value).map_err(|e| anyhow::anyhow!("{}", e))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -87,4 +682,150 @@ mod tests {
         assert_eq!(scores.feasibility, 0.0);
         assert_eq!(scores.clarity, 0.0);
     }
+
+    #[test]
+    fn test_run_state_round_trip() {
+        let mut state = RunState::new("run-1");
+        state.iteration = 2;
+        state.ideas.push(Idea {
+            id: "idea-001".to_string(),
+            gen: 1,
+            origin: Origin::Generated,
+            parents: vec![],
+            title: "Test".to_string(),
+            summary: "Summary".to_string(),
+            facets: Facets::default(),
+            scores: Some(Scores::default()),
+            overall_score: Some(5.0),
+            judge_notes: None,
+            status: IdeaStatus::Active,
+        });
+
+        let json = serde_json::to_string(&state).unwrap();
+        let parsed: RunState = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, state);
+        assert_eq!(parsed.active_ideas().count(), 1);
+    }
+
+    #[test]
+    fn test_final_result_accepts_legacy_best_layout() {
+        let legacy = serde_json::json!({
+            "run_id": "run-1",
+            "best": {
+                "idea_id": "idea-7",
+                "title": "Winner",
+                "summary": "S",
+                "facets": {},
+                "overall_score": 8.1,
+                "why_won": ["fast", "cheap"]
+            },
+            "runners_up": [{ "idea_id": "idea-2", "title": "Second", "overall_score": 7.0 }]
+        });
+
+        let result = FinalResult::from_value(legacy).unwrap();
+        assert_eq!(result.best_idea.id, "idea-7");
+        assert_eq!(result.best_idea.overall_score, Some(8.1));
+        assert_eq!(result.runner_up.unwrap().title, "Second");
+        assert_eq!(result.why_won.len(), 2);
+    }
+
+    #[test]
+    fn test_final_result_errors_name_the_field() {
+        let err = FinalResult::from_value(serde_json::json!({
+            "run_id": "run-1",
+            "best_idea": { "id": "idea-1", "origin": "generated", "title": 3, "status": "active" }
+        }))
+        .unwrap_err();
+        assert!(err.to_string().starts_with("best_idea.title"), "{}", err);
+
+        let err = FinalResult::from_value(serde_json::json!({ "run_id": "run-1" })).unwrap_err();
+        assert!(err.to_string().contains("best_idea"));
+    }
+
+    #[test]
+    fn test_preferences_round_trip_omits_empty_rationale() {
+        let mut preferences = Preferences::default();
+        preferences
+            .comparisons
+            .push(Comparison::decided("a", "b", "a"));
+        preferences.elo_ratings.insert("a".to_string(), 1016.0);
+
+        let json = serde_json::to_value(&preferences).unwrap();
+        assert!(json["comparisons"][0].get("rationale").is_none());
+        assert!(json["comparisons"][0].get("blind").is_none());
+        assert!(json["comparisons"][0].get("outcome").is_none());
+        let parsed: Preferences = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed, preferences);
+    }
+
+    #[test]
+    fn test_comparison_outcome_is_independent_of_idea_ids() {
+        // Ideas named like the outcomes must not be mistaken for them
+        let tie = Comparison::tie("tie", "neither");
+        let json = serde_json::to_value(&tie).unwrap();
+        assert_eq!(json["outcome"], "tie");
+        assert!(json.get("winner").is_none());
+        assert_eq!(tie.score_a(), Some(0.5));
+
+        let won = Comparison::decided("tie", "neither", "neither");
+        assert!(!won.is_neither());
+        assert_eq!(won.score_a(), Some(0.0));
+        assert!(won.check().is_ok());
+
+        let parsed: Comparison = serde_json::from_value(serde_json::json!({
+            "idea_a": "a", "idea_b": "b", "winner": "tie"
+        }))
+        .unwrap();
+        assert_eq!(parsed.score_a(), None);
+        assert!(parsed.check().is_err());
+        assert!(Comparison {
+            winner: "a".to_string(),
+            ..Comparison::neither("a", "b")
+        }
+        .check()
+        .is_err());
+    }
+
+    #[test]
+    fn test_eliminated_idea_keeps_violations_next_to_status() {
+        let violation = ConstraintViolation {
+            constraint: "forbidden".to_string(),
+            detail: "mentions 'crypto'".to_string(),
+        };
+        let idea = Idea {
+            id: "idea-001".to_string(),
+            gen: 1,
+            origin: Origin::Generated,
+            parents: vec![],
+            title: "Crypto wallet".to_string(),
+            summary: String::new(),
+            facets: Facets::default(),
+            scores: None,
+            overall_score: Some(0.0),
+            judge_notes: None,
+            status: IdeaStatus::Eliminated {
+                violations: vec![violation.clone()],
+            },
+        };
+
+        let json = serde_json::to_value(&idea).unwrap();
+        assert_eq!(json["status"], "eliminated");
+        assert_eq!(json["violations"][0]["constraint"], "forbidden");
+
+        let parsed: Idea = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed, idea);
+    }
+
+    #[test]
+    fn test_violations_on_active_idea_are_rejected() {
+        let err = serde_json::from_value::<Idea>(serde_json::json!({
+            "id": "idea-001",
+            "origin": "generated",
+            "title": "T",
+            "status": "active",
+            "violations": [{ "constraint": "forbidden", "detail": "x" }]
+        }))
+        .unwrap_err();
+        assert!(err.to_string().contains("records constraint violations"));
+    }
 }
@@ -0,0 +1,321 @@
+use anyhow::Result;
+use std::io::IsTerminal;
+
+use crate::data::{Idea, RunState};
+use crate::storage::Storage;
+
+/// Role of a word in a word-level diff
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Change {
+    Same,
+    Removed,
+    Added,
+}
+
+/// Word-level diff of two texts (longest common subsequence over
+/// whitespace-separated words)
+pub fn word_diff<'a>(before: &'a str, after: &'a str) -> Vec<(Change, &'a str)> {
+    let old: Vec<&str> = before.split_whitespace().collect();
+    let new: Vec<&str> = after.split_whitespace().collect();
+
+    // lcs[i][j] = length of the LCS of old[i..] and new[j..]; on ties removals
+    // are emitted before additions
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut words = Vec::new();
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            words.push((Change::Same, old[i]));
+            i += 1;
+            j += 1;
+        } else if j < new.len() && (i == old.len() || lcs[i][j + 1] > lcs[i + 1][j]) {
+            words.push((Change::Added, new[j]));
+            j += 1;
+        } else {
+            words.push((Change::Removed, old[i]));
+            i += 1;
+        }
+    }
+    words
+}
+
+/// How removed and added runs of words are marked
+struct Style {
+    removed: (&'static str, &'static str),
+    added: (&'static str, &'static str),
+}
+
+const PLAIN: Style = Style {
+    removed: ("[-", "-]"),
+    added: ("{+", "+}"),
+};
+
+const COLOR: Style = Style {
+    removed: ("\x1b[31;9m", "\x1b[0m"),
+    added: ("\x1b[32m", "\x1b[0m"),
+};
+
+const MARKDOWN: Style = Style {
+    removed: ("~~", "~~"),
+    added: ("**", "**"),
+};
+
+/// Join a word diff back into one line, wrapping each run of removed or
+/// added words in the style's markers
+fn render_words(words: &[(Change, &str)], style: &Style) -> String {
+    let mut parts: Vec<String> = Vec::new();
+    let mut i = 0;
+    while i < words.len() {
+        let change = words[i].0;
+        let run: Vec<&str> = words[i..]
+            .iter()
+            .take_while(|(c, _)| *c == change)
+            .map(|(_, word)| *word)
+            .collect();
+        i += run.len();
+        let text = run.join(" ");
+        parts.push(match change {
+            Change::Same => text,
+            Change::Removed => format!("{}{}{}", style.removed.0, text, style.removed.1),
+            Change::Added => format!("{}{}{}", style.added.0, text, style.added.1),
+        });
+    }
+    parts.join(" ")
+}
+
+/// Title, summary and facets of an idea as (field, text)
+fn fields(idea: &Idea) -> Vec<(String, &str)> {
+    let mut fields = vec![
+        ("title".to_string(), idea.title.as_str()),
+        ("summary".to_string(), idea.summary.as_str()),
+    ];
+    for (name, text) in idea.facets.fields() {
+        fields.push((format!("facets.{}", name), text));
+    }
+    fields
+}
+
+/// Per-criterion scores of both ideas plus the overall score, as
+/// (name, before, after)
+fn score_changes(before: &Idea, after: &Idea) -> Vec<(&'static str, Option<f32>, Option<f32>)> {
+    let criteria = |idea: &Idea| idea.scores.as_ref().map(|s| s.criteria());
+    let (old, new) = (criteria(before), criteria(after));
+    let mut rows: Vec<(&'static str, Option<f32>, Option<f32>)> = crate::data::Scores::NAMES
+        .iter()
+        .enumerate()
+        .map(|(i, name)| (*name, old.map(|c| c[i].1), new.map(|c| c[i].1)))
+        .collect();
+    rows.push(("overall", before.overall_score, after.overall_score));
+    rows
+}
+
+fn num(value: Option<f32>) -> String {
+    value
+        .map(|v| format!("{:.2}", v))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+fn delta(before: Option<f32>, after: Option<f32>) -> String {
+    match (before, after) {
+        (Some(before), Some(after)) => format!("{:+.2}", after - before),
+        _ => String::new(),
+    }
+}
+
+fn heading(idea: &Idea) -> String {
+    format!("{} \"{}\" ({})", idea.id, idea.title, idea.origin.as_str())
+}
+
+/// Text diff of `idea` against `base`; `color` uses ANSI escapes instead of
+/// `[-removed-]{+added+}` markers
+pub fn render_text(base: &Idea, idea: &Idea, color: bool) -> String {
+    let style = if color { &COLOR } else { &PLAIN };
+    let mut out = format!("{}\n  vs {}\n\n", heading(idea), heading(base));
+
+    let mut unchanged = Vec::new();
+    for ((field, old), (_, new)) in fields(base).into_iter().zip(fields(idea)) {
+        let words = word_diff(old, new);
+        if words.iter().all(|(c, _)| *c == Change::Same) {
+            unchanged.push(field);
+        } else {
+            out.push_str(&format!("{}: {}\n", field, render_words(&words, style)));
+        }
+    }
+    if !unchanged.is_empty() {
+        out.push_str(&format!("unchanged: {}\n", unchanged.join(", ")));
+    }
+
+    out.push_str("\nScores:\n");
+    for (name, old, new) in score_changes(base, idea) {
+        out.push_str(&format!(
+            "  {:<16} {:>5} → {:<5} {}\n",
+            name,
+            num(old),
+            num(new),
+            delta(old, new)
+        ));
+    }
+    out
+}
+
+/// Markdown diff for reviews: struck-through removals, bold additions and a
+/// score table
+pub fn render_markdown(base: &Idea, idea: &Idea) -> String {
+    let mut out = format!("### {} vs {}\n\n", heading(idea), heading(base));
+
+    let mut unchanged = Vec::new();
+    for ((field, old), (_, new)) in fields(base).into_iter().zip(fields(idea)) {
+        let words = word_diff(old, new);
+        if words.iter().all(|(c, _)| *c == Change::Same) {
+            unchanged.push(format!("`{}`", field));
+        } else {
+            out.push_str(&format!(
+                "- **{}**: {}\n",
+                field,
+                render_words(&words, &MARKDOWN)
+            ));
+        }
+    }
+    if !unchanged.is_empty() {
+        out.push_str(&format!("- unchanged: {}\n", unchanged.join(", ")));
+    }
+
+    out.push_str("\n| Criterion | Before | After | Change |\n|---|---:|---:|---:|\n");
+    for (name, old, new) in score_changes(base, idea) {
+        out.push_str(&format!(
+            "| {} | {} | {} | {} |\n",
+            name,
+            num(old),
+            num(new),
+            delta(old, new)
+        ));
+    }
+    out
+}
+
+fn find_idea<'a>(state: &'a RunState, run_id: &str, id: &str) -> Result<&'a Idea> {
+    state
+        .idea(id)
+        .ok_or_else(|| anyhow::anyhow!("Idea {} not found in run {}", id, run_id))
+}
+
+/// Print the diff of an idea against `against`, or against each of its parents
+pub fn diff_ideas(
+    storage: &dyn Storage,
+    run_id: &str,
+    idea_id: &str,
+    against: Option<&str>,
+    format: &str,
+) -> Result<()> {
+    if !["text", "markdown"].contains(&format) {
+        anyhow::bail!("Unknown format: {} (supported: text, markdown)", format);
+    }
+    if !storage.has_state(run_id)? {
+        anyhow::bail!("Run {} not found", run_id);
+    }
+    let state = storage.load_state(run_id)?;
+    let idea = find_idea(&state, run_id, idea_id)?;
+
+    let bases: Vec<&Idea> = match against {
+        Some(id) => vec![find_idea(&state, run_id, id)?],
+        None if idea.parents.is_empty() => anyhow::bail!(
+            "Idea {} has no parents ({}); pass --against to compare with another idea",
+            idea_id,
+            idea.origin.as_str()
+        ),
+        None => idea
+            .parents
+            .iter()
+            .map(|id| find_idea(&state, run_id, id))
+            .collect::<Result<_>>()?,
+    };
+
+    let color = std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none();
+    let diffs: Vec<String> = bases
+        .iter()
+        .map(|base| match format {
+            "markdown" => render_markdown(base, idea),
+            _ => render_text(base, idea, color),
+        })
+        .collect();
+    print!("{}", diffs.join("\n"));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pair() -> (Idea, Idea) {
+        let base: Idea = serde_json::from_value(serde_json::json!({
+            "id": "idea-1", "origin": "generated", "title": "Issue tracker",
+            "summary": "Track issues in a spreadsheet", "status": "archived",
+            "facets": {"audience": "Solo developers", "risks": "Crowded market"},
+            "scores": {"feasibility": 7.0, "speed_to_value": 6.0, "differentiation": 5.0, "market_size": 5.0,
+                       "distribution": 4.0, "moats": 3.0, "risk": 6.0, "clarity": 7.0},
+            "overall_score": 5.5
+        }))
+        .unwrap();
+        let child: Idea = serde_json::from_value(serde_json::json!({
+            "id": "idea-2", "origin": "mutated", "parents": ["idea-1"], "title": "Issue tracker",
+            "summary": "Track issues in the git repository", "status": "active",
+            "facets": {"audience": "Open source maintainers", "risks": "Crowded market"},
+            "scores": {"feasibility": 7.0, "speed_to_value": 6.5, "differentiation": 7.0, "market_size": 5.0,
+                       "distribution": 4.0, "moats": 3.0, "risk": 6.0, "clarity": 7.0},
+            "overall_score": 6.25
+        }))
+        .unwrap();
+        (base, child)
+    }
+
+    #[test]
+    fn test_word_diff_keeps_common_words() {
+        let words = word_diff(
+            "Track issues in a spreadsheet",
+            "Track issues in the git repository",
+        );
+        assert_eq!(
+            render_words(&words, &PLAIN),
+            "Track issues in [-a spreadsheet-] {+the git repository+}"
+        );
+        assert!(word_diff("same words", "same  words")
+            .iter()
+            .all(|(c, _)| *c == Change::Same));
+        assert_eq!(word_diff("", "new"), vec![(Change::Added, "new")]);
+    }
+
+    #[test]
+    fn test_text_diff_lists_changed_fields_and_scores() {
+        let (base, child) = pair();
+        let text = render_text(&base, &child, false);
+        assert!(text.starts_with("idea-2 \"Issue tracker\" (mutated)\n  vs idea-1"));
+        assert!(text.contains("facets.audience: [-Solo developers-] {+Open source maintainers+}\n"));
+        assert!(text.contains("unchanged: title, facets.jtbd,"));
+        assert!(!text.contains("title: "));
+        assert!(text.contains("differentiation   5.00 → 7.00  +2.00"));
+        assert!(text.contains("overall           5.50 → 6.25  +0.75"));
+        assert!(!text.contains('\x1b'));
+        assert!(render_text(&base, &child, true).contains("\x1b[32mOpen source maintainers\x1b[0m"));
+    }
+
+    #[test]
+    fn test_markdown_diff() {
+        let (base, child) = pair();
+        let md = render_markdown(&base, &child);
+        assert!(
+            md.contains("- **summary**: Track issues in ~~a spreadsheet~~ **the git repository**")
+        );
+        assert!(md.contains("| feasibility | 7.00 | 7.00 | +0.00 |"));
+        assert!(md.contains("- unchanged: `title`"));
+    }
+}
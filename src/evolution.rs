@@ -0,0 +1,1203 @@
+use anyhow::{Context, Result};
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+
+use crate::config::RunConfig;
+use crate::data::{EventType, FinalResult, HistoryEvent, Idea, IdeaStatus, Origin, RunState};
+use crate::llm::{
+    backend_from_config, IdeaDraft, LlmBackend, MutationType, MAX_CRITIQUE_BATCH,
+    MAX_GENERATE_BATCH,
+};
+use crate::scoring::calculate_overall_score;
+use crate::storage::{FileStorage, Storage};
+use std::collections::{BTreeMap, HashSet};
+
+/// Minimum gain in best score that counts as an improvement for stagnation tracking
+const IMPROVEMENT_EPSILON: f32 = 0.01;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StopReason {
+    Threshold,
+    Stagnation,
+    MaxRounds,
+}
+
+impl StopReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StopReason::Threshold => "threshold",
+            StopReason::Stagnation => "stagnation",
+            StopReason::MaxRounds => "max_rounds",
+        }
+    }
+}
+
+/// `--max-rounds` value for `resume`: an absolute limit (`8`) or extra rounds (`+2`)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RoundsLimit {
+    Absolute(u32),
+    Extra(u32),
+}
+
+impl RoundsLimit {
+    fn resolve(self, completed: u32) -> u32 {
+        match self {
+            RoundsLimit::Absolute(n) => n,
+            RoundsLimit::Extra(n) => completed + n,
+        }
+    }
+}
+
+impl std::str::FromStr for RoundsLimit {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let parse = |n: &str| {
+            n.parse::<u32>()
+                .with_context(|| format!("Invalid max rounds: {} (expected N or +N)", s))
+        };
+        match s.strip_prefix('+') {
+            Some(extra) => Ok(RoundsLimit::Extra(parse(extra)?)),
+            None => Ok(RoundsLimit::Absolute(parse(s)?)),
+        }
+    }
+}
+
+/// Where the loop picks up inside an iteration
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Phase {
+    /// Start a new iteration
+    Start,
+    /// Finish GENERATE/CRITIQUE of the current iteration, then check stop conditions
+    Scoring,
+    /// Run SELECT through REFINE of the current iteration
+    Variation,
+}
+
+/// Generate a timestamp-based run id that does not collide with existing runs
+pub fn new_run_id(storage: &dyn Storage) -> Result<String> {
+    let base = format!("run-{}", chrono::Utc::now().format("%Y%m%d-%H%M%S"));
+    let mut id = base.clone();
+    let mut n = 1;
+    while storage.run_exists(&id)? {
+        n += 1;
+        id = format!("{}-{}", base, n);
+    }
+    Ok(id)
+}
+
+/// Run a full evolution and print a short summary
+pub fn run_evolution(config: RunConfig, storage: &FileStorage) -> Result<()> {
+    println!("Starting run {}: {}", config.run_id, config.prompt);
+
+    let result = evolve(config, storage)?;
+    print_summary(&result, storage);
+    Ok(())
+}
+
+/// Resume a run and print a short summary
+pub fn resume_evolution(
+    run_id: &str,
+    max_rounds: Option<RoundsLimit>,
+    storage: &FileStorage,
+) -> Result<()> {
+    // Keep a second resume (or a tournament) from interleaving state.json and history.ndjson
+    let _lock = match storage.run_exists(run_id)? {
+        true => Some(storage.lock_run(run_id)?),
+        false => None,
+    };
+    let result = resume(run_id, max_rounds, storage)?;
+    print_summary(&result, storage);
+    Ok(())
+}
+
+fn print_summary(result: &FinalResult, storage: &FileStorage) {
+    let score = result.best_idea.overall_score.unwrap_or(0.0);
+
+    println!(
+        "Stopped after {} iterations ({})",
+        result.iterations_completed, result.stop_reason
+    );
+    println!("Best: [{:.2}] {}", score, result.best_idea.title);
+    println!("Artifacts: {}", storage.run_dir(&result.run_id).display());
+}
+
+/// Initialize a run directory and drive the memetic loop until a stop condition fires
+pub fn evolve(config: RunConfig, storage: &dyn Storage) -> Result<FinalResult> {
+    config.validate()?;
+    let llm = backend_from_config(&config.llm)?;
+    evolve_with(config, storage, llm)
+}
+
+fn evolve_with(
+    config: RunConfig,
+    storage: &dyn Storage,
+    llm: Box<dyn LlmBackend>,
+) -> Result<FinalResult> {
+    storage.init_run(&config)?;
+
+    let state = RunState::new(&config.run_id);
+    let mut evolution = Evolution {
+        config,
+        storage,
+        llm,
+        state,
+        pending_update: false,
+        skip_threshold: false,
+        resume_pending: false,
+        deferred: Vec::new(),
+    };
+    evolution.run(Phase::Start)
+}
+
+/// Continue an interrupted or finished run from its state.json and history.ndjson.
+/// The stagnation counter and best score are replayed from the scored events.
+pub fn resume(
+    run_id: &str,
+    max_rounds: Option<RoundsLimit>,
+    storage: &dyn Storage,
+) -> Result<FinalResult> {
+    if !storage.run_exists(run_id)? {
+        anyhow::bail!("Run not found: {}", run_id);
+    }
+
+    let mut config = storage.load_config(run_id)?;
+    let mut state = storage.load_state(run_id)?;
+    let history = storage.load_history(run_id)?;
+
+    let finished = history
+        .last()
+        .is_some_and(|e| e.event_type == EventType::Stopped);
+    if let Some(limit) = max_rounds {
+        config.max_rounds = limit.resolve(state.iteration);
+    }
+    if finished && config.max_rounds <= state.iteration {
+        anyhow::bail!(
+            "Run {} already finished after {} iterations; pass --max-rounds +N to continue",
+            run_id,
+            state.iteration
+        );
+    }
+    config.validate()?;
+    let llm = backend_from_config(&config.llm)?;
+
+    let has_event = |event_type: EventType| {
+        history
+            .iter()
+            .any(|e| e.iteration == state.iteration && e.event_type == event_type)
+    };
+    let phase = if state.iteration == 0 {
+        Phase::Start
+    } else if finished {
+        Phase::Variation
+    } else if has_event(EventType::Selected) {
+        // Variation was cut short: keep what was produced and start the next iteration
+        Phase::Start
+    } else {
+        Phase::Scoring
+    };
+
+    // An unfinished CRITIQUE is folded into the best score once scoring completes
+    let pending_update = phase == Phase::Scoring && has_event(EventType::Scored);
+    let replay_through = if pending_update {
+        state.iteration - 1
+    } else {
+        state.iteration
+    };
+    replay_best(&mut state, &history, replay_through);
+
+    // Extending a finished run buys exactly the requested rounds: the old
+    // stagnation streak and an already-met threshold would stop it again
+    // right after the first one
+    let extending = finished && max_rounds.is_some();
+    let skip_threshold = extending
+        && state
+            .best_score
+            .is_some_and(|score| score >= config.score_threshold);
+    if extending {
+        state.stagnation_counter = 0;
+    }
+
+    tracing::info!(
+        run_id = %run_id,
+        iteration = state.iteration,
+        max_rounds = config.max_rounds,
+        "Resuming run"
+    );
+    println!(
+        "Resuming run {} after iteration {} (max rounds: {})",
+        run_id, state.iteration, config.max_rounds
+    );
+
+    let mut evolution = Evolution {
+        config,
+        storage,
+        llm,
+        state,
+        pending_update,
+        skip_threshold,
+        resume_pending: true,
+        deferred: Vec::new(),
+    };
+    evolution.run(phase)
+}
+
+struct Evolution<'a> {
+    config: RunConfig,
+    storage: &'a dyn Storage,
+    llm: Box<dyn LlmBackend>,
+    state: RunState,
+    /// Scores from the current iteration are not yet reflected in best/stagnation
+    pending_update: bool,
+    /// The run was extended after reaching the score threshold, so only the
+    /// other stop conditions apply
+    skip_threshold: bool,
+    /// config.json and final.json still describe the run before this resume
+    resume_pending: bool,
+    /// Events held back until the resume is committed
+    deferred: Vec<HistoryEvent>,
+}
+
+impl Evolution<'_> {
+    fn run(&mut self, mut phase: Phase) -> Result<FinalResult> {
+        let reason = loop {
+            if phase == Phase::Start {
+                self.state.iteration += 1;
+            }
+            let _span = tracing::info_span!(
+                "iteration",
+                run_id = %self.state.run_id,
+                iteration = self.state.iteration
+            )
+            .entered();
+
+            if phase != Phase::Variation {
+                self.generate()?;
+                if self.critique()? || std::mem::take(&mut self.pending_update) {
+                    update_best(&mut self.state);
+                }
+                self.storage.save_state(&self.state)?;
+
+                if let Some(reason) = check_stop(&self.state, &self.config, self.skip_threshold) {
+                    break reason;
+                }
+            }
+            phase = Phase::Start;
+
+            let (survivors, elite) = self.select()?;
+            self.crossover(&survivors)?;
+            self.mutate(&survivors)?;
+            self.refine(&elite)?;
+            self.storage.save_state(&self.state)?;
+        };
+
+        self.finish(reason)
+    }
+
+    /// GENERATE: top up the active population to `population_size`
+    fn generate(&mut self) -> Result<()> {
+        let active = self.state.active_ideas().count();
+        let missing = (self.config.population_size as usize).saturating_sub(active);
+        if missing == 0 {
+            return Ok(());
+        }
+
+        tracing::info!(count = missing, "Generating ideas");
+        let mut remaining = missing;
+        while remaining > 0 {
+            let batch = remaining.min(MAX_GENERATE_BATCH);
+            let seed = self.state.ideas.len() as u64;
+            let output = self.llm.generate(&self.config.prompt, batch, seed)?;
+
+            let ideas: Vec<Idea> = output
+                .ideas
+                .iter()
+                .take(batch)
+                .map(|draft| self.add_idea(draft, Origin::Generated, Vec::new()))
+                .collect();
+            if ideas.is_empty() {
+                anyhow::bail!("Generator returned no ideas");
+            }
+
+            self.emit(
+                EventType::Generated,
+                serde_json::json!({ "ideas": ideas, "output": output }),
+            )?;
+            // A short reply is topped up by the next request
+            remaining -= ideas.len();
+        }
+
+        Ok(())
+    }
+
+    /// CRITIQUE: eliminate constraint violators, then score every active idea that has
+    /// no score yet. Returns whether anything was eliminated or scored.
+    fn critique(&mut self) -> Result<bool> {
+        let eliminated = self.eliminate_violators()?;
+
+        let pending: Vec<&Idea> = self
+            .state
+            .active_ideas()
+            .filter(|idea| idea.overall_score.is_none())
+            .collect();
+        if pending.is_empty() {
+            return Ok(eliminated);
+        }
+
+        tracing::info!(count = pending.len(), "Scoring ideas");
+        let pending: Vec<Idea> = pending.into_iter().cloned().collect();
+
+        for batch in pending.chunks(MAX_CRITIQUE_BATCH) {
+            let refs: Vec<&Idea> = batch.iter().collect();
+            let output = self.llm.critique(&refs, &self.config.scoring_weights)?;
+
+            // Only the ideas sent may be scored, each once: a stray id would
+            // re-score an elite or give an eliminated idea a nonzero score
+            let mut seen = HashSet::new();
+            for patch in &output.patches {
+                if !batch.iter().any(|idea| idea.id == patch.id) {
+                    anyhow::bail!(
+                        "Critic returned a score for idea {} outside the batch",
+                        patch.id
+                    );
+                }
+                if !seen.insert(patch.id.as_str()) {
+                    anyhow::bail!("Critic scored idea {} twice", patch.id);
+                }
+            }
+
+            let mut results = Vec::new();
+            for patch in &output.patches {
+                let Some(idea) = self.state.ideas.iter_mut().find(|i| i.id == patch.id) else {
+                    anyhow::bail!("Critic returned a score for unknown idea {}", patch.id);
+                };
+                let overall = calculate_overall_score(&patch.scores, &self.config.scoring_weights);
+                idea.scores = Some(patch.scores.clone());
+                idea.overall_score = Some(overall);
+                idea.judge_notes = Some(patch.judge_notes.clone());
+                results.push(serde_json::json!({
+                    "id": idea.id,
+                    "scores": patch.scores,
+                    "overall_score": overall,
+                }));
+            }
+
+            for idea in batch {
+                if self
+                    .state
+                    .idea(&idea.id)
+                    .and_then(|i| i.overall_score)
+                    .is_none()
+                {
+                    anyhow::bail!("Critic did not score idea {}", idea.id);
+                }
+            }
+
+            self.emit(
+                EventType::Scored,
+                serde_json::json!({ "results": results, "output": output }),
+            )?;
+        }
+
+        Ok(true)
+    }
+
+    /// Eliminate unscored active ideas that break a configured constraint.
+    /// They get `overall_score = 0` and never reach the critic.
+    fn eliminate_violators(&mut self) -> Result<bool> {
+        let constraints = &self.config.constraints;
+        if constraints.is_empty() {
+            return Ok(false);
+        }
+
+        let mut eliminated = Vec::new();
+        for idea in self.state.ideas.iter_mut() {
+            if idea.status != IdeaStatus::Active || idea.overall_score.is_some() {
+                continue;
+            }
+            let violations = constraints.check(idea);
+            if violations.is_empty() {
+                continue;
+            }
+            tracing::info!(id = %idea.id, count = violations.len(), "Eliminating idea");
+            eliminated.push(serde_json::json!({ "id": idea.id, "violations": violations }));
+            idea.overall_score = Some(0.0);
+            idea.status = IdeaStatus::Eliminated { violations };
+        }
+
+        if eliminated.is_empty() {
+            return Ok(false);
+        }
+        self.emit(
+            EventType::Scored,
+            serde_json::json!({ "results": [], "eliminated": eliminated }),
+        )?;
+        Ok(true)
+    }
+
+    /// SELECT: keep the elite plus one diversity slot, archive the rest.
+    /// Returns (survivors, elite ids).
+    fn select(&mut self) -> Result<(Vec<Idea>, Vec<String>)> {
+        let mut ranked: Vec<(String, f32)> = self
+            .state
+            .active_ideas()
+            .filter_map(|idea| idea.overall_score.map(|s| (idea.id.clone(), s)))
+            .collect();
+        ranked.sort_by(|a, b| {
+            b.1.partial_cmp(&a.1)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.0.cmp(&b.0))
+        });
+
+        let elite_n = (self.config.elite_count as usize).min(ranked.len());
+        let elite: Vec<String> = ranked[..elite_n].iter().map(|(id, _)| id.clone()).collect();
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(self.state.iteration as u64);
+        let diversity = pick_diversity_slot(&ranked, elite_n, &mut rng);
+
+        let mut kept = elite.clone();
+        kept.extend(diversity.clone());
+
+        let mut archived = Vec::new();
+        for idea in self.state.ideas.iter_mut() {
+            if idea.status == IdeaStatus::Active && !kept.contains(&idea.id) {
+                idea.status = IdeaStatus::Archived;
+                archived.push(idea.id.clone());
+            }
+        }
+
+        self.emit(
+            EventType::Selected,
+            serde_json::json!({
+                "kept": kept,
+                "elite": elite,
+                "diversity": diversity,
+                "archived": archived,
+            }),
+        )?;
+
+        let survivors = kept
+            .iter()
+            .filter_map(|id| self.state.idea(id).cloned())
+            .collect();
+        Ok((survivors, elite))
+    }
+
+    /// CROSSOVER: merge distinct survivor pairs, best-ranked pairs first
+    fn crossover(&mut self, survivors: &[Idea]) -> Result<()> {
+        let mut pairs = Vec::new();
+        for i in 0..survivors.len() {
+            for j in (i + 1)..survivors.len() {
+                pairs.push((i, j));
+            }
+        }
+
+        for &(i, j) in pairs.iter().take(self.config.crossover_count as usize) {
+            let (a, b) = (&survivors[i], &survivors[j]);
+            let output = self.llm.merge(a, b)?;
+            let idea = self.add_idea(
+                &output.idea,
+                Origin::Crossover,
+                vec![a.id.clone(), b.id.clone()],
+            );
+            self.emit(
+                EventType::Crossover,
+                serde_json::json!({ "ideas": [idea], "output": output }),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// MUTATE: change exactly one facet of a survivor, cycling through mutation types
+    fn mutate(&mut self, survivors: &[Idea]) -> Result<()> {
+        if survivors.is_empty() {
+            return Ok(());
+        }
+
+        for k in 0..self.config.mutation_count as usize {
+            let source = &survivors[k % survivors.len()];
+            let mutation_type = MutationType::ALL[k % MutationType::ALL.len()];
+            let output = self.llm.mutate(source, mutation_type)?;
+            let idea = self.add_idea(&output.idea, Origin::Mutated, vec![source.id.clone()]);
+            self.emit(
+                EventType::Mutated,
+                serde_json::json!({ "ideas": [idea], "output": output }),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// REFINE: replace each elite idea with an improved child and archive the original
+    fn refine(&mut self, elite: &[String]) -> Result<()> {
+        for id in elite {
+            let Some(source) = self.state.idea(id).cloned() else {
+                continue;
+            };
+            let output = self.llm.refine(&source)?;
+            if output.patch.id != source.id {
+                anyhow::bail!(
+                    "Refiner returned a patch for {} instead of {}",
+                    output.patch.id,
+                    source.id
+                );
+            }
+            let draft = IdeaDraft {
+                title: output.patch.title.clone(),
+                summary: output.patch.summary.clone(),
+                facets: output.patch.facets.clone(),
+            };
+            let idea = self.add_idea(&draft, Origin::Refined, vec![source.id.clone()]);
+
+            if let Some(original) = self.state.ideas.iter_mut().find(|i| i.id == *id) {
+                original.status = IdeaStatus::Archived;
+            }
+
+            self.emit(
+                EventType::Refined,
+                serde_json::json!({ "ideas": [idea], "archived": [id], "output": output }),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn finish(&mut self, reason: StopReason) -> Result<FinalResult> {
+        let best_id = self
+            .state
+            .best_idea_id
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("Run stopped without a scored idea"))?;
+        let best_idea = self
+            .state
+            .idea(&best_id)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Best idea {} missing from state", best_id))?;
+
+        let runner_up = self.state.runner_up().cloned();
+
+        tracing::info!(reason = reason.as_str(), best = %best_id, "Run stopped");
+        self.emit(
+            EventType::Stopped,
+            serde_json::json!({
+                "reason": reason.as_str(),
+                "best_idea_id": best_id,
+                "best_score": self.state.best_score,
+            }),
+        )?;
+
+        let result = FinalResult {
+            run_id: self.state.run_id.clone(),
+            best_idea,
+            runner_up,
+            iterations_completed: self.state.iteration,
+            stop_reason: reason.as_str().to_string(),
+            why_won: Vec::new(),
+        };
+
+        self.storage.save_state(&self.state)?;
+        self.commit_resume()?;
+        self.storage.save_final(&result)?;
+        Ok(result)
+    }
+
+    fn add_idea(&mut self, draft: &IdeaDraft, origin: Origin, parents: Vec<String>) -> Idea {
+        let idea = Idea {
+            id: format!("idea-{:03}", self.state.ideas.len() + 1),
+            gen: self.state.iteration,
+            origin,
+            parents,
+            title: draft.title.clone(),
+            summary: draft.summary.clone(),
+            facets: draft.facets.clone(),
+            scores: None,
+            overall_score: None,
+            judge_notes: None,
+            status: IdeaStatus::Active,
+        };
+        self.state.ideas.push(idea.clone());
+        idea
+    }
+
+    /// Save the resumed config and drop the old final.json only once the run
+    /// has something new to record, so a resume that fails on its first LLM
+    /// call leaves a finished run finished
+    fn commit_resume(&mut self) -> Result<()> {
+        if std::mem::take(&mut self.resume_pending) {
+            self.storage.save_config(&self.config)?;
+            self.storage.clear_final(&self.state.run_id)?;
+            self.storage.save_state(&self.state)?;
+            for event in std::mem::take(&mut self.deferred) {
+                self.storage.append_event(&self.state.run_id, &event)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Persist state, then append the event, so a crash never leaves history
+    /// referring to ideas that state.json does not know about
+    fn emit(&mut self, event_type: EventType, payload: serde_json::Value) -> Result<()> {
+        let event = HistoryEvent::new(self.state.iteration, event_type, payload);
+        if self.resume_pending && event_type == EventType::Selected {
+            // Selection needs no LLM call, so it alone does not commit a resume
+            self.deferred.push(event);
+            return Ok(());
+        }
+        self.commit_resume()?;
+        self.storage.save_state(&self.state)?;
+        self.storage.append_event(&self.state.run_id, &event)
+    }
+}
+
+/// Pick one non-elite idea from the mid-rank band (30%-70% by score) so selection
+/// does not collapse onto the top of the ranking.
+fn pick_diversity_slot(
+    ranked: &[(String, f32)],
+    elite_n: usize,
+    rng: &mut rand::rngs::StdRng,
+) -> Option<String> {
+    let rest = &ranked[elite_n..];
+    if rest.is_empty() {
+        return None;
+    }
+
+    let lo = ((ranked.len() as f32) * 0.3).floor() as usize;
+    let hi = ((ranked.len() as f32) * 0.7).ceil() as usize;
+    let band: Vec<&(String, f32)> = ranked
+        .iter()
+        .enumerate()
+        .filter(|(pos, _)| *pos >= elite_n && *pos >= lo && *pos < hi)
+        .map(|(_, item)| item)
+        .collect();
+
+    let candidates: Vec<&(String, f32)> = if band.is_empty() {
+        rest.iter().collect()
+    } else {
+        band
+    };
+    candidates.choose(rng).map(|(id, _)| id.clone())
+}
+
+/// Recompute the best non-eliminated idea and advance or reset the stagnation counter
+fn update_best(state: &mut RunState) {
+    let best = state
+        .ideas
+        .iter()
+        .filter(|idea| !idea.status.is_eliminated())
+        .filter_map(|idea| idea.overall_score.map(|s| (idea, s)))
+        .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+
+    if let Some((idea, score)) = best {
+        let id = idea.id.clone();
+        record_best(state, id, score);
+    }
+}
+
+fn record_best(state: &mut RunState, id: String, score: f32) {
+    match state.best_score {
+        Some(previous) if score <= previous + IMPROVEMENT_EPSILON => {
+            state.stagnation_counter += 1;
+        }
+        _ => {
+            state.best_idea_id = Some(id);
+            state.best_score = Some(score);
+            state.stagnation_counter = 0;
+        }
+    }
+}
+
+/// Rebuild best idea, best score and stagnation counter from the scored events of
+/// iterations up to `through_iteration`, applying `update_best` once per iteration
+pub(crate) fn replay_best(state: &mut RunState, history: &[HistoryEvent], through_iteration: u32) {
+    let mut by_iteration: BTreeMap<u32, Vec<(String, f32)>> = BTreeMap::new();
+    for event in history
+        .iter()
+        .filter(|e| e.event_type == EventType::Scored && e.iteration <= through_iteration)
+    {
+        // Every scored event counts as one update, even if it only eliminated ideas
+        let scores = by_iteration.entry(event.iteration).or_default();
+        let results = event.payload.get("results").and_then(|r| r.as_array());
+        for result in results.into_iter().flatten() {
+            let id = result.get("id").and_then(|v| v.as_str());
+            let score = result.get("overall_score").and_then(|v| v.as_f64());
+            if let (Some(id), Some(score)) = (id, score) {
+                scores.push((id.to_string(), score as f32));
+            }
+        }
+    }
+
+    state.best_idea_id = None;
+    state.best_score = None;
+    state.stagnation_counter = 0;
+
+    let mut top: Option<(String, f32)> = None;
+    for results in by_iteration.into_values() {
+        for (id, score) in results {
+            if top.as_ref().is_none_or(|(_, best)| score >= *best) {
+                top = Some((id, score));
+            }
+        }
+        if let Some((id, score)) = top.clone() {
+            record_best(state, id, score);
+        }
+    }
+}
+
+fn check_stop(state: &RunState, config: &RunConfig, skip_threshold: bool) -> Option<StopReason> {
+    if !skip_threshold
+        && state
+            .best_score
+            .is_some_and(|score| score >= config.score_threshold)
+    {
+        Some(StopReason::Threshold)
+    } else if state.stagnation_counter >= config.stagnation_patience {
+        Some(StopReason::Stagnation)
+    } else if state.iteration >= config.max_rounds {
+        Some(StopReason::MaxRounds)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::config::{LlmConfig, LlmMode, ScoringWeights};
+    use crate::llm::{MockBackend, Stage};
+    use tempfile::TempDir;
+
+    fn test_config(run_id: &str) -> RunConfig {
+        RunConfig {
+            run_id: run_id.to_string(),
+            prompt: "Developer tools".to_string(),
+            max_rounds: 3,
+            population_size: 6,
+            elite_count: 2,
+            mutation_count: 2,
+            crossover_count: 2,
+            stagnation_patience: 3,
+            score_threshold: 10.0,
+            scoring_weights: ScoringWeights::default(),
+            constraints: Default::default(),
+            llm: Default::default(),
+            created_at: None,
+        }
+    }
+
+    /// Config for a mock run that neither stagnates nor hits the threshold,
+    /// so it always lasts exactly `max_rounds`
+    pub(crate) fn mock_run_config(run_id: &str, max_rounds: u32) -> RunConfig {
+        RunConfig {
+            run_id: run_id.to_string(),
+            prompt: "Developer tools".to_string(),
+            max_rounds,
+            stagnation_patience: 10,
+            score_threshold: 10.0,
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn evolve_mock_run(
+        storage: &dyn Storage,
+        run_id: &str,
+        max_rounds: u32,
+    ) -> FinalResult {
+        evolve(mock_run_config(run_id, max_rounds), storage).unwrap()
+    }
+
+    fn scored_idea(id: &str, score: f32) -> Idea {
+        Idea {
+            id: id.to_string(),
+            gen: 1,
+            origin: Origin::Generated,
+            parents: vec![],
+            title: id.to_string(),
+            summary: String::new(),
+            facets: Default::default(),
+            scores: None,
+            overall_score: Some(score),
+            judge_notes: None,
+            status: IdeaStatus::Active,
+        }
+    }
+
+    #[test]
+    fn test_evolve_writes_all_artifacts() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = FileStorage::new(temp_dir.path());
+
+        let result = evolve(test_config("run-test"), &storage).unwrap();
+
+        assert!(storage.config_path("run-test").exists());
+        assert!(storage.state_path("run-test").exists());
+        assert!(storage.history_path("run-test").exists());
+        assert!(storage.final_path("run-test").exists());
+        assert_eq!(result.iterations_completed, 3);
+        assert_eq!(result.stop_reason, "max_rounds");
+
+        let state = storage.load_state("run-test").unwrap();
+        let max_score = state
+            .ideas
+            .iter()
+            .filter_map(|i| i.overall_score)
+            .fold(f32::MIN, f32::max);
+        assert_eq!(result.best_idea.overall_score, Some(max_score));
+
+        let errors = crate::validation::validate_state_idea_invariants(&state);
+        assert!(errors.is_empty(), "{:?}", errors);
+    }
+
+    #[test]
+    fn test_evolve_history_covers_all_phases() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = FileStorage::new(temp_dir.path());
+        evolve(test_config("run-test"), &storage).unwrap();
+
+        let history = std::fs::read_to_string(storage.history_path("run-test")).unwrap();
+        let types: Vec<EventType> = history
+            .lines()
+            .map(|line| {
+                serde_json::from_str::<HistoryEvent>(line)
+                    .unwrap()
+                    .event_type
+            })
+            .collect();
+
+        for expected in [
+            EventType::Generated,
+            EventType::Scored,
+            EventType::Selected,
+            EventType::Crossover,
+            EventType::Mutated,
+            EventType::Refined,
+        ] {
+            assert!(types.contains(&expected), "missing {}", expected.as_str());
+        }
+        assert_eq!(types.last(), Some(&EventType::Stopped));
+    }
+
+    #[test]
+    fn test_evolve_eliminates_constraint_violators() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = FileStorage::new(temp_dir.path());
+        let mut config = test_config("run-test");
+        config.constraints.forbidden = vec!["agencies".to_string()];
+
+        let result = evolve(config, &storage).unwrap();
+        assert!(!result.best_idea.title.contains("agencies"));
+
+        let state = storage.load_state("run-test").unwrap();
+        let eliminated: Vec<&Idea> = state.eliminated_ideas().collect();
+        assert!(!eliminated.is_empty());
+        for idea in &eliminated {
+            assert_eq!(idea.overall_score, Some(0.0));
+            assert!(matches!(
+                &idea.status,
+                IdeaStatus::Eliminated { violations } if violations[0].constraint == "forbidden"
+            ));
+        }
+        assert!(state
+            .active_ideas()
+            .all(|idea| !idea.title.contains("agencies")));
+
+        let errors = crate::validation::validate_state_idea_invariants(&state);
+        assert!(errors.is_empty(), "{:?}", errors);
+    }
+
+    #[test]
+    fn test_evolve_rejects_existing_run() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = FileStorage::new(temp_dir.path());
+        evolve(test_config("run-test"), &storage).unwrap();
+        assert!(evolve(test_config("run-test"), &storage).is_err());
+    }
+
+    #[test]
+    fn test_resume_finished_run_adds_rounds() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = FileStorage::new(temp_dir.path());
+        evolve(test_config("run-test"), &storage).unwrap();
+
+        let err = resume("run-test", None, &storage).unwrap_err();
+        assert!(err.to_string().contains("--max-rounds +N"), "{}", err);
+
+        let result = resume("run-test", Some(RoundsLimit::Extra(2)), &storage).unwrap();
+        assert!(result.iterations_completed > 3);
+
+        let config = storage.load_config("run-test").unwrap();
+        assert_eq!(config.max_rounds, 5);
+
+        let history = storage.load_history("run-test").unwrap();
+        assert!(history
+            .iter()
+            .any(|e| e.iteration == 3 && e.event_type == EventType::Selected));
+        assert_eq!(history.last().unwrap().event_type, EventType::Stopped);
+
+        let state = storage.load_state("run-test").unwrap();
+        let errors = crate::validation::validate_state_idea_invariants(&state);
+        assert!(errors.is_empty(), "{:?}", errors);
+    }
+
+    #[test]
+    fn test_resume_threshold_stopped_run_adds_requested_rounds() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = FileStorage::new(temp_dir.path());
+        let mut config = test_config("run-test");
+        config.score_threshold = 0.0;
+        let result = evolve(config, &storage).unwrap();
+        assert_eq!(result.stop_reason, "threshold");
+        let before = storage.load_state("run-test").unwrap().iteration;
+
+        resume("run-test", Some(RoundsLimit::Extra(2)), &storage).unwrap();
+        let state = storage.load_state("run-test").unwrap();
+        assert_eq!(state.iteration, before + 2);
+    }
+
+    #[test]
+    fn test_failed_resume_leaves_finished_run_untouched() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = FileStorage::new(temp_dir.path());
+        evolve(test_config("run-test"), &storage).unwrap();
+        let mut config = storage.load_config("run-test").unwrap();
+        config.llm = LlmConfig {
+            mode: LlmMode::Command,
+            command: Some("exit 1".to_string()),
+            ..LlmConfig::default()
+        };
+        storage.save_config(&config).unwrap();
+
+        assert!(resume("run-test", Some(RoundsLimit::Extra(2)), &storage).is_err());
+        assert_eq!(
+            storage.load_config("run-test").unwrap().max_rounds,
+            config.max_rounds
+        );
+        assert!(storage.load_final("run-test").is_ok());
+        let history = storage.load_history("run-test").unwrap();
+        assert_eq!(history.last().unwrap().event_type, EventType::Stopped);
+    }
+
+    #[test]
+    fn test_resume_fails_while_run_is_locked() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = FileStorage::new(temp_dir.path());
+        evolve(test_config("run-test"), &storage).unwrap();
+
+        let lock = storage.lock_run("run-test").unwrap();
+        let err = resume_evolution("run-test", Some(RoundsLimit::Extra(1)), &storage).unwrap_err();
+        assert!(err.to_string().contains("is locked"), "{}", err);
+        drop(lock);
+        assert!(resume_evolution("run-test", Some(RoundsLimit::Extra(1)), &storage).is_ok());
+    }
+
+    #[test]
+    fn test_resume_interrupted_run_continues_iteration() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = FileStorage::new(temp_dir.path());
+        let mut config = test_config("run-test");
+        config.max_rounds = 2;
+        evolve(config, &storage).unwrap();
+
+        // Simulate a crash right after the stop check of iteration 2
+        let history = std::fs::read_to_string(storage.history_path("run-test")).unwrap();
+        let kept: Vec<&str> = history
+            .lines()
+            .filter(|line| !line.contains("\"type\":\"stopped\""))
+            .collect();
+        std::fs::write(storage.history_path("run-test"), kept.join("\n") + "\n").unwrap();
+        std::fs::remove_file(storage.final_path("run-test")).unwrap();
+        let before = storage.load_state("run-test").unwrap();
+
+        let result = resume("run-test", Some(RoundsLimit::Absolute(3)), &storage).unwrap();
+        assert_eq!(result.iterations_completed, 3);
+
+        let history = storage.load_history("run-test").unwrap();
+        let selected_in_2 = history
+            .iter()
+            .filter(|e| e.iteration == 2 && e.event_type == EventType::Selected)
+            .count();
+        assert_eq!(selected_in_2, 1);
+
+        let mut replayed = RunState::new("run-test");
+        replay_best(&mut replayed, &history, 2);
+        assert_eq!(replayed.best_score, before.best_score);
+        assert_eq!(replayed.stagnation_counter, before.stagnation_counter);
+    }
+
+    #[test]
+    fn test_replay_best_matches_live_tracking() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = FileStorage::new(temp_dir.path());
+        let mut config = test_config("run-test");
+        config.max_rounds = 5;
+        config.stagnation_patience = 5;
+        evolve(config, &storage).unwrap();
+
+        let state = storage.load_state("run-test").unwrap();
+        let history = storage.load_history("run-test").unwrap();
+        let mut replayed = state.clone();
+        replay_best(&mut replayed, &history, state.iteration);
+
+        assert_eq!(replayed.best_idea_id, state.best_idea_id);
+        assert_eq!(replayed.best_score, state.best_score);
+        assert_eq!(replayed.stagnation_counter, state.stagnation_counter);
+    }
+
+    #[test]
+    fn test_rounds_limit_parse() {
+        assert_eq!("+2".parse::<RoundsLimit>().unwrap(), RoundsLimit::Extra(2));
+        assert_eq!(
+            "8".parse::<RoundsLimit>().unwrap(),
+            RoundsLimit::Absolute(8)
+        );
+        assert!("+x".parse::<RoundsLimit>().is_err());
+        assert_eq!(RoundsLimit::Extra(2).resolve(3), 5);
+    }
+
+    #[test]
+    fn test_check_stop_conditions() {
+        let config = test_config("run-test");
+        let mut state = RunState::new("run-test");
+        state.iteration = 1;
+        state.best_score = Some(5.0);
+        assert_eq!(check_stop(&state, &config, false), None);
+
+        state.best_score = Some(10.0);
+        assert_eq!(
+            check_stop(&state, &config, false),
+            Some(StopReason::Threshold)
+        );
+        assert_eq!(check_stop(&state, &config, true), None);
+
+        state.best_score = Some(5.0);
+        state.stagnation_counter = 3;
+        assert_eq!(
+            check_stop(&state, &config, false),
+            Some(StopReason::Stagnation)
+        );
+
+        state.stagnation_counter = 0;
+        state.iteration = 3;
+        assert_eq!(
+            check_stop(&state, &config, false),
+            Some(StopReason::MaxRounds)
+        );
+    }
+
+    #[test]
+    fn test_update_best_tracks_stagnation() {
+        let mut state = RunState::new("run-test");
+        state.ideas.push(scored_idea("a", 5.0));
+        update_best(&mut state);
+        assert_eq!(state.best_idea_id.as_deref(), Some("a"));
+        assert_eq!(state.stagnation_counter, 0);
+
+        update_best(&mut state);
+        assert_eq!(state.stagnation_counter, 1);
+
+        state.ideas.push(scored_idea("b", 6.0));
+        update_best(&mut state);
+        assert_eq!(state.best_idea_id.as_deref(), Some("b"));
+        assert_eq!(state.stagnation_counter, 0);
+    }
+
+    #[test]
+    fn test_diversity_slot_is_not_elite() {
+        let ranked: Vec<(String, f32)> = (0..10)
+            .map(|i| (format!("idea-{}", i), 10.0 - i as f32))
+            .collect();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(1);
+        let pick = pick_diversity_slot(&ranked, 3, &mut rng).unwrap();
+        let pos = ranked.iter().position(|(id, _)| *id == pick).unwrap();
+        assert!((3..7).contains(&pos));
+    }
+
+    #[cfg(unix)]
+    /// Mock backend whose replies pass through `tamper` before validation
+    struct TamperedMock<F>(F);
+
+    impl<F: Fn(Stage, &mut serde_json::Value)> LlmBackend for TamperedMock<F> {
+        fn name(&self) -> &'static str {
+            "tampered"
+        }
+
+        fn complete(&self, stage: Stage, input: &serde_json::Value) -> Result<serde_json::Value> {
+            let mut output = MockBackend.complete(stage, input)?;
+            (self.0)(stage, &mut output);
+            Ok(output)
+        }
+    }
+
+    fn evolve_tampered(tamper: impl Fn(Stage, &mut serde_json::Value) + 'static) -> String {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = FileStorage::new(temp_dir.path());
+        let llm = Box::new(TamperedMock(tamper));
+        let err = evolve_with(test_config("run-test"), &storage, llm).unwrap_err();
+        format!("{:#}", err)
+    }
+
+    #[test]
+    fn test_generate_tops_up_short_replies() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = FileStorage::new(temp_dir.path());
+        let llm = Box::new(TamperedMock(|stage, output: &mut serde_json::Value| {
+            if stage == Stage::Generator {
+                output["ideas"].as_array_mut().unwrap().truncate(1);
+            }
+        }));
+        let config = test_config("run-test");
+        let population = config.population_size as usize;
+        evolve_with(config, &storage, llm).unwrap();
+
+        let state = storage.load_state("run-test").unwrap();
+        let generated = state
+            .ideas
+            .iter()
+            .filter(|i| i.gen == 1 && i.origin == Origin::Generated)
+            .count();
+        assert_eq!(generated, population);
+    }
+
+    #[test]
+    fn test_refine_rejects_patch_for_another_idea() {
+        let err = evolve_tampered(|stage, output| {
+            if stage == Stage::Refiner {
+                output["patch"]["id"] = "idea-999".into();
+            }
+        });
+        assert!(err.contains("patch for idea-999 instead of"), "{}", err);
+    }
+
+    #[test]
+    fn test_critique_rejects_patches_outside_the_batch() {
+        let err = evolve_tampered(|stage, output| {
+            if stage == Stage::Critic {
+                output["patches"][0]["id"] = "idea-999".into();
+            }
+        });
+        assert!(err.contains("idea-999 outside the batch"), "{}", err);
+
+        let err = evolve_tampered(|stage, output| {
+            if stage == Stage::Critic {
+                let first = output["patches"][0].clone();
+                output["patches"].as_array_mut().unwrap().push(first);
+            }
+        });
+        assert!(err.contains("twice"), "{}", err);
+    }
+
+    #[test]
+    fn test_evolve_fails_cleanly_on_invalid_backend_output() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = FileStorage::new(temp_dir.path());
+        let mut config = test_config("run-test");
+        config.llm = crate::config::LlmConfig {
+            mode: crate::config::LlmMode::Command,
+            command: Some("cat > /dev/null; echo '{}'".to_string()),
+            ..Default::default()
+        };
+
+        let err = format!("{:#}", evolve(config, &storage).unwrap_err());
+        assert!(err.contains("missing required property 'ideas'"), "{}", err);
+    }
+}
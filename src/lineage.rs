@@ -0,0 +1,307 @@
+use anyhow::Result;
+use std::collections::{HashSet, VecDeque};
+
+use crate::data::{Idea, RunState};
+use crate::storage::Storage;
+use crate::tree::children_map;
+
+/// Longest facet text shown in a change line
+const MAX_FACET_WIDTH: usize = 60;
+
+/// Ancestors of an idea with their distance (1 = parent), nearest first
+pub fn ancestors<'a>(state: &'a RunState, idea_id: &str) -> Vec<(&'a Idea, usize)> {
+    let mut found = Vec::new();
+    let mut seen = HashSet::from([idea_id.to_string()]);
+    let mut queue: VecDeque<(&str, usize)> = VecDeque::from([(idea_id, 0)]);
+    while let Some((id, distance)) = queue.pop_front() {
+        let Some(idea) = state.idea(id) else {
+            continue;
+        };
+        for parent_id in &idea.parents {
+            if !seen.insert(parent_id.clone()) {
+                continue;
+            }
+            if let Some(parent) = state.idea(parent_id) {
+                found.push((parent, distance + 1));
+                queue.push_back((&parent.id, distance + 1));
+            }
+        }
+    }
+    found
+}
+
+/// Descendants of an idea with their distance (1 = child), nearest first
+pub fn descendants<'a>(state: &'a RunState, idea_id: &str) -> Vec<(&'a Idea, usize)> {
+    let children = children_map(state);
+    let mut found = Vec::new();
+    let mut seen = HashSet::from([idea_id.to_string()]);
+    let mut queue: VecDeque<(&str, usize)> = VecDeque::from([(idea_id, 0)]);
+    while let Some((id, distance)) = queue.pop_front() {
+        for &child in children.get(id).into_iter().flatten() {
+            if seen.insert(child.id.clone()) {
+                found.push((child, distance + 1));
+                queue.push_back((&child.id, distance + 1));
+            }
+        }
+    }
+    found
+}
+
+/// The best idea and its ancestors, oldest first. Crossovers follow the
+/// higher-scoring parent.
+pub fn winning_lineage<'a>(state: &'a RunState, best_id: &str) -> Vec<&'a Idea> {
+    let mut lineage = Vec::new();
+    let mut seen = HashSet::new();
+    let mut current = state.idea(best_id);
+    while let Some(idea) = current {
+        if !seen.insert(idea.id.as_str()) {
+            break;
+        }
+        lineage.push(idea);
+        current = idea
+            .parents
+            .iter()
+            .filter_map(|id| state.idea(id))
+            .max_by(|a, b| {
+                a.overall_score
+                    .partial_cmp(&b.overall_score)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            });
+    }
+    lineage.reverse();
+    lineage
+}
+
+/// Facets whose text differs between two ideas, as (name, before, after)
+pub fn facet_changes<'a>(
+    before: &'a Idea,
+    after: &'a Idea,
+) -> Vec<(&'static str, &'a str, &'a str)> {
+    before
+        .facets
+        .fields()
+        .into_iter()
+        .zip(after.facets.fields())
+        .filter(|((_, old), (_, new))| old.trim() != new.trim())
+        .map(|((name, old), (_, new))| (name, old, new))
+        .collect()
+}
+
+fn truncate(text: &str) -> String {
+    if text.chars().count() > MAX_FACET_WIDTH {
+        let short: String = text.chars().take(MAX_FACET_WIDTH - 3).collect();
+        format!("{}...", short)
+    } else {
+        text.to_string()
+    }
+}
+
+fn score(idea: &Idea) -> String {
+    idea.overall_score
+        .map(|s| format!("{:.2}", s))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+fn print_relatives(label: &str, idea_id: &str, relatives: &[(&Idea, usize)], marker: &str) {
+    println!("{} of {} ({}):", label, idea_id, relatives.len());
+    if relatives.is_empty() {
+        println!("  (none)");
+    }
+    for (idea, distance) in relatives {
+        println!(
+            "  {}{} [{}] {} {} ({}, {})",
+            marker,
+            distance,
+            score(idea),
+            idea.id,
+            idea.title,
+            idea.origin.as_str(),
+            idea.status.as_str()
+        );
+    }
+}
+
+/// Print the ancestors and/or descendants of one idea
+pub fn print_lineage(
+    storage: &dyn Storage,
+    run_id: &str,
+    idea_id: &str,
+    ancestors_only: bool,
+    descendants_only: bool,
+) -> Result<()> {
+    let state = load_state(storage, run_id)?;
+    let Some(idea) = state.idea(idea_id) else {
+        anyhow::bail!("Idea {} not found in run {}", idea_id, run_id);
+    };
+
+    println!(
+        "[{}] {} {} ({}, {})\n",
+        score(idea),
+        idea.id,
+        idea.title,
+        idea.origin.as_str(),
+        idea.status.as_str()
+    );
+    let both = !ancestors_only && !descendants_only;
+    if ancestors_only || both {
+        print_relatives("Ancestors", idea_id, &ancestors(&state, idea_id), "↑");
+    }
+    if both {
+        println!();
+    }
+    if descendants_only || both {
+        print_relatives("Descendants", idea_id, &descendants(&state, idea_id), "↓");
+    }
+    Ok(())
+}
+
+/// Print the steps that produced the best idea: origin, score delta and the
+/// facets that changed relative to the parent each step followed
+pub fn print_winner_path(storage: &dyn Storage, run_id: &str) -> Result<()> {
+    let state = load_state(storage, run_id)?;
+    let Some(best_id) = state.best_idea_id.as_deref() else {
+        anyhow::bail!("Run {} has no best idea yet", run_id);
+    };
+    let path = winning_lineage(&state, best_id);
+    if path.is_empty() {
+        anyhow::bail!("Best idea {} not found in run {}", best_id, run_id);
+    }
+
+    let origins: Vec<&str> = path.iter().map(|idea| idea.origin.as_str()).collect();
+    println!(
+        "Winning path to {} in run {}: {}\n",
+        best_id,
+        run_id,
+        origins.join(" → ")
+    );
+
+    for (step, &idea) in path.iter().enumerate() {
+        let previous = step.checked_sub(1).map(|i| path[i]);
+        let delta = match (previous.and_then(|p| p.overall_score), idea.overall_score) {
+            (Some(before), Some(after)) => format!(" ({:+.2})", after - before),
+            _ => String::new(),
+        };
+        let source = match previous {
+            None => String::new(),
+            Some(parent) if idea.parents.len() > 1 => {
+                let others: Vec<&str> = idea
+                    .parents
+                    .iter()
+                    .map(String::as_str)
+                    .filter(|id| *id != parent.id)
+                    .collect();
+                format!(" of {} + {}", parent.id, others.join(" + "))
+            }
+            Some(parent) => format!(" from {}", parent.id),
+        };
+        println!(
+            "{}. {} [{}{}] {}{}",
+            step + 1,
+            idea.id,
+            score(idea),
+            delta,
+            idea.origin.as_str(),
+            source
+        );
+        println!("   {}", idea.title);
+
+        let Some(parent) = previous else {
+            continue;
+        };
+        let changes = facet_changes(parent, idea);
+        if changes.is_empty() {
+            println!("   (no facet changes)");
+        }
+        for (name, before, after) in changes {
+            println!("   {}: {} → {}", name, truncate(before), truncate(after));
+        }
+    }
+    Ok(())
+}
+
+fn load_state(storage: &dyn Storage, run_id: &str) -> Result<RunState> {
+    if !storage.has_state(run_id)? {
+        anyhow::bail!("Run {} not found", run_id);
+    }
+    storage.load_state(run_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state() -> RunState {
+        serde_json::from_value(serde_json::json!({
+            "run_id": "run-1",
+            "iteration": 3,
+            "best_idea_id": "idea-5",
+            "ideas": [
+                {"id": "idea-1", "origin": "generated", "title": "A", "status": "archived", "overall_score": 5.0,
+                 "facets": {"audience": "Developers", "monetization": "Ads"}},
+                {"id": "idea-2", "origin": "generated", "title": "B", "status": "archived", "overall_score": 7.0,
+                 "facets": {"audience": "Teams", "monetization": "Subscription"}},
+                {"id": "idea-3", "origin": "crossover", "parents": ["idea-1", "idea-2"], "title": "AB", "status": "archived", "overall_score": 7.5,
+                 "facets": {"audience": "Developer teams", "monetization": "Subscription"}},
+                {"id": "idea-4", "origin": "mutated", "parents": ["idea-1"], "title": "A'", "status": "eliminated", "overall_score": 4.0},
+                {"id": "idea-5", "origin": "refined", "parents": ["idea-3"], "title": "AB+", "status": "active", "overall_score": 8.0,
+                 "facets": {"audience": "Developer teams", "monetization": "Per-seat subscription"}}
+            ]
+        }))
+        .unwrap()
+    }
+
+    fn ids(relatives: &[(&Idea, usize)]) -> Vec<(String, usize)> {
+        relatives
+            .iter()
+            .map(|(idea, distance)| (idea.id.clone(), *distance))
+            .collect()
+    }
+
+    #[test]
+    fn test_ancestors_and_descendants() {
+        let state = state();
+        assert_eq!(
+            ids(&ancestors(&state, "idea-5")),
+            vec![
+                ("idea-3".to_string(), 1),
+                ("idea-1".to_string(), 2),
+                ("idea-2".to_string(), 2)
+            ]
+        );
+        assert_eq!(
+            ids(&descendants(&state, "idea-1")),
+            vec![
+                ("idea-3".to_string(), 1),
+                ("idea-4".to_string(), 1),
+                ("idea-5".to_string(), 2)
+            ]
+        );
+        assert!(ancestors(&state, "idea-1").is_empty());
+        assert!(descendants(&state, "idea-5").is_empty());
+    }
+
+    #[test]
+    fn test_winning_lineage_follows_best_parent() {
+        let state = state();
+        let ids: Vec<&str> = winning_lineage(&state, "idea-5")
+            .iter()
+            .map(|i| i.id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["idea-2", "idea-3", "idea-5"]);
+    }
+
+    #[test]
+    fn test_facet_changes() {
+        let state = state();
+        let crossover = state.idea("idea-3").unwrap();
+        let refined = state.idea("idea-5").unwrap();
+        assert_eq!(
+            facet_changes(crossover, refined),
+            vec![("monetization", "Subscription", "Per-seat subscription")]
+        );
+        assert_eq!(
+            facet_changes(state.idea("idea-2").unwrap(), crossover),
+            vec![("audience", "Teams", "Developer teams")]
+        );
+    }
+}
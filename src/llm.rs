@@ -0,0 +1,846 @@
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use crate::config::{LlmConfig, LlmMode, ScoringWeights};
+use crate::data::{EventType, Facets, Idea, Scores};
+use crate::schema;
+use crate::scoring::calculate_overall_score;
+
+/// Maximum ideas per generator call (generator.output.schema.json maxItems)
+pub const MAX_GENERATE_BATCH: usize = 30;
+/// Maximum ideas per critic call (critic.output.schema.json maxItems)
+pub const MAX_CRITIQUE_BATCH: usize = 50;
+
+/// LLM-driven stages of the loop; each maps to a bundled output schema
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Stage {
+    Generator,
+    Critic,
+    Merger,
+    Mutator,
+    Refiner,
+}
+
+impl Stage {
+    /// Stage whose output is embedded in a history event of this type
+    pub fn for_event(event_type: EventType) -> Option<Self> {
+        match event_type {
+            EventType::Generated => Some(Stage::Generator),
+            EventType::Scored => Some(Stage::Critic),
+            EventType::Crossover => Some(Stage::Merger),
+            EventType::Mutated => Some(Stage::Mutator),
+            EventType::Refined => Some(Stage::Refiner),
+            EventType::Selected | EventType::Stopped => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Stage::Generator => "generator",
+            Stage::Critic => "critic",
+            Stage::Merger => "merger",
+            Stage::Mutator => "mutator",
+            Stage::Refiner => "refiner",
+        }
+    }
+
+    fn instructions(&self) -> &'static str {
+        match self {
+            Stage::Generator => "Generate `count` diverse product ideas for `prompt`. Fill every facet concretely.",
+            Stage::Critic => "Score each idea 0-10 on every criterion (risk: higher means riskier). Explain the weakest spots in judge_notes.",
+            Stage::Merger => "Merge `idea_a` and `idea_b` into one idea that keeps the strongest facets of both.",
+            Stage::Mutator => "Rewrite `idea` changing exactly one facet, given by `mutation_type`. Keep the other facets.",
+            Stage::Refiner => "Improve `idea` by addressing its judge_notes and weakest scores. List what changed.",
+        }
+    }
+}
+
+/// A model that can execute the LLM stages of the loop.
+///
+/// Backends only implement `complete`, which maps a stage input to raw JSON.
+/// The typed calls validate every response against the stage's bundled schema
+/// before deserializing it.
+pub trait LlmBackend {
+    fn name(&self) -> &'static str;
+
+    fn complete(&self, stage: Stage, input: &serde_json::Value) -> Result<serde_json::Value>;
+
+    fn generate(&self, prompt: &str, count: usize, seed: u64) -> Result<GeneratorOutput> {
+        let input = GenerateInput {
+            prompt: prompt.to_string(),
+            count,
+            seed,
+        };
+        run_stage(self, Stage::Generator, &input)
+    }
+
+    fn critique(&self, ideas: &[&Idea], weights: &ScoringWeights) -> Result<CriticOutput> {
+        let input = CritiqueInput {
+            ideas: ideas.iter().map(|idea| (*idea).clone()).collect(),
+            weights: weights.clone(),
+        };
+        run_stage(self, Stage::Critic, &input)
+    }
+
+    fn merge(&self, a: &Idea, b: &Idea) -> Result<MergerOutput> {
+        let input = MergeInput {
+            idea_a: a.clone(),
+            idea_b: b.clone(),
+        };
+        run_stage(self, Stage::Merger, &input)
+    }
+
+    fn mutate(&self, idea: &Idea, mutation_type: MutationType) -> Result<MutatorOutput> {
+        let input = MutateInput {
+            idea: idea.clone(),
+            mutation_type,
+        };
+        run_stage(self, Stage::Mutator, &input)
+    }
+
+    fn refine(&self, idea: &Idea) -> Result<RefinerOutput> {
+        let input = RefineInput { idea: idea.clone() };
+        run_stage(self, Stage::Refiner, &input)
+    }
+}
+
+/// Build the backend selected in the run config
+pub fn backend_from_config(config: &LlmConfig) -> Result<Box<dyn LlmBackend>> {
+    match config.mode {
+        LlmMode::Mock => Ok(Box::new(MockBackend)),
+        LlmMode::Command => {
+            let command = config
+                .command
+                .clone()
+                .ok_or_else(|| anyhow::anyhow!("Command mode requires --llm-command"))?;
+            Ok(Box::new(CommandBackend { command }))
+        }
+        LlmMode::Http => {
+            let url = config
+                .url
+                .clone()
+                .ok_or_else(|| anyhow::anyhow!("HTTP mode requires --llm-url"))?;
+            Ok(Box::new(HttpBackend::new(&url, config.model.clone())))
+        }
+    }
+}
+
+fn run_stage<B, I, T>(backend: &B, stage: Stage, input: &I) -> Result<T>
+where
+    B: LlmBackend + ?Sized,
+    I: Serialize,
+    T: DeserializeOwned,
+{
+    let input = serde_json::to_value(input)?;
+    let output = backend.complete(stage, &input).with_context(|| {
+        format!(
+            "{} backend failed at {} stage",
+            backend.name(),
+            stage.name()
+        )
+    })?;
+
+    let violations = schema::validate(&schema::bundled(stage.name())?, &output);
+    if !violations.is_empty() {
+        let details: Vec<String> = violations.iter().map(|v| format!("  - {}", v)).collect();
+        anyhow::bail!(
+            "{} output from {} backend does not match {}.output.schema.json:\n{}",
+            stage.name(),
+            backend.name(),
+            stage.name(),
+            details.join("\n")
+        );
+    }
+
+    serde_json::from_value(output)
+        .with_context(|| format!("Failed to parse {} output", stage.name()))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct GenerateInput {
+    prompt: String,
+    count: usize,
+    seed: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CritiqueInput {
+    ideas: Vec<Idea>,
+    weights: ScoringWeights,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct MergeInput {
+    idea_a: Idea,
+    idea_b: Idea,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct MutateInput {
+    idea: Idea,
+    mutation_type: MutationType,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RefineInput {
+    idea: Idea,
+}
+
+/// Idea content produced by the generator, merger and mutator stages
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IdeaDraft {
+    pub title: String,
+    pub summary: String,
+    pub facets: Facets,
+}
+
+/// Output of the generator stage (generator.output.schema.json)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GeneratorOutput {
+    pub ideas: Vec<IdeaDraft>,
+}
+
+/// Per-idea scoring result from the critic stage
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ScorePatch {
+    pub id: String,
+    pub scores: Scores,
+    pub overall_score: f32,
+    pub judge_notes: String,
+}
+
+/// Output of the critic stage (critic.output.schema.json)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CriticOutput {
+    pub patches: Vec<ScorePatch>,
+}
+
+/// Output of the crossover stage (merger.output.schema.json)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MergerOutput {
+    pub idea: IdeaDraft,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum MutationType {
+    Audience,
+    Monetization,
+    Distribution,
+    Differentiator,
+    Jtbd,
+}
+
+impl MutationType {
+    pub const ALL: [MutationType; 5] = [
+        MutationType::Audience,
+        MutationType::Monetization,
+        MutationType::Distribution,
+        MutationType::Differentiator,
+        MutationType::Jtbd,
+    ];
+}
+
+/// Output of the mutation stage (mutator.output.schema.json)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MutatorOutput {
+    pub mutation_type: MutationType,
+    pub idea: IdeaDraft,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RefinePatch {
+    pub id: String,
+    pub title: String,
+    pub summary: String,
+    pub facets: Facets,
+    pub changes: Vec<String>,
+}
+
+/// Output of the refinement stage (refiner.output.schema.json)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RefinerOutput {
+    pub patch: RefinePatch,
+}
+
+/// Pipes a JSON request (stage, instructions, schema, input) to a local executable's
+/// stdin and reads the stage output JSON from its stdout.
+pub struct CommandBackend {
+    pub command: String,
+}
+
+impl LlmBackend for CommandBackend {
+    fn name(&self) -> &'static str {
+        "command"
+    }
+
+    fn complete(&self, stage: Stage, input: &serde_json::Value) -> Result<serde_json::Value> {
+        let request = serde_json::to_vec(&request_envelope(stage, input)?)?;
+
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg(&self.command)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("Failed to start LLM command: {}", self.command))?;
+
+        // Write on a separate thread so a chatty child cannot deadlock on a full stdout pipe.
+        let mut stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| anyhow::anyhow!("Failed to open LLM command stdin"))?;
+        let writer = std::thread::spawn(move || stdin.write_all(&request));
+
+        let output = child.wait_with_output()?;
+        writer
+            .join()
+            .map_err(|_| anyhow::anyhow!("LLM command stdin writer panicked"))?
+            .context("Failed to write request to LLM command")?;
+
+        if !output.status.success() {
+            anyhow::bail!(
+                "LLM command exited with {}: {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+
+        parse_json_response(&String::from_utf8_lossy(&output.stdout))
+    }
+}
+
+/// Calls an OpenAI-compatible `/chat/completions` endpoint with a JSON-schema response format.
+/// The API key is read from `EVOIDEA_API_KEY` (or `OPENAI_API_KEY`) and never written to disk.
+pub struct HttpBackend {
+    base_url: String,
+    model: Option<String>,
+    api_key: Option<String>,
+    agent: ureq::Agent,
+}
+
+impl HttpBackend {
+    pub fn new(base_url: &str, model: Option<String>) -> Self {
+        let api_key = std::env::var("EVOIDEA_API_KEY")
+            .or_else(|_| std::env::var("OPENAI_API_KEY"))
+            .ok()
+            .filter(|key| !key.is_empty());
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            model,
+            api_key,
+            agent: ureq::AgentBuilder::new()
+                .timeout(Duration::from_secs(300))
+                .build(),
+        }
+    }
+}
+
+impl LlmBackend for HttpBackend {
+    fn name(&self) -> &'static str {
+        "http"
+    }
+
+    fn complete(&self, stage: Stage, input: &serde_json::Value) -> Result<serde_json::Value> {
+        let schema = schema::bundled(stage.name())?;
+        let mut body = serde_json::json!({
+            "messages": [
+                {
+                    "role": "system",
+                    "content": format!(
+                        "{}\nRespond with a single JSON object matching this JSON Schema and nothing else:\n{}",
+                        stage.instructions(),
+                        schema
+                    ),
+                },
+                { "role": "user", "content": input.to_string() },
+            ],
+            // Strict mode rejects the bundled schemas' bounds (minimum, minItems);
+            // run_stage validates every response against them instead
+            "response_format": {
+                "type": "json_schema",
+                "json_schema": { "name": stage.name(), "schema": schema, "strict": false },
+            },
+        });
+        if let Some(model) = &self.model {
+            body["model"] = serde_json::Value::String(model.clone());
+        }
+
+        let url = format!("{}/chat/completions", self.base_url);
+        let mut request = self.agent.post(&url);
+        if let Some(key) = &self.api_key {
+            request = request.set("Authorization", &format!("Bearer {}", key));
+        }
+
+        let response: serde_json::Value = request
+            .send_json(body)
+            .with_context(|| format!("Request to {} failed", url))?
+            .into_json()
+            .context("Response is not valid JSON")?;
+
+        let content = response
+            .pointer("/choices/0/message/content")
+            .and_then(|c| c.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Response has no choices[0].message.content"))?;
+        parse_json_response(content)
+    }
+}
+
+fn request_envelope(stage: Stage, input: &serde_json::Value) -> Result<serde_json::Value> {
+    Ok(serde_json::json!({
+        "stage": stage.name(),
+        "instructions": stage.instructions(),
+        "schema": schema::bundled(stage.name())?,
+        "input": input,
+    }))
+}
+
+/// Parse model output as JSON, tolerating a surrounding markdown code fence
+fn parse_json_response(text: &str) -> Result<serde_json::Value> {
+    let trimmed = text.trim();
+    let unfenced = trimmed
+        .strip_prefix("```json")
+        .or_else(|| trimmed.strip_prefix("```"))
+        .and_then(|rest| rest.strip_suffix("```"))
+        .unwrap_or(trimmed);
+    serde_json::from_str(unfenced.trim()).context("LLM response is not valid JSON")
+}
+
+const AUDIENCES: [&str; 6] = [
+    "solo developers",
+    "small agencies",
+    "indie hackers",
+    "platform teams",
+    "freelance designers",
+    "open source maintainers",
+];
+
+const DIFFERENTIATORS: [&str; 6] = [
+    "works fully offline",
+    "git-native storage",
+    "zero-config setup",
+    "opinionated templates",
+    "keyboard-first workflow",
+    "built-in analytics",
+];
+
+const MONETIZATION: [&str; 5] = [
+    "$9/mo subscription",
+    "usage-based pricing",
+    "one-time license",
+    "free core, paid team tier",
+    "marketplace commission",
+];
+
+const DISTRIBUTION: [&str; 5] = [
+    "Hacker News launch",
+    "GitHub Marketplace",
+    "developer newsletters",
+    "SEO content",
+    "partner integrations",
+];
+
+const JTBD: [&str; 5] = [
+    "ship faster without extra tooling",
+    "keep track of work without context switching",
+    "turn scattered notes into decisions",
+    "automate repetitive setup",
+    "prove value to stakeholders",
+];
+
+/// Deterministic stand-in for the LLM stages.
+/// Content is derived from a hash of the inputs, so identical runs produce identical artifacts.
+pub struct MockBackend;
+
+impl LlmBackend for MockBackend {
+    fn name(&self) -> &'static str {
+        "mock"
+    }
+
+    fn complete(&self, stage: Stage, input: &serde_json::Value) -> Result<serde_json::Value> {
+        let input = input.clone();
+        let output = match stage {
+            Stage::Generator => {
+                let req: GenerateInput = serde_json::from_value(input)?;
+                serde_json::to_value(mock_generate(&req.prompt, req.count, req.seed as usize))?
+            }
+            Stage::Critic => {
+                let req: CritiqueInput = serde_json::from_value(input)?;
+                serde_json::to_value(mock_critique(&req.ideas, &req.weights))?
+            }
+            Stage::Merger => {
+                let req: MergeInput = serde_json::from_value(input)?;
+                serde_json::to_value(mock_merge(&req.idea_a, &req.idea_b))?
+            }
+            Stage::Mutator => {
+                let req: MutateInput = serde_json::from_value(input)?;
+                serde_json::to_value(mock_mutate(&req.idea, req.mutation_type))?
+            }
+            Stage::Refiner => {
+                let req: RefineInput = serde_json::from_value(input)?;
+                serde_json::to_value(mock_refine(&req.idea))?
+            }
+        };
+        Ok(output)
+    }
+}
+
+fn mock_generate(prompt: &str, count: usize, offset: usize) -> GeneratorOutput {
+    let topic = prompt.trim();
+    let ideas = (offset..offset + count)
+        .map(|n| {
+            let audience = pick(&AUDIENCES, topic, n);
+            let differentiator = pick(&DIFFERENTIATORS, topic, n + 1);
+            IdeaDraft {
+                title: format!("{} for {} #{}", topic, audience, n + 1),
+                summary: format!(
+                    "A tool that helps {} {} with a {} approach.",
+                    audience,
+                    pick(&JTBD, topic, n),
+                    differentiator
+                ),
+                facets: Facets {
+                    audience: audience.to_string(),
+                    jtbd: pick(&JTBD, topic, n).to_string(),
+                    differentiator: differentiator.to_string(),
+                    monetization: pick(&MONETIZATION, topic, n).to_string(),
+                    distribution: pick(&DISTRIBUTION, topic, n).to_string(),
+                    risks: "Crowded market; unclear willingness to pay".to_string(),
+                },
+            }
+        })
+        .collect();
+    GeneratorOutput { ideas }
+}
+
+fn mock_critique(ideas: &[Idea], weights: &ScoringWeights) -> CriticOutput {
+    let patches = ideas
+        .iter()
+        .map(|idea| {
+            let scores = mock_scores(idea);
+            let overall_score = calculate_overall_score(&scores, weights);
+            let weakest = weakest_criterion(&scores);
+            ScorePatch {
+                id: idea.id.clone(),
+                scores,
+                overall_score,
+                judge_notes: format!("Weakest criterion: {}", weakest),
+            }
+        })
+        .collect();
+    CriticOutput { patches }
+}
+
+fn mock_merge(a: &Idea, b: &Idea) -> MergerOutput {
+    MergerOutput {
+        idea: IdeaDraft {
+            title: format!("{} x {}", short_title(&a.title), short_title(&b.title)),
+            summary: format!("{} Combined with: {}", a.summary, b.facets.differentiator),
+            facets: Facets {
+                audience: a.facets.audience.clone(),
+                jtbd: a.facets.jtbd.clone(),
+                differentiator: format!(
+                    "{} + {}",
+                    a.facets.differentiator, b.facets.differentiator
+                ),
+                monetization: b.facets.monetization.clone(),
+                distribution: b.facets.distribution.clone(),
+                risks: a.facets.risks.clone(),
+            },
+        },
+    }
+}
+
+fn mock_mutate(idea: &Idea, mutation_type: MutationType) -> MutatorOutput {
+    let mut facets = idea.facets.clone();
+    let key = &idea.title;
+    let (label, value) = match mutation_type {
+        MutationType::Audience => {
+            facets.audience = pick(&AUDIENCES, key, 7).to_string();
+            ("audience", facets.audience.clone())
+        }
+        MutationType::Monetization => {
+            facets.monetization = pick(&MONETIZATION, key, 7).to_string();
+            ("monetization", facets.monetization.clone())
+        }
+        MutationType::Distribution => {
+            facets.distribution = pick(&DISTRIBUTION, key, 7).to_string();
+            ("distribution", facets.distribution.clone())
+        }
+        MutationType::Differentiator => {
+            facets.differentiator = pick(&DIFFERENTIATORS, key, 7).to_string();
+            ("differentiator", facets.differentiator.clone())
+        }
+        MutationType::Jtbd => {
+            facets.jtbd = pick(&JTBD, key, 7).to_string();
+            ("jtbd", facets.jtbd.clone())
+        }
+    };
+
+    MutatorOutput {
+        mutation_type,
+        idea: IdeaDraft {
+            title: format!("{} ({} shift)", short_title(&idea.title), label),
+            summary: format!("{} Now with {}: {}.", idea.summary, label, value),
+            facets,
+        },
+    }
+}
+
+fn mock_refine(idea: &Idea) -> RefinerOutput {
+    let weakest = idea
+        .scores
+        .as_ref()
+        .map(weakest_criterion)
+        .unwrap_or("clarity");
+    let change = format!("Refinement: strengthened {}.", weakest);
+
+    RefinerOutput {
+        patch: RefinePatch {
+            id: idea.id.clone(),
+            title: idea.title.clone(),
+            summary: format!("{} {}", idea.summary, change),
+            facets: idea.facets.clone(),
+            changes: vec![change],
+        },
+    }
+}
+
+fn mock_scores(idea: &Idea) -> Scores {
+    let base = format!("{}|{}", idea.title, idea.facets.differentiator);
+    // Each refinement pass nudges every criterion up, so the loop can make progress.
+    let bonus = idea.summary.matches("Refinement:").count() as f32 * 0.6;
+    let score = |criterion: &str| {
+        let raw = 3.0 + (fnv1a(&format!("{}|{}", base, criterion)) % 55) as f32 / 10.0;
+        (raw + bonus).min(10.0)
+    };
+
+    let mut scores = Scores {
+        feasibility: score("feasibility"),
+        speed_to_value: score("speed_to_value"),
+        differentiation: score("differentiation"),
+        market_size: score("market_size"),
+        distribution: score("distribution"),
+        moats: score("moats"),
+        risk: 0.0,
+        clarity: score("clarity"),
+    };
+    // Risk is inverted in the overall score, so refinement lowers it.
+    scores.risk = (3.0 + (fnv1a(&format!("{}|risk", base)) % 55) as f32 / 10.0 - bonus).max(0.0);
+    scores
+}
+
+fn weakest_criterion(scores: &Scores) -> &'static str {
+    scores
+        .criteria()
+        .iter()
+        .map(|(name, value)| {
+            // Risk is a cost, so its "strength" is the inverted value.
+            let strength = if *name == "risk" {
+                10.0 - value
+            } else {
+                *value
+            };
+            (*name, strength)
+        })
+        .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+        .map(|(name, _)| name)
+        .unwrap_or("clarity")
+}
+
+fn short_title(title: &str) -> String {
+    title
+        .chars()
+        .take(40)
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+fn pick<'a>(pool: &[&'a str], key: &str, n: usize) -> &'a str {
+    let idx = (fnv1a(key) as usize).wrapping_add(n) % pool.len();
+    pool[idx]
+}
+
+fn fnv1a(input: &str) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in input.bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{IdeaStatus, Origin};
+
+    fn idea_from(draft: &IdeaDraft) -> Idea {
+        Idea {
+            id: "idea-001".to_string(),
+            gen: 1,
+            origin: Origin::Generated,
+            parents: vec![],
+            title: draft.title.clone(),
+            summary: draft.summary.clone(),
+            facets: draft.facets.clone(),
+            scores: None,
+            overall_score: None,
+            judge_notes: None,
+            status: IdeaStatus::Active,
+        }
+    }
+
+    #[test]
+    fn test_mock_generate_is_deterministic() {
+        let a = MockBackend.generate("Developer tools", 3, 0).unwrap();
+        let b = MockBackend.generate("Developer tools", 3, 0).unwrap();
+        assert_eq!(a, b);
+        assert_eq!(a.ideas.len(), 3);
+    }
+
+    #[test]
+    fn test_mock_scores_in_range() {
+        let draft = &MockBackend.generate("Developer tools", 1, 0).unwrap().ideas[0];
+        let idea = idea_from(draft);
+        let out = MockBackend
+            .critique(&[&idea], &ScoringWeights::default())
+            .unwrap();
+        for (_, value) in out.patches[0].scores.criteria() {
+            assert!((0.0..=10.0).contains(&value));
+        }
+    }
+
+    #[test]
+    fn test_mock_refine_improves_score() {
+        let draft = &MockBackend.generate("Developer tools", 1, 0).unwrap().ideas[0];
+        let mut idea = idea_from(draft);
+        let weights = ScoringWeights::default();
+        let scored = MockBackend.critique(&[&idea], &weights).unwrap();
+        let before = scored.patches[0].overall_score;
+
+        idea.scores = Some(scored.patches[0].scores.clone());
+        idea.summary = MockBackend.refine(&idea).unwrap().patch.summary;
+        let after = MockBackend.critique(&[&idea], &weights).unwrap().patches[0].overall_score;
+
+        assert!(after > before);
+    }
+
+    #[test]
+    fn test_mock_mutate_changes_one_facet() {
+        let draft = &MockBackend.generate("Developer tools", 1, 0).unwrap().ideas[0];
+        let idea = idea_from(draft);
+        let out = MockBackend
+            .mutate(&idea, MutationType::Monetization)
+            .unwrap();
+        assert_eq!(out.idea.facets.audience, idea.facets.audience);
+        assert_eq!(out.idea.facets.jtbd, idea.facets.jtbd);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_command_backend_reads_stage_output() {
+        let backend = CommandBackend {
+            command: r#"cat > /dev/null; echo '{"idea": {"title": "T", "summary": "S", "facets": {"audience": "a", "jtbd": "j", "differentiator": "d", "monetization": "m", "distribution": "di", "risks": "r"}}}'"#.to_string(),
+        };
+        let draft = &MockBackend.generate("Developer tools", 2, 0).unwrap().ideas;
+        let (a, b) = (idea_from(&draft[0]), idea_from(&draft[1]));
+
+        let out = backend.merge(&a, &b).unwrap();
+        assert_eq!(out.idea.title, "T");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_command_backend_rejects_schema_violations() {
+        let backend = CommandBackend {
+            command: r#"cat > /dev/null; echo '{"idea": {"title": "T"}}'"#.to_string(),
+        };
+        let draft = &MockBackend.generate("Developer tools", 2, 0).unwrap().ideas;
+        let (a, b) = (idea_from(&draft[0]), idea_from(&draft[1]));
+
+        let err = backend.merge(&a, &b).unwrap_err().to_string();
+        assert!(err.contains("merger.output.schema.json"), "{}", err);
+        assert!(err.contains("/idea"), "{}", err);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_command_backend_reports_failed_command() {
+        let backend = CommandBackend {
+            command: "cat > /dev/null; echo boom >&2; exit 3".to_string(),
+        };
+        let err = format!("{:#}", backend.generate("x", 1, 0).unwrap_err());
+        assert!(err.contains("boom"), "{}", err);
+    }
+
+    #[test]
+    fn test_http_backend_against_local_server() {
+        use std::io::{BufRead, BufReader, Read};
+        use std::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let content = serde_json::to_string(&mock_generate("Developer tools", 1, 0)).unwrap();
+
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim().is_empty() {
+                    break;
+                }
+                if let Some(v) = line.to_lowercase().strip_prefix("content-length:") {
+                    content_length = v.trim().parse().unwrap();
+                }
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+
+            let response = serde_json::json!({
+                "choices": [{ "message": { "role": "assistant", "content": content } }]
+            })
+            .to_string();
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                response.len(),
+                response
+            )
+            .unwrap();
+
+            (
+                request_line,
+                serde_json::from_slice::<serde_json::Value>(&body).unwrap(),
+            )
+        });
+
+        let backend = HttpBackend::new(&format!("http://{}/v1", addr), Some("local".to_string()));
+        let out = backend.generate("Developer tools", 1, 0).unwrap();
+        assert_eq!(out.ideas.len(), 1);
+
+        let (request_line, body) = server.join().unwrap();
+        assert!(request_line.starts_with("POST /v1/chat/completions"));
+        assert_eq!(body["model"], "local");
+        assert_eq!(body["response_format"]["json_schema"]["name"], "generator");
+        assert_eq!(body["response_format"]["json_schema"]["strict"], false);
+    }
+
+    #[test]
+    fn test_parse_json_response_strips_code_fence() {
+        let value = parse_json_response("```json\n{\"a\": 1}\n```").unwrap();
+        assert_eq!(value["a"], 1);
+    }
+}
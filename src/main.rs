@@ -2,29 +2,130 @@ use anyhow::Result;
 use clap::{Parser, Subcommand};
 use tracing_subscriber::EnvFilter;
 
+mod compare;
 mod config;
 mod data;
+mod diff;
 #[cfg(test)]
 mod discovery;
+mod evolution;
+mod lineage;
+mod llm;
 mod orchestrator;
+mod plot;
+mod ranking;
+mod replay;
+mod schema;
 mod scoring;
+mod search;
+mod similarity;
+#[cfg(feature = "sqlite")]
+mod sqlite_storage;
+mod stats;
 mod storage;
+mod tree;
+mod tui;
+mod validation;
+
+/// Commands that can read runs from a SQLite database with --db
+const DB_COMMANDS: &str =
+    "list, show, tree, lineage, diff, search, dedup, compare, stats, plot, replay";
 
 #[derive(Parser)]
 #[command(name = "evoidea")]
-#[command(about = "Evoidea CLI - utilities for viewing and validating evolution runs", long_about = None)]
+#[command(about = "Evoidea CLI - run, view and validate evolution runs", long_about = None)]
 struct Cli {
+    /// Directory holding runs (default: EVOIDEA_RUNS_DIR, then runs_dir in .evoidea.json, then ./runs)
+    #[arg(long, global = true)]
+    runs_dir: Option<String>,
+
+    /// SQLite database to read runs from instead of the runs directory
+    /// (read commands), or to import into (import-sqlite)
+    #[cfg(feature = "sqlite")]
+    #[arg(long, global = true)]
+    db: Option<String>,
+
     #[command(subcommand)]
     command: Commands,
 }
 
 #[derive(Subcommand)]
 enum Commands {
+    /// Run a full evolution loop (generate, critique, select, crossover, mutate, refine)
+    Run {
+        /// Prompt describing the direction to explore
+        #[arg(long)]
+        prompt: String,
+
+        /// Maximum number of rounds
+        #[arg(long, default_value_t = 6)]
+        max_rounds: u32,
+
+        /// Target number of active ideas per round
+        #[arg(long, default_value_t = 12)]
+        population: u32,
+
+        /// Number of top ideas kept (and refined) each round
+        #[arg(long, default_value_t = 4)]
+        elite: u32,
+
+        /// Number of mutated ideas added each round
+        #[arg(long, default_value_t = 4)]
+        mutations: u32,
+
+        /// Number of crossover ideas added each round
+        #[arg(long, default_value_t = 4)]
+        crossovers: u32,
+
+        /// Stop once the best overall score reaches this value
+        #[arg(long, default_value_t = 8.7)]
+        threshold: f32,
+
+        /// Stop after this many rounds without improvement
+        #[arg(long, default_value_t = 2)]
+        stagnation: u32,
+
+        /// Eliminate ideas that mention any of these terms (comma-separated)
+        #[arg(long, value_delimiter = ',')]
+        forbidden: Vec<String>,
+
+        /// Eliminate ideas that do not mention all of these terms (comma-separated)
+        #[arg(long, value_delimiter = ',')]
+        must_include: Vec<String>,
+
+        /// LLM backend (mock, command or http)
+        #[arg(long, default_value = "mock")]
+        mode: String,
+
+        /// Executable that reads a stage request on stdin and prints JSON (command mode)
+        #[arg(long)]
+        llm_command: Option<String>,
+
+        /// Base URL of an OpenAI-compatible API, e.g. http://localhost:8080/v1 (http mode)
+        #[arg(long)]
+        llm_url: Option<String>,
+
+        /// Model name sent to the HTTP backend
+        #[arg(long)]
+        model: Option<String>,
+    },
+
+    /// Continue an interrupted or finished run
+    Resume {
+        /// Run ID to resume
+        #[arg(long)]
+        run_id: String,
+
+        /// New round limit: absolute (8) or relative to completed rounds (+2)
+        #[arg(long)]
+        max_rounds: Option<String>,
+    },
+
     /// List all runs
     List {
-        /// Output directory containing runs
-        #[arg(long, default_value = "runs")]
-        dir: String,
+        /// Directory containing runs (same as --runs-dir)
+        #[arg(long)]
+        dir: Option<String>,
     },
 
     /// Show run results
@@ -36,13 +137,25 @@ enum Commands {
         /// Output format (json or md)
         #[arg(long, default_value = "json")]
         format: String,
+
+        /// Show a snapshot replayed from history.ndjson at this iteration
+        #[arg(long)]
+        iteration: Option<u32>,
     },
 
     /// Validate run artifacts
     Validate {
         /// Run ID to validate
+        #[arg(long, required_unless_present = "all", conflicts_with = "all")]
+        run_id: Option<String>,
+
+        /// Validate every run under the runs directory
         #[arg(long)]
-        run_id: String,
+        all: bool,
+
+        /// Output format (text or json)
+        #[arg(long, default_value = "text")]
+        format: String,
     },
 
     /// Export run results in various formats
@@ -54,6 +167,10 @@ enum Commands {
         /// Export preset (landing, decision-log, stakeholder-brief, changelog-entry)
         #[arg(long, default_value = "landing")]
         preset: String,
+
+        /// Export a snapshot replayed from history.ndjson at this iteration
+        #[arg(long)]
+        iteration: Option<u32>,
     },
 
     /// Interactive tournament mode for preference learning
@@ -66,13 +183,25 @@ enum Commands {
         #[arg(long)]
         auto: bool,
 
-        /// Use pairwise comparison mode (A/B choices, ~2n comparisons)
+        /// Use pairwise comparison mode (asks the most informative A/B pair)
         #[arg(long)]
         pairwise: bool,
 
         /// Ask for an optional rationale after each choice
         #[arg(long)]
         rationale: bool,
+
+        /// Pairwise mode stops once this many leading ideas are settled
+        #[arg(long, default_value_t = 3)]
+        top_k: usize,
+
+        /// Full-screen view with both ideas side by side and a live leaderboard
+        #[arg(long, conflicts_with = "auto")]
+        tui: bool,
+
+        /// Hide scores and Elo while judging and assign A and B at random
+        #[arg(long, conflicts_with = "auto")]
+        blind: bool,
     },
 
     /// Manage preference profiles for scoring calibration
@@ -87,9 +216,152 @@ enum Commands {
         #[arg(long)]
         run_id: String,
 
-        /// Output format (ascii or mermaid)
+        /// Output format (ascii, mermaid, dot or svg)
         #[arg(long, default_value = "ascii")]
         format: String,
+
+        /// Render the tree replayed from history.ndjson at this iteration
+        #[arg(long)]
+        iteration: Option<u32>,
+    },
+
+    /// Trace the ancestors and descendants of an idea, or the path to the best idea
+    Lineage {
+        /// Run ID to inspect
+        #[arg(long)]
+        run_id: String,
+
+        /// Idea to trace
+        #[arg(long, required_unless_present = "winner_path")]
+        idea: Option<String>,
+
+        /// Only show the ideas this idea descends from
+        #[arg(long, conflicts_with = "winner_path")]
+        ancestors: bool,
+
+        /// Only show the ideas derived from this idea
+        #[arg(long, conflicts_with = "winner_path")]
+        descendants: bool,
+
+        /// Show each step that produced the best idea, with score deltas and facet changes
+        #[arg(long, conflicts_with = "idea")]
+        winner_path: bool,
+    },
+
+    /// Word-level diff of an idea against its parents or another idea
+    Diff {
+        /// Run ID to inspect
+        #[arg(long)]
+        run_id: String,
+
+        /// Idea to diff
+        #[arg(long)]
+        idea: String,
+
+        /// Idea to compare with (default: each of the idea's parents)
+        #[arg(long)]
+        against: Option<String>,
+
+        /// Output format (text or markdown)
+        #[arg(long, default_value = "text")]
+        format: String,
+    },
+
+    /// Search ideas across all runs (BM25 over title, summary and facets)
+    Search {
+        /// Words to look for
+        query: String,
+
+        /// Restrict matching to one field: title, summary, facets or facets.<name>
+        #[arg(long)]
+        field: Option<String>,
+
+        /// Only ideas with at least this overall score
+        #[arg(long)]
+        min_score: Option<f32>,
+
+        /// Only ideas with this status (active, archived or eliminated)
+        #[arg(long)]
+        status: Option<String>,
+
+        /// Maximum number of hits to print
+        #[arg(long, default_value_t = 10)]
+        limit: usize,
+    },
+
+    /// Cluster near-duplicate ideas within a run, or across all runs
+    Dedup {
+        /// Run ID to check (default: compare ideas across every run)
+        #[arg(long)]
+        run_id: Option<String>,
+
+        /// Cosine similarity (0-1) at which two ideas count as duplicates
+        #[arg(long, default_value_t = similarity::DEFAULT_THRESHOLD)]
+        threshold: f64,
+
+        /// Also cluster archived and eliminated ideas
+        #[arg(long)]
+        include_inactive: bool,
+    },
+
+    /// Compare configs, scores, idea overlap and winners of several runs
+    Compare {
+        /// Runs to compare (repeat the flag: --run-id A --run-id B)
+        #[arg(long, required = true)]
+        run_id: Vec<String>,
+
+        /// Cosine similarity (0-1) at which ideas of two runs count as overlapping
+        #[arg(long, default_value_t = similarity::DEFAULT_THRESHOLD)]
+        threshold: f64,
+
+        /// Output format (markdown or json)
+        #[arg(long, default_value = "markdown")]
+        format: String,
+    },
+
+    /// Per-iteration score, diversity and convergence metrics of a run
+    Stats {
+        /// Run ID to analyze
+        #[arg(long)]
+        run_id: String,
+
+        /// Output format (text or json)
+        #[arg(long, default_value = "text")]
+        format: String,
+    },
+
+    /// Chart best/mean score per iteration and the criteria of the winning lineage
+    Plot {
+        /// Run ID to plot
+        #[arg(long)]
+        run_id: String,
+
+        /// Draw with plain ASCII instead of Unicode block characters
+        #[arg(long)]
+        ascii: bool,
+    },
+
+    /// Rebuild the population at an iteration from history.ndjson
+    Replay {
+        /// Run ID to replay
+        #[arg(long)]
+        run_id: String,
+
+        /// Iteration to stop at (its events included)
+        #[arg(long)]
+        iteration: u32,
+
+        /// Output format (text or json)
+        #[arg(long, default_value = "text")]
+        format: String,
+    },
+
+    /// Copy run directories into a SQLite database
+    #[cfg(feature = "sqlite")]
+    ImportSqlite {
+        /// Import only this run (default: every run in the runs directory)
+        #[arg(long)]
+        run_id: Option<String>,
     },
 }
 
@@ -131,50 +403,256 @@ fn main() -> Result<()> {
         .init();
 
     let cli = Cli::parse();
+    // Resolved per command so `list --dir`/`--db` still work next to a broken .evoidea.json
+    let runs_dir = cli.runs_dir.clone();
+    #[cfg(feature = "sqlite")]
+    let db = cli.db.clone();
+    #[cfg(not(feature = "sqlite"))]
+    let db: Option<String> = None;
+    let run_files = || -> Result<storage::FileStorage> {
+        let runs_dir = config::resolve_runs_dir(runs_dir.as_deref())?;
+        Ok(storage::FileStorage::new(runs_dir))
+    };
+    // Commands that write artifacts or read run files directly
+    let files = || -> Result<storage::FileStorage> {
+        if db.is_some() {
+            anyhow::bail!("--db only works with read commands ({})", DB_COMMANDS);
+        }
+        run_files()
+    };
+    // Read commands, from the database with --db
+    let storage = || -> Result<Box<dyn storage::Storage>> {
+        #[cfg(feature = "sqlite")]
+        if let Some(db) = &db {
+            if !std::path::Path::new(db).exists() {
+                anyhow::bail!("Database not found: {}", db);
+            }
+            return Ok(Box::new(sqlite_storage::SqliteStorage::open(db)?));
+        }
+        Ok(Box::new(run_files()?))
+    };
 
     match cli.command {
-        Commands::List { dir } => {
-            tracing::info!(dir = %dir, "Listing runs");
-            orchestrator::list_runs(&dir)?;
+        Commands::Run {
+            prompt,
+            max_rounds,
+            population,
+            elite,
+            mutations,
+            crossovers,
+            threshold,
+            stagnation,
+            forbidden,
+            must_include,
+            mode,
+            llm_command,
+            llm_url,
+            model,
+        } => {
+            let storage = files()?;
+            let llm = config::LlmConfig {
+                mode: mode.parse()?,
+                command: llm_command,
+                url: llm_url,
+                model,
+            };
+            let config = config::RunConfig {
+                run_id: evolution::new_run_id(&storage)?,
+                prompt,
+                max_rounds,
+                population_size: population,
+                elite_count: elite,
+                mutation_count: mutations,
+                crossover_count: crossovers,
+                stagnation_patience: stagnation,
+                score_threshold: threshold,
+                scoring_weights: config::ScoringWeights::default(),
+                constraints: config::Constraints {
+                    forbidden,
+                    must_include,
+                },
+                llm,
+                created_at: Some(chrono::Utc::now().to_rfc3339()),
+            };
+            tracing::info!(run_id = %config.run_id, "Starting run");
+            evolution::run_evolution(config, &storage)?;
         }
-        Commands::Show { run_id, format } => {
-            tracing::info!(run_id = %run_id, format = %format, "Showing run");
-            orchestrator::show_run(&run_id, &format)?;
+        Commands::Resume { run_id, max_rounds } => {
+            let max_rounds = max_rounds.map(|m| m.parse()).transpose()?;
+            evolution::resume_evolution(&run_id, max_rounds, &files()?)?;
         }
-        Commands::Validate { run_id } => {
-            tracing::info!(run_id = %run_id, "Validating run");
-            orchestrator::validate_run(&run_id)?;
+        Commands::List { dir } => match (db.as_deref(), dir) {
+            (Some(_), Some(_)) => anyhow::bail!("list takes either --dir or --db, not both"),
+            (Some(db), None) => {
+                tracing::info!(db = %db, "Listing runs");
+                orchestrator::print_runs(&*storage()?, db)?;
+            }
+            (None, dir) => {
+                let storage = match dir {
+                    Some(dir) => storage::FileStorage::new(dir),
+                    None => files()?,
+                };
+                tracing::info!(dir = %storage.base_dir().display(), "Listing runs");
+                orchestrator::list_runs(&storage)?;
+            }
+        },
+        Commands::Show {
+            run_id,
+            format,
+            iteration,
+        } => {
+            tracing::info!(run_id = %run_id, format = %format, iteration = ?iteration, "Showing run");
+            orchestrator::show_run(&*storage()?, &run_id, &format, iteration)?;
+        }
+        Commands::Validate {
+            run_id,
+            all,
+            format,
+        } => {
+            let code = match run_id {
+                Some(run_id) => {
+                    tracing::info!(run_id = %run_id, format = %format, "Validating run");
+                    validation::validate_run(&files()?, &run_id, &format)?
+                }
+                None => {
+                    tracing::info!(all = %all, format = %format, "Validating all runs");
+                    validation::validate_all(&files()?, &format)?
+                }
+            };
+            if code != 0 {
+                std::process::exit(code);
+            }
         }
-        Commands::Export { run_id, preset } => {
-            tracing::info!(run_id = %run_id, preset = %preset, "Exporting run");
-            orchestrator::export_run(&run_id, &preset)?;
+        Commands::Export {
+            run_id,
+            preset,
+            iteration,
+        } => {
+            tracing::info!(run_id = %run_id, preset = %preset, iteration = ?iteration, "Exporting run");
+            orchestrator::export_run(&files()?, &run_id, &preset, iteration)?;
         }
         Commands::Tournament {
             run_id,
             auto,
             pairwise,
             rationale,
+            top_k,
+            tui,
+            blind,
         } => {
-            tracing::info!(run_id = %run_id, auto = %auto, pairwise = %pairwise, rationale = %rationale, "Running tournament");
-            orchestrator::tournament(&run_id, auto, pairwise, rationale)?;
+            tracing::info!(run_id = %run_id, auto = %auto, pairwise = %pairwise, rationale = %rationale, tui = %tui, blind = %blind, "Running tournament");
+            let options = orchestrator::TournamentOptions {
+                auto,
+                pairwise,
+                rationale,
+                top_k,
+                tui,
+                blind,
+            };
+            orchestrator::tournament(&files()?, &run_id, options)?;
         }
         Commands::Profile { action } => match action {
             ProfileAction::Export { run_id, output } => {
                 tracing::info!(run_id = %run_id, "Exporting profile");
-                orchestrator::profile_export(&run_id, output.as_deref())?;
+                orchestrator::profile_export(&files()?, &run_id, output.as_deref())?;
             }
             ProfileAction::Import { file, run_id } => {
                 tracing::info!(run_id = %run_id, file = %file, "Importing profile");
-                orchestrator::profile_import(&file, &run_id)?;
+                orchestrator::profile_import(&files()?, &file, &run_id)?;
             }
             ProfileAction::Show { run_id } => {
                 tracing::info!(run_id = %run_id, "Showing profile");
-                orchestrator::profile_show(&run_id)?;
+                orchestrator::profile_show(&files()?, &run_id)?;
             }
         },
-        Commands::Tree { run_id, format } => {
-            tracing::info!(run_id = %run_id, format = %format, "Rendering tree");
-            orchestrator::render_tree(&run_id, &format)?;
+        Commands::Tree {
+            run_id,
+            format,
+            iteration,
+        } => {
+            tracing::info!(run_id = %run_id, format = %format, iteration = ?iteration, "Rendering tree");
+            tree::render_tree(&*storage()?, &run_id, &format, iteration)?;
+        }
+        Commands::Lineage {
+            run_id,
+            idea,
+            ancestors,
+            descendants,
+            winner_path,
+        } => {
+            tracing::info!(run_id = %run_id, idea = ?idea, "Tracing lineage");
+            match idea {
+                Some(idea) if !winner_path => {
+                    lineage::print_lineage(&*storage()?, &run_id, &idea, ancestors, descendants)?
+                }
+                _ => lineage::print_winner_path(&*storage()?, &run_id)?,
+            }
+        }
+        Commands::Diff {
+            run_id,
+            idea,
+            against,
+            format,
+        } => {
+            tracing::info!(run_id = %run_id, idea = %idea, against = ?against, "Diffing idea");
+            diff::diff_ideas(&*storage()?, &run_id, &idea, against.as_deref(), &format)?;
+        }
+        Commands::Search {
+            query,
+            field,
+            min_score,
+            status,
+            limit,
+        } => {
+            tracing::info!(query = %query, field = ?field, "Searching runs");
+            search::search_runs(
+                &*storage()?,
+                &query,
+                field.as_deref(),
+                min_score,
+                status.as_deref(),
+                limit,
+            )?;
+        }
+        Commands::Dedup {
+            run_id,
+            threshold,
+            include_inactive,
+        } => {
+            tracing::info!(run_id = ?run_id, threshold = %threshold, "Finding near-duplicates");
+            similarity::dedup_runs(&*storage()?, run_id.as_deref(), threshold, include_inactive)?;
+        }
+        Commands::Compare {
+            run_id,
+            threshold,
+            format,
+        } => {
+            tracing::info!(runs = ?run_id, format = %format, "Comparing runs");
+            compare::compare_runs(&*storage()?, &run_id, threshold, &format)?;
+        }
+        Commands::Stats { run_id, format } => {
+            tracing::info!(run_id = %run_id, format = %format, "Computing stats");
+            stats::print_stats(&*storage()?, &run_id, &format)?;
+        }
+        Commands::Plot { run_id, ascii } => {
+            tracing::info!(run_id = %run_id, "Plotting run");
+            plot::plot_run(&*storage()?, &run_id, ascii)?;
+        }
+        Commands::Replay {
+            run_id,
+            iteration,
+            format,
+        } => {
+            tracing::info!(run_id = %run_id, iteration = %iteration, "Replaying run");
+            replay::replay_run(&*storage()?, &run_id, iteration, &format)?;
+        }
+        #[cfg(feature = "sqlite")]
+        Commands::ImportSqlite { run_id } => {
+            let Some(db) = db.clone() else {
+                anyhow::bail!("import-sqlite needs --db <path> (the database to create or extend)");
+            };
+            tracing::info!(db = %db, run_id = ?run_id, "Importing runs");
+            sqlite_storage::import_runs(&run_files()?, &db, run_id.as_deref())?;
         }
     }
 
@@ -1,66 +1,60 @@
 use anyhow::Result;
 use rand::seq::SliceRandom;
 use rand::SeedableRng;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
 use std::io::{self, Write};
-use std::path::PathBuf;
+use std::path::Path;
 
-/// List all runs in the given directory
-pub fn list_runs(dir: &str) -> Result<()> {
-    let runs_path = PathBuf::from(dir);
+use crate::config::RunConfig;
+use crate::data::{
+    join_violations, Comparison, FinalResult, Idea, IdeaStatus, Preferences, RunState,
+};
+use crate::ranking;
+use crate::replay;
+use crate::storage::{write_artifact, FileStorage, Storage};
+
+/// List all runs in the storage's runs directory
+pub fn list_runs(storage: &FileStorage) -> Result<()> {
+    let runs_path = storage.base_dir();
+    let dir = runs_path.display().to_string();
 
     if !runs_path.exists() {
         println!("No runs directory found at: {}", dir);
         return Ok(());
     }
 
+    print_runs(storage, &dir)
+}
+
+/// Print a status table of every run in `storage`, labelled with its `location`
+pub fn print_runs(storage: &dyn Storage, location: &str) -> Result<()> {
     let mut runs: Vec<(String, String, Option<f32>)> = Vec::new();
 
-    for entry in fs::read_dir(&runs_path)? {
-        let entry = entry?;
-        let path = entry.path();
-
-        if path.is_dir() {
-            let run_id = path
-                .file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or("unknown")
-                .to_string();
-
-            let final_path = path.join("final.json");
-            let state_path = path.join("state.json");
-
-            let (status, best_score) = if final_path.exists() {
-                // Try to parse final.json to get best score
-                let score = fs::read_to_string(&final_path)
-                    .ok()
-                    .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
-                    .and_then(|v| {
-                        v.get("best_idea")
-                            .and_then(|b| b.get("overall_score"))
-                            .and_then(|s| s.as_f64())
-                            .map(|s| s as f32)
-                    });
-                ("complete".to_string(), score)
-            } else if state_path.exists() {
-                ("in_progress".to_string(), None)
-            } else {
-                ("unknown".to_string(), None)
-            };
+    for run_id in storage.run_ids()? {
+        let (status, best_score) = if storage.has_final(&run_id)? {
+            match storage.load_final(&run_id) {
+                Ok(result) => ("complete".to_string(), result.best_idea.overall_score),
+                Err(_) => ("invalid".to_string(), None),
+            }
+        } else if storage.has_state(&run_id)? {
+            ("in_progress".to_string(), None)
+        } else {
+            ("unknown".to_string(), None)
+        };
 
-            runs.push((run_id, status, best_score));
-        }
+        runs.push((run_id, status, best_score));
     }
 
     if runs.is_empty() {
-        println!("No runs found in: {}", dir);
+        println!("No runs found in: {}", location);
         return Ok(());
     }
 
     // Sort by run_id (newest first, assuming timestamp-based IDs)
     runs.sort_by(|a, b| b.0.cmp(&a.0));
 
-    println!("Runs in {}:", dir);
+    println!("Runs in {}:", location);
     println!("{:<30} {:<12} BEST SCORE", "RUN ID", "STATUS");
     println!("{}", "-".repeat(55));
 
@@ -74,30 +68,27 @@ pub fn list_runs(dir: &str) -> Result<()> {
     Ok(())
 }
 
-/// Show run results
-pub fn show_run(run_id: &str, format: &str) -> Result<()> {
-    let final_path = PathBuf::from("runs").join(run_id).join("final.json");
-
-    if !final_path.exists() {
+/// Show run results, or a snapshot replayed from history at `iteration`
+pub fn show_run(
+    storage: &dyn Storage,
+    run_id: &str,
+    format: &str,
+    iteration: Option<u32>,
+) -> Result<()> {
+    if iteration.is_none() && !storage.has_final(run_id)? {
         // Check if run exists at all
-        let state_path = PathBuf::from("runs").join(run_id).join("state.json");
-        if state_path.exists() {
-            let content = fs::read_to_string(&state_path)?;
-            let state: serde_json::Value = serde_json::from_str(&content)?;
+        if storage.has_state(run_id)? {
+            let state = storage.load_state(run_id)?;
 
             println!("Run {} has not completed yet.", run_id);
-            if let Some(iteration) = state.get("iteration") {
-                println!("Current iteration: {}", iteration);
+            println!("Current iteration: {}", state.iteration);
+            println!("Active ideas: {}", state.active_ideas().count());
+            let eliminated = state.eliminated_ideas().count();
+            if eliminated > 0 {
+                println!("Eliminated ideas: {}", eliminated);
             }
-            if let Some(ideas) = state.get("ideas").and_then(|i| i.as_array()) {
-                let active = ideas
-                    .iter()
-                    .filter(|i| i.get("status").and_then(|s| s.as_str()) == Some("active"))
-                    .count();
-                println!("Active ideas: {}", active);
-            }
-            if let Some(best_score) = state.get("best_score") {
-                println!("Best score: {}", best_score);
+            if let Some(best_score) = state.best_score {
+                println!("Best score: {:.2}", best_score);
             }
             return Ok(());
         }
@@ -105,260 +96,125 @@ pub fn show_run(run_id: &str, format: &str) -> Result<()> {
         anyhow::bail!("Run {} not found", run_id);
     }
 
-    let content = fs::read_to_string(&final_path)?;
+    let (result, state) = load_result_view(storage, run_id, iteration)?;
 
     match format {
-        "json" => println!("{}", content),
         "md" => {
-            let result: serde_json::Value = serde_json::from_str(&content)?;
-
-            if let Some(best) = result.get("best_idea") {
-                let title = best
-                    .get("title")
-                    .and_then(|t| t.as_str())
-                    .unwrap_or("Unknown");
-                let summary = best.get("summary").and_then(|s| s.as_str()).unwrap_or("");
-                let score = best
-                    .get("overall_score")
-                    .and_then(|s| s.as_f64())
-                    .map(|s| format!("{:.2}", s))
-                    .unwrap_or_else(|| "-".to_string());
-
-                println!("# Best Idea: {}\n", title);
-                println!("**Score:** {}/10\n", score);
-                println!("{}\n", summary);
-
-                if let Some(facets) = best.get("facets") {
-                    println!("## Details\n");
-                    if let Some(audience) = facets.get("audience").and_then(|a| a.as_str()) {
-                        println!("**Audience:** {}", audience);
-                    }
-                    if let Some(jtbd) = facets.get("jtbd").and_then(|j| j.as_str()) {
-                        println!("**Problem:** {}", jtbd);
-                    }
-                    if let Some(diff) = facets.get("differentiator").and_then(|d| d.as_str()) {
-                        println!("**Unique:** {}", diff);
-                    }
-                    if let Some(mon) = facets.get("monetization").and_then(|m| m.as_str()) {
-                        println!("**Monetization:** {}", mon);
-                    }
-                    if let Some(dist) = facets.get("distribution").and_then(|d| d.as_str()) {
-                        println!("**Distribution:** {}", dist);
-                    }
-                    if let Some(risks) = facets.get("risks").and_then(|r| r.as_str()) {
-                        println!("**Risks:** {}", risks);
-                    }
+            let best = &result.best_idea;
+            let score = best
+                .overall_score
+                .map(|s| format!("{:.2}", s))
+                .unwrap_or_else(|| "-".to_string());
+
+            println!("# Best Idea: {}\n", best.title);
+            println!("**Score:** {}/10\n", score);
+            println!("{}\n", best.summary);
+
+            let facets = &best.facets;
+            println!("## Details\n");
+            println!("**Audience:** {}", facets.audience);
+            println!("**Problem:** {}", facets.jtbd);
+            println!("**Unique:** {}", facets.differentiator);
+            println!("**Monetization:** {}", facets.monetization);
+            println!("**Distribution:** {}", facets.distribution);
+            println!("**Risks:** {}", facets.risks);
+
+            if !result.why_won.is_empty() {
+                println!("\n## Why It Won\n");
+                for reason in &result.why_won {
+                    println!("- {}", reason);
                 }
             }
 
-            if let Some(runner_up) = result.get("runner_up") {
-                if !runner_up.is_null() {
-                    let title = runner_up
-                        .get("title")
-                        .and_then(|t| t.as_str())
-                        .unwrap_or("Unknown");
-                    println!("\n## Runner Up: {}", title);
-                }
+            if let Some(runner_up) = &result.runner_up {
+                println!("\n## Runner Up: {}", runner_up.title);
             }
-        }
-        _ => println!("{}", content),
-    }
-
-    Ok(())
-}
-
-/// Validate run artifacts
-pub fn validate_run(run_id: &str) -> Result<()> {
-    let run_dir = PathBuf::from("runs").join(run_id);
-
-    if !run_dir.exists() {
-        anyhow::bail!("Run directory not found: {}", run_id);
-    }
-
-    let mut errors = Vec::new();
-
-    // Validate config exists
-    let config_path = run_dir.join("config.json");
-    if config_path.exists() {
-        match fs::read_to_string(&config_path) {
-            Ok(content) => match serde_json::from_str::<serde_json::Value>(&content) {
-                Ok(config) => {
-                    let prompt = config.get("prompt").and_then(|p| p.as_str()).unwrap_or("?");
-                    let truncated = if prompt.len() > 30 {
-                        &prompt[..30]
-                    } else {
-                        prompt
-                    };
-                    println!("Config: OK (prompt: {}...)", truncated);
-                }
-                Err(e) => errors.push(format!("Config JSON invalid: {}", e)),
-            },
-            Err(e) => errors.push(format!("Config read error: {}", e)),
-        }
-    } else {
-        errors.push("Config: MISSING".to_string());
-    }
-
-    // Validate state
-    let state_path = run_dir.join("state.json");
-    if state_path.exists() {
-        match fs::read_to_string(&state_path) {
-            Ok(content) => match serde_json::from_str::<serde_json::Value>(&content) {
-                Ok(state) => {
-                    let iteration = state.get("iteration").and_then(|i| i.as_u64()).unwrap_or(0);
-                    let ideas_count = state
-                        .get("ideas")
-                        .and_then(|i| i.as_array())
-                        .map(|a| a.len())
-                        .unwrap_or(0);
-                    println!(
-                        "State: OK (iteration: {}, ideas: {})",
-                        iteration, ideas_count
-                    );
 
-                    errors.extend(validate_state_idea_invariants(&state));
+            if let Some(state) = &state {
+                let eliminated: Vec<&Idea> = state.eliminated_ideas().collect();
+                if !eliminated.is_empty() {
+                    println!("\n## Eliminated\n");
+                    for idea in eliminated {
+                        println!("- {} {}: {}", idea.id, idea.title, violations_of(idea));
+                    }
                 }
-                Err(e) => errors.push(format!("State JSON invalid: {}", e)),
-            },
-            Err(e) => errors.push(format!("State read error: {}", e)),
-        }
-    } else {
-        errors.push("State: MISSING".to_string());
-    }
-
-    // Validate history
-    let history_path = run_dir.join("history.ndjson");
-    if history_path.exists() {
-        match fs::read_to_string(&history_path) {
-            Ok(content) => {
-                let event_count = content.lines().count();
-                println!("History: OK ({} events)", event_count);
             }
-            Err(e) => errors.push(format!("History read error: {}", e)),
-        }
-    } else {
-        errors.push("History: MISSING".to_string());
-    }
-
-    // Validate final if exists
-    let final_path = run_dir.join("final.json");
-    if final_path.exists() {
-        match fs::read_to_string(&final_path) {
-            Ok(content) => match serde_json::from_str::<serde_json::Value>(&content) {
-                Ok(result) => {
-                    let title = result
-                        .get("best_idea")
-                        .and_then(|b| b.get("title"))
-                        .and_then(|t| t.as_str())
-                        .unwrap_or("?");
-                    println!("Final: OK (best: {})", title);
-                }
-                Err(e) => errors.push(format!("Final JSON invalid: {}", e)),
-            },
-            Err(e) => errors.push(format!("Final read error: {}", e)),
-        }
-    } else {
-        println!("Final: NOT YET (run in progress)");
-    }
-
-    // Report invariant errors
-    if errors.is_empty() {
-        println!("Invariants: OK");
-    } else {
-        println!("Errors: {} found", errors.len());
-        for err in &errors {
-            println!("  - {}", err);
         }
+        _ => println!("{}", serde_json::to_string_pretty(&result)?),
     }
 
     Ok(())
 }
 
-fn validate_state_idea_invariants(state: &serde_json::Value) -> Vec<String> {
-    let mut errors = Vec::new();
+/// final.json and state.json, or with `iteration` a snapshot replayed from
+/// history.ndjson summarized like final.json
+fn load_result_view(
+    storage: &dyn Storage,
+    run_id: &str,
+    iteration: Option<u32>,
+) -> Result<(FinalResult, Option<RunState>)> {
+    if let Some(iteration) = iteration {
+        let state = replay::load_snapshot(storage, run_id, iteration)?;
+        return Ok((replay::snapshot_result(&state)?, Some(state)));
+    }
 
-    let Some(ideas) = state.get("ideas").and_then(|i| i.as_array()) else {
-        return errors;
+    let result = storage.load_final(run_id)?;
+    let state = if storage.has_state(run_id)? {
+        Some(storage.load_state(run_id)?)
+    } else {
+        None
     };
+    Ok((result, state))
+}
 
-    for idea in ideas {
-        let id = idea.get("id").and_then(|i| i.as_str()).unwrap_or("?");
-        let origin = idea.get("origin").and_then(|o| o.as_str()).unwrap_or("?");
-        let parents = idea.get("parents").and_then(|p| p.as_array());
-        let has_parents = parents.map(|p| !p.is_empty()).unwrap_or(false);
-        let status = idea.get("status").and_then(|s| s.as_str()).unwrap_or("?");
-
-        match origin {
-            "generated" => {
-                if has_parents {
-                    errors.push(format!("Idea {} (generated) has parents", id));
-                }
-            }
-            "refined" | "crossover" | "mutated" => {
-                if !has_parents {
-                    errors.push(format!("Idea {} ({}) has no parents", id, origin));
-                }
-            }
-            _ => {}
-        }
-
-        // Active ideas should always be scored (tournament/profile export depends on it).
-        if status == "active" {
-            if extract_scores(idea).is_none() {
-                errors.push(format!("Idea {} (active) has missing/invalid scores", id));
-            }
-            if idea.get("overall_score").and_then(|s| s.as_f64()).is_none() {
-                errors.push(format!(
-                    "Idea {} (active) has missing/invalid overall_score",
-                    id
-                ));
-            }
+/// Violations of an eliminated idea, or "no recorded violations"
+fn violations_of(idea: &Idea) -> String {
+    match &idea.status {
+        IdeaStatus::Eliminated { violations } if !violations.is_empty() => {
+            join_violations(violations)
         }
+        _ => "no recorded violations".to_string(),
     }
-
-    errors
 }
 
 /// Export run results in various preset formats
-pub fn export_run(run_id: &str, preset: &str) -> Result<()> {
-    let run_dir = PathBuf::from("runs").join(run_id);
-    let final_path = run_dir.join("final.json");
-    let config_path = run_dir.join("config.json");
-    let state_path = run_dir.join("state.json");
-
-    if !final_path.exists() {
+pub fn export_run(
+    storage: &FileStorage,
+    run_id: &str,
+    preset: &str,
+    iteration: Option<u32>,
+) -> Result<()> {
+    if iteration.is_none() && !storage.has_final(run_id)? {
         anyhow::bail!("Run {} has no final.json (not completed yet)", run_id);
     }
 
-    let final_content = fs::read_to_string(&final_path)?;
-    let result: serde_json::Value = serde_json::from_str(&final_content)?;
+    let (result, state) = load_result_view(storage, run_id, iteration)?;
 
-    let config: Option<serde_json::Value> = if config_path.exists() {
-        Some(serde_json::from_str(&fs::read_to_string(&config_path)?)?)
+    let config: Option<RunConfig> = if storage.config_path(run_id).exists() {
+        Some(storage.load_config(run_id)?)
     } else {
         None
     };
 
-    let state: Option<serde_json::Value> = if state_path.exists() {
-        Some(serde_json::from_str(&fs::read_to_string(&state_path)?)?)
-    } else {
-        None
-    };
-
-    let (output, filename) = match preset {
-        "landing" => (generate_landing_page(&result, config.as_ref())?, "landing.md"),
-        "decision-log" => (generate_decision_log(&result, config.as_ref(), state.as_ref())?, "decision-log.md"),
-        "stakeholder-brief" => (generate_stakeholder_brief(&result, config.as_ref())?, "stakeholder-brief.md"),
-        "changelog-entry" => (generate_changelog_entry(&result, config.as_ref())?, "changelog-entry.md"),
+    let (output, stem) = match preset {
+        "landing" => (generate_landing_page(&result, config.as_ref()), "landing"),
+        "decision-log" => (generate_decision_log(&result, config.as_ref(), state.as_ref()), "decision-log"),
+        "stakeholder-brief" => (generate_stakeholder_brief(&result, config.as_ref()), "stakeholder-brief"),
+        "changelog-entry" => (generate_changelog_entry(&result, config.as_ref()), "changelog-entry"),
         _ => anyhow::bail!("Unknown preset: {} (supported: landing, decision-log, stakeholder-brief, changelog-entry)", preset),
     };
 
     // Create exports directory
-    let exports_dir = run_dir.join("exports");
+    let exports_dir = storage.run_dir(run_id).join("exports");
     fs::create_dir_all(&exports_dir)?;
 
+    // Snapshot exports never overwrite the export of the finished run
+    let filename = match iteration {
+        Some(n) => format!("{}.iteration-{}.md", stem, n),
+        None => format!("{}.md", stem),
+    };
     let output_path = exports_dir.join(filename);
-    fs::write(&output_path, &output)?;
+    write_artifact(&output_path, output.as_bytes())?;
 
     println!("Exported to: {}", output_path.display());
     println!();
@@ -367,59 +223,20 @@ pub fn export_run(run_id: &str, preset: &str) -> Result<()> {
     Ok(())
 }
 
-fn generate_landing_page(
-    result: &serde_json::Value,
-    config: Option<&serde_json::Value>,
-) -> Result<String> {
-    // Handle both "best_idea" and "best" formats
-    let best = result
-        .get("best_idea")
-        .or_else(|| result.get("best"))
-        .ok_or_else(|| anyhow::anyhow!("No best_idea or best in final.json"))?;
-
-    let title = best
-        .get("title")
-        .and_then(|t| t.as_str())
-        .unwrap_or("Unknown Product");
-    let summary = best.get("summary").and_then(|s| s.as_str()).unwrap_or("");
-    // Try multiple paths for score
+fn prompt_of(config: Option<&RunConfig>) -> &str {
+    config.map(|c| c.prompt.as_str()).unwrap_or("")
+}
+
+fn generate_landing_page(result: &FinalResult, config: Option<&RunConfig>) -> String {
+    let best = &result.best_idea;
+    let title = best.title.as_str();
+    let summary = best.summary.as_str();
     let score = best
-        .get("overall_score")
-        .or_else(|| best.get("scores").and_then(|s| s.get("overall")))
-        .and_then(|s| s.as_f64())
+        .overall_score
         .map(|s| format!("{:.1}", s))
         .unwrap_or_else(|| "N/A".to_string());
-
-    let facets = best.get("facets");
-    let audience = facets
-        .and_then(|f| f.get("audience"))
-        .and_then(|a| a.as_str())
-        .unwrap_or("");
-    let jtbd = facets
-        .and_then(|f| f.get("jtbd"))
-        .and_then(|j| j.as_str())
-        .unwrap_or("");
-    let differentiator = facets
-        .and_then(|f| f.get("differentiator"))
-        .and_then(|d| d.as_str())
-        .unwrap_or("");
-    let monetization = facets
-        .and_then(|f| f.get("monetization"))
-        .and_then(|m| m.as_str())
-        .unwrap_or("");
-    let distribution = facets
-        .and_then(|f| f.get("distribution"))
-        .and_then(|d| d.as_str())
-        .unwrap_or("");
-    let risks = facets
-        .and_then(|f| f.get("risks"))
-        .and_then(|r| r.as_str())
-        .unwrap_or("");
-
-    let prompt = config
-        .and_then(|c| c.get("prompt"))
-        .and_then(|p| p.as_str())
-        .unwrap_or("");
+    let facets = &best.facets;
+    let prompt = prompt_of(config);
 
     // Extract product name (first part before colon if present)
     let product_name = title.split(':').next().unwrap_or(title).trim();
@@ -435,8 +252,7 @@ fn generate_landing_page(
     // Header with metadata
     output.push_str(&format!(
         "<!-- Source: {} | Score: {}/10 -->\n",
-        run_id_from_result(result),
-        score
+        result.run_id, score
     ));
     if !prompt.is_empty() {
         output.push_str(&format!("<!-- Prompt: {} -->\n", prompt));
@@ -450,99 +266,52 @@ fn generate_landing_page(
 
     // Value proposition
     output.push_str("## The Problem\n\n");
-    output.push_str(&format!("{}\n\n", jtbd));
+    output.push_str(&format!("{}\n\n", facets.jtbd));
 
     // Benefits (3 key points)
     output.push_str("## Why Choose Us\n\n");
-    output.push_str(&format!("**1. Unique Approach:** {}\n\n", differentiator));
-    output.push_str(&format!("**2. Built For:** {}\n\n", audience));
-    output.push_str(&format!("**3. Clear Path to Value:** {}\n\n", distribution));
+    output.push_str(&format!(
+        "**1. Unique Approach:** {}\n\n",
+        facets.differentiator
+    ));
+    output.push_str(&format!("**2. Built For:** {}\n\n", facets.audience));
+    output.push_str(&format!(
+        "**3. Clear Path to Value:** {}\n\n",
+        facets.distribution
+    ));
 
     // CTA section
     output.push_str("## Get Started\n\n");
-    output.push_str(&format!("**Pricing:** {}\n\n", monetization));
+    output.push_str(&format!("**Pricing:** {}\n\n", facets.monetization));
     output.push_str("[Start Free Trial] [Book a Demo]\n\n");
 
     // Risk acknowledgment (shows transparency)
     output.push_str("## Our Commitment\n\n");
-    output.push_str(&format!("We know the challenges: {}\n\n", risks));
+    output.push_str(&format!("We know the challenges: {}\n\n", facets.risks));
     output.push_str("That's why we're committed to helping you succeed.\n\n");
 
     // Footer
     output.push_str("---\n");
     output.push_str(&format!("*Evolution Score: {}/10*\n", score));
 
-    Ok(output)
-}
-
-fn run_id_from_result(result: &serde_json::Value) -> &str {
-    result
-        .get("run_id")
-        .and_then(|r| r.as_str())
-        .unwrap_or("unknown")
+    output
 }
 
 /// Generate decision log format for engineering documentation
 fn generate_decision_log(
-    result: &serde_json::Value,
-    config: Option<&serde_json::Value>,
-    state: Option<&serde_json::Value>,
-) -> Result<String> {
-    let best = result
-        .get("best_idea")
-        .or_else(|| result.get("best"))
-        .ok_or_else(|| anyhow::anyhow!("No best_idea in final.json"))?;
-
-    let run_id = run_id_from_result(result);
-    let title = best
-        .get("title")
-        .and_then(|t| t.as_str())
-        .unwrap_or("Unknown");
-    let summary = best.get("summary").and_then(|s| s.as_str()).unwrap_or("");
-    let score = best
-        .get("overall_score")
-        .and_then(|s| s.as_f64())
-        .unwrap_or(0.0);
-
-    let facets = best.get("facets");
-    let audience = facets
-        .and_then(|f| f.get("audience"))
-        .and_then(|a| a.as_str())
-        .unwrap_or("");
-    let jtbd = facets
-        .and_then(|f| f.get("jtbd"))
-        .and_then(|j| j.as_str())
-        .unwrap_or("");
-    let differentiator = facets
-        .and_then(|f| f.get("differentiator"))
-        .and_then(|d| d.as_str())
-        .unwrap_or("");
-    let risks = facets
-        .and_then(|f| f.get("risks"))
-        .and_then(|r| r.as_str())
-        .unwrap_or("");
-
-    let prompt = config
-        .and_then(|c| c.get("prompt"))
-        .and_then(|p| p.as_str())
-        .unwrap_or("");
-    let iterations = result
-        .get("iterations_completed")
-        .and_then(|i| i.as_i64())
-        .unwrap_or(0);
-    let stop_reason = result
-        .get("stop_reason")
-        .and_then(|s| s.as_str())
-        .unwrap_or("");
+    result: &FinalResult,
+    config: Option<&RunConfig>,
+    state: Option<&RunState>,
+) -> String {
+    let best = &result.best_idea;
+    let run_id = result.run_id.as_str();
+    let title = best.title.as_str();
+    let score = best.overall_score.unwrap_or(0.0);
+    let facets = &best.facets;
+    let prompt = prompt_of(config);
 
     // Count alternatives considered
-    let alternatives_count = state
-        .and_then(|s| s.get("ideas"))
-        .and_then(|i| i.as_array())
-        .map(|a| a.len())
-        .unwrap_or(0);
-
-    let runner_up = result.get("runner_up");
+    let alternatives_count = state.map(|s| s.ideas.len()).unwrap_or(0);
 
     let mut output = String::new();
 
@@ -556,43 +325,59 @@ fn generate_decision_log(
 
     output.push_str("## Context\n\n");
     output.push_str(&format!("**Problem Statement:** {}\n\n", prompt));
-    output.push_str(&format!("**Target Audience:** {}\n\n", audience));
+    output.push_str(&format!("**Target Audience:** {}\n\n", facets.audience));
 
     output.push_str("## Decision\n\n");
     output.push_str(&format!("**Selected:** {}\n\n", title));
-    output.push_str(&format!("{}\n\n", summary));
+    output.push_str(&format!("{}\n\n", best.summary));
 
     output.push_str("## Rationale\n\n");
     output.push_str(&format!("- **Confidence Score:** {:.1}/10\n", score));
-    output.push_str(&format!("- **Key Differentiator:** {}\n", differentiator));
-    output.push_str(&format!("- **Problem Solved:** {}\n\n", jtbd));
+    output.push_str(&format!(
+        "- **Key Differentiator:** {}\n",
+        facets.differentiator
+    ));
+    output.push_str(&format!("- **Problem Solved:** {}\n", facets.jtbd));
+    for reason in &result.why_won {
+        output.push_str(&format!("- {}\n", reason));
+    }
+    output.push('\n');
 
     output.push_str("## Alternatives Considered\n\n");
     output.push_str(&format!(
         "- **Total evaluated:** {} ideas over {} iterations\n",
-        alternatives_count, iterations
+        alternatives_count, result.iterations_completed
     ));
 
-    if let Some(runner) = runner_up {
-        let runner_title = runner
-            .get("title")
-            .and_then(|t| t.as_str())
-            .unwrap_or("Unknown");
-        let runner_score = runner
-            .get("overall_score")
-            .and_then(|s| s.as_f64())
-            .unwrap_or(0.0);
+    if let Some(runner) = &result.runner_up {
         output.push_str(&format!(
             "- **Runner-up:** {} ({:.1}/10)\n",
-            runner_title, runner_score
+            runner.title,
+            runner.overall_score.unwrap_or(0.0)
         ));
     }
 
     output.push_str("- **Selection method:** Evolutionary algorithm with scoring\n");
-    output.push_str(&format!("- **Stop reason:** {}\n\n", stop_reason));
+    output.push_str(&format!("- **Stop reason:** {}\n\n", result.stop_reason));
+
+    let eliminated: Vec<&Idea> = state
+        .map(|s| s.eliminated_ideas().collect())
+        .unwrap_or_default();
+    if !eliminated.is_empty() {
+        output.push_str("## Eliminated by Constraints\n\n");
+        for idea in eliminated {
+            output.push_str(&format!(
+                "- **{}** (`{}`): {}\n",
+                idea.title,
+                idea.id,
+                violations_of(idea)
+            ));
+        }
+        output.push('\n');
+    }
 
     output.push_str("## Risks & Mitigations\n\n");
-    output.push_str(&format!("{}\n\n", risks));
+    output.push_str(&format!("{}\n\n", facets.risks));
 
     output.push_str("---\n");
     output.push_str(&format!(
@@ -600,60 +385,16 @@ fn generate_decision_log(
         run_id, score
     ));
 
-    Ok(output)
+    output
 }
 
 /// Generate stakeholder brief for non-technical audiences
-fn generate_stakeholder_brief(
-    result: &serde_json::Value,
-    config: Option<&serde_json::Value>,
-) -> Result<String> {
-    let best = result
-        .get("best_idea")
-        .or_else(|| result.get("best"))
-        .ok_or_else(|| anyhow::anyhow!("No best_idea in final.json"))?;
-
-    let run_id = run_id_from_result(result);
-    let title = best
-        .get("title")
-        .and_then(|t| t.as_str())
-        .unwrap_or("Unknown");
-    let summary = best.get("summary").and_then(|s| s.as_str()).unwrap_or("");
-    let score = best
-        .get("overall_score")
-        .and_then(|s| s.as_f64())
-        .unwrap_or(0.0);
-
-    let facets = best.get("facets");
-    let audience = facets
-        .and_then(|f| f.get("audience"))
-        .and_then(|a| a.as_str())
-        .unwrap_or("");
-    let jtbd = facets
-        .and_then(|f| f.get("jtbd"))
-        .and_then(|j| j.as_str())
-        .unwrap_or("");
-    let differentiator = facets
-        .and_then(|f| f.get("differentiator"))
-        .and_then(|d| d.as_str())
-        .unwrap_or("");
-    let monetization = facets
-        .and_then(|f| f.get("monetization"))
-        .and_then(|m| m.as_str())
-        .unwrap_or("");
-    let distribution = facets
-        .and_then(|f| f.get("distribution"))
-        .and_then(|d| d.as_str())
-        .unwrap_or("");
-    let risks = facets
-        .and_then(|f| f.get("risks"))
-        .and_then(|r| r.as_str())
-        .unwrap_or("");
-
-    let prompt = config
-        .and_then(|c| c.get("prompt"))
-        .and_then(|p| p.as_str())
-        .unwrap_or("");
+fn generate_stakeholder_brief(result: &FinalResult, config: Option<&RunConfig>) -> String {
+    let best = &result.best_idea;
+    let title = best.title.as_str();
+    let score = best.overall_score.unwrap_or(0.0);
+    let facets = &best.facets;
+    let prompt = prompt_of(config);
 
     // Extract product name
     let product_name = title.split(':').next().unwrap_or(title).trim();
@@ -665,16 +406,19 @@ fn generate_stakeholder_brief(
     output.push_str("## The Opportunity\n\n");
     output.push_str(&format!("**Direction explored:** {}\n\n", prompt));
     output.push_str(&format!("**Recommended approach:** {}\n\n", title));
-    output.push_str(&format!("{}\n\n", summary));
+    output.push_str(&format!("{}\n\n", best.summary));
 
     output.push_str("## Key Points\n\n");
     output.push_str("| Aspect | Details |\n");
     output.push_str("|--------|----------|\n");
-    output.push_str(&format!("| Target Market | {} |\n", audience));
-    output.push_str(&format!("| Problem Solved | {} |\n", jtbd));
-    output.push_str(&format!("| Competitive Edge | {} |\n", differentiator));
-    output.push_str(&format!("| Revenue Model | {} |\n", monetization));
-    output.push_str(&format!("| Go-to-Market | {} |\n\n", distribution));
+    output.push_str(&format!("| Target Market | {} |\n", facets.audience));
+    output.push_str(&format!("| Problem Solved | {} |\n", facets.jtbd));
+    output.push_str(&format!(
+        "| Competitive Edge | {} |\n",
+        facets.differentiator
+    ));
+    output.push_str(&format!("| Revenue Model | {} |\n", facets.monetization));
+    output.push_str(&format!("| Go-to-Market | {} |\n\n", facets.distribution));
 
     output.push_str("## Confidence Assessment\n\n");
     let confidence_label = if score >= 7.0 {
@@ -691,7 +435,7 @@ fn generate_stakeholder_brief(
     output.push_str("This assessment is based on automated evaluation of feasibility, market potential, differentiation, and risk factors.\n\n");
 
     output.push_str("## Known Risks\n\n");
-    output.push_str(&format!("{}\n\n", risks));
+    output.push_str(&format!("{}\n\n", facets.risks));
 
     output.push_str("## Next Steps\n\n");
     output.push_str("1. Review and validate assumptions with domain experts\n");
@@ -701,51 +445,18 @@ fn generate_stakeholder_brief(
     output.push_str("---\n");
     output.push_str(&format!(
         "*Generated by evoidea | {} | Confidence: {:.1}/10*\n",
-        run_id, score
+        result.run_id, score
     ));
 
-    Ok(output)
+    output
 }
 
 /// Generate changelog entry format
-fn generate_changelog_entry(
-    result: &serde_json::Value,
-    config: Option<&serde_json::Value>,
-) -> Result<String> {
-    let best = result
-        .get("best_idea")
-        .or_else(|| result.get("best"))
-        .ok_or_else(|| anyhow::anyhow!("No best_idea in final.json"))?;
-
-    let run_id = run_id_from_result(result);
-    let title = best
-        .get("title")
-        .and_then(|t| t.as_str())
-        .unwrap_or("Unknown");
-    let summary = best.get("summary").and_then(|s| s.as_str()).unwrap_or("");
-    let score = best
-        .get("overall_score")
-        .and_then(|s| s.as_f64())
-        .unwrap_or(0.0);
-
-    let facets = best.get("facets");
-    let audience = facets
-        .and_then(|f| f.get("audience"))
-        .and_then(|a| a.as_str())
-        .unwrap_or("");
-    let jtbd = facets
-        .and_then(|f| f.get("jtbd"))
-        .and_then(|j| j.as_str())
-        .unwrap_or("");
-
-    let prompt = config
-        .and_then(|c| c.get("prompt"))
-        .and_then(|p| p.as_str())
-        .unwrap_or("");
-    let iterations = result
-        .get("iterations_completed")
-        .and_then(|i| i.as_i64())
-        .unwrap_or(0);
+fn generate_changelog_entry(result: &FinalResult, config: Option<&RunConfig>) -> String {
+    let best = &result.best_idea;
+    let title = best.title.as_str();
+    let score = best.overall_score.unwrap_or(0.0);
+    let prompt = prompt_of(config);
 
     // Extract product name
     let product_name = title.split(':').next().unwrap_or(title).trim();
@@ -759,52 +470,63 @@ fn generate_changelog_entry(
     output.push_str("### Added\n\n");
     output.push_str(&format!("- **New concept explored:** {}\n", title));
     output.push_str(&format!("- **Problem space:** {}\n", prompt));
-    output.push_str(&format!("- **Target users:** {}\n\n", audience));
+    output.push_str(&format!("- **Target users:** {}\n\n", best.facets.audience));
 
     output.push_str("### Details\n\n");
-    output.push_str(&format!("{}\n\n", summary));
-    output.push_str(&format!("**Core value:** {}\n\n", jtbd));
+    output.push_str(&format!("{}\n\n", best.summary));
+    output.push_str(&format!("**Core value:** {}\n\n", best.facets.jtbd));
 
     output.push_str("### Metrics\n\n");
     output.push_str(&format!("- Confidence score: {:.1}/10\n", score));
-    output.push_str(&format!("- Evolution iterations: {}\n", iterations));
-    output.push_str(&format!("- Run ID: `{}`\n\n", run_id));
+    output.push_str(&format!(
+        "- Evolution iterations: {}\n",
+        result.iterations_completed
+    ));
+    output.push_str(&format!("- Run ID: `{}`\n\n", result.run_id));
 
     output.push_str("---\n");
     output.push_str("*Entry generated by evoidea evolutionary ideation*\n");
 
-    Ok(output)
+    output
 }
 
-/// Interactive tournament mode for preference learning
-pub fn tournament(run_id: &str, auto: bool, pairwise: bool, rationale: bool) -> Result<()> {
-    let run_dir = PathBuf::from("runs").join(run_id);
-    let state_path = run_dir.join("state.json");
+/// How `tournament` asks its questions
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TournamentOptions {
+    /// Rank by score without asking anything
+    pub auto: bool,
+    /// Ask the most informative pair until the top `top_k` is settled
+    pub pairwise: bool,
+    /// Ask for an optional rationale after each choice
+    pub rationale: bool,
+    pub top_k: usize,
+    /// Full-screen view instead of line prompts
+    pub tui: bool,
+    /// Hide scores and Elo and assign A and B at random
+    pub blind: bool,
+}
 
-    if !state_path.exists() {
+/// Interactive tournament mode for preference learning
+pub fn tournament(storage: &FileStorage, run_id: &str, options: TournamentOptions) -> Result<()> {
+    let TournamentOptions {
+        auto,
+        pairwise,
+        rationale,
+        top_k,
+        tui,
+        blind,
+    } = options;
+
+    if !storage.state_path(run_id).exists() {
         anyhow::bail!("Run {} has no state.json", run_id);
     }
 
-    let state_content = fs::read_to_string(&state_path)?;
-    let state: serde_json::Value = serde_json::from_str(&state_content)?;
+    let state = storage.load_state(run_id)?;
 
     // Get all active ideas
-    let ideas = state
-        .get("ideas")
-        .and_then(|i| i.as_array())
-        .ok_or_else(|| anyhow::anyhow!("No ideas in state.json"))?;
-
-    let active_ideas: Vec<&serde_json::Value> = ideas
-        .iter()
-        .filter(|idea| {
-            idea.get("status")
-                .and_then(|s| s.as_str())
-                .map(|s| s == "active")
-                .unwrap_or(false)
-        })
-        .collect();
+    let active_ideas: Vec<&Idea> = state.active_ideas().collect();
 
-    let eligible_ideas: Vec<&serde_json::Value> = active_ideas
+    let eligible_ideas: Vec<&Idea> = active_ideas
         .iter()
         .copied()
         .filter(|idea| idea_has_complete_scores(idea))
@@ -832,360 +554,440 @@ pub fn tournament(run_id: &str, auto: bool, pairwise: bool, rationale: bool) ->
         // Auto mode: just show ranking by score
         println!("=== Auto Mode: Ranking by Score ===\n");
 
-        let mut ranked: Vec<(&serde_json::Value, f64)> = eligible_ideas
+        let mut ranked: Vec<(&Idea, f32)> = eligible_ideas
             .iter()
-            .map(|idea| {
-                let score = idea
-                    .get("overall_score")
-                    .and_then(|s| s.as_f64())
-                    .unwrap_or(0.0);
-                (*idea, score)
-            })
+            .map(|idea| (*idea, idea.overall_score.unwrap_or(0.0)))
             .collect();
 
         ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
 
         for (rank, (idea, score)) in ranked.iter().enumerate() {
-            let title = idea
-                .get("title")
-                .and_then(|t| t.as_str())
-                .unwrap_or("Unknown");
-            let short_title: String = title.chars().take(60).collect();
+            let short_title: String = idea.title.chars().take(60).collect();
             println!("{}. [{:.2}] {}", rank + 1, score, short_title);
         }
 
         return Ok(());
     }
 
-    // Interactive tournament mode
-    let preferences_path = run_dir.join("preferences.json");
-    let mut preferences: serde_json::Value = if preferences_path.exists() {
-        serde_json::from_str(&fs::read_to_string(&preferences_path)?)?
-    } else {
-        serde_json::json!({
-            "comparisons": [],
-            "elo_ratings": {}
-        })
-    };
+    // Interactive tournament mode; the lock keeps a second tournament from clobbering our comparisons
+    let _lock = storage.lock_run(run_id)?;
+    let preferences_path = storage.preferences_path(run_id);
+    let mut preferences = storage.load_preferences(run_id)?.unwrap_or_default();
 
     // Initialize Elo ratings if needed
-    {
-        let elo_ratings = preferences
-            .get_mut("elo_ratings")
-            .and_then(|e| e.as_object_mut())
-            .ok_or_else(|| anyhow::anyhow!("Invalid preferences format"))?;
-
-        for idea in &eligible_ideas {
-            let id = idea.get("id").and_then(|i| i.as_str()).unwrap_or("unknown");
-            if !elo_ratings.contains_key(id) {
-                elo_ratings.insert(id.to_string(), serde_json::json!(1000.0));
-            }
-        }
-    }
-
-    let mut comparison_count = 0;
-
-    if pairwise {
-        // Pairwise mode: smart sampling with ~2n comparisons
-        let max_comparisons = calculate_pairwise_limit(eligible_ideas.len());
+    for idea in &eligible_ideas {
+        preferences
+            .elo_ratings
+            .entry(idea.id.clone())
+            .or_insert(1000.0);
+    }
+
+    let mut comparison_count: usize = 0;
+    let mut session_start = preferences.comparisons.len();
+
+    if blind {
+        println!("Blind mode: scores and Elo stay hidden and A/B sides are assigned at random\n");
+    }
+
+    if tui {
+        // The full-screen view always offers a rationale key
+        let top_k = pairwise.then(|| top_k.clamp(1, eligible_ideas.len() - 1));
+        comparison_count = crate::tui::run_tournament(
+            storage,
+            run_id,
+            &state,
+            &eligible_ideas,
+            &mut preferences,
+            top_k,
+            blind,
+        )?;
+    } else if pairwise {
+        // Pairwise mode: ask for the most informative pair until the top k is settled
+        let k = top_k.clamp(1, eligible_ideas.len() - 1);
 
         println!("=== Pairwise Comparison Mode ===");
         println!(
-            "Smart sampling: up to {} comparisons (vs {} for exhaustive)",
-            max_comparisons,
+            "Adaptive sampling: stops once the top {} is settled with {:.0}% confidence (at most {} comparisons)",
+            k,
+            ranking::TOP_K_CONFIDENCE * 100.0,
             eligible_ideas.len() * (eligible_ideas.len() - 1) / 2
         );
-        println!("Pick your preference: [A] or [B]. [S] Skip | [Q] Quit");
+        println!("Pick your preference: [A] or [B]. {}", OTHER_ANSWERS);
         if rationale {
             println!("Then optionally add a rationale (free-text).");
         }
         println!();
 
-        // Build data structures for pair selection
-        let ids: Vec<String> = eligible_ideas
-            .iter()
-            .map(|idea| {
-                idea.get("id")
-                    .and_then(|i| i.as_str())
-                    .unwrap_or("unknown")
-                    .to_string()
-            })
-            .collect();
-
-        // Build compared set from existing comparisons
-        let mut compared: std::collections::HashSet<(String, String)> =
-            std::collections::HashSet::new();
-        if let Some(comps) = preferences.get("comparisons").and_then(|c| c.as_array()) {
-            for c in comps {
-                let id_a = c.get("idea_a").and_then(|a| a.as_str()).unwrap_or("");
-                let id_b = c.get("idea_b").and_then(|b| b.as_str()).unwrap_or("");
-                let pair_key = if id_a < id_b {
-                    (id_a.to_string(), id_b.to_string())
-                } else {
-                    (id_b.to_string(), id_a.to_string())
-                };
-                compared.insert(pair_key);
-            }
-        }
-
-        while comparison_count < max_comparisons {
-            // Get current Elo ratings
-            let elo_ratings: std::collections::HashMap<String, f64> = preferences
-                .get("elo_ratings")
-                .and_then(|e| e.as_object())
-                .map(|obj| {
-                    obj.iter()
-                        .filter_map(|(k, v)| v.as_f64().map(|r| (k.clone(), r)))
-                        .collect()
-                })
-                .unwrap_or_default();
-
-            // Select next pair
-            let pair = select_next_pair(&ids, &elo_ratings, &compared);
-            if pair.is_none() {
-                println!("All pairs compared!");
-                break;
-            }
+        // Skipped pairs wait until every other informative pair has been asked
+        let mut skipped: HashSet<(String, String)> = HashSet::new();
+
+        loop {
+            let excluded: HashSet<(String, String)> = compared_pairs(&preferences.comparisons)
+                .union(&skipped)
+                .cloned()
+                .collect();
+            let (id_a, id_b, confidence) = match next_pairwise_step(
+                &eligible_ideas,
+                &state,
+                &preferences.comparisons,
+                &excluded,
+                k,
+            ) {
+                PairwiseStep::Ask { pair, confidence } => {
+                    let (a, b) = present(pair, blind);
+                    (a, b, confidence)
+                }
+                PairwiseStep::Settled { top, confidence } => {
+                    println!(
+                        "Top {} settled with {:.0}% confidence: {}\n",
+                        k,
+                        confidence * 100.0,
+                        top.join(", ")
+                    );
+                    break;
+                }
+                PairwiseStep::Exhausted if !skipped.is_empty() => {
+                    println!("Back to the {} skipped pair(s)\n", skipped.len());
+                    skipped.clear();
+                    continue;
+                }
+                PairwiseStep::Exhausted => {
+                    println!("All pairs compared!");
+                    break;
+                }
+            };
 
-            let (id_a, id_b) = pair.unwrap();
+            let elo_ratings = &preferences.elo_ratings;
 
             // Find idea details
-            let idea_a = eligible_ideas
-                .iter()
-                .find(|idea| idea.get("id").and_then(|i| i.as_str()) == Some(&id_a))
-                .ok_or_else(|| anyhow::anyhow!("Idea {} not found", id_a))?;
-            let idea_b = eligible_ideas
-                .iter()
-                .find(|idea| idea.get("id").and_then(|i| i.as_str()) == Some(&id_b))
-                .ok_or_else(|| anyhow::anyhow!("Idea {} not found", id_b))?;
-
-            let title_a = idea_a
-                .get("title")
-                .and_then(|t| t.as_str())
-                .unwrap_or("Unknown");
-            let title_b = idea_b
-                .get("title")
-                .and_then(|t| t.as_str())
-                .unwrap_or("Unknown");
+            let idea_a = find_idea(&eligible_ideas, &id_a)?;
+            let idea_b = find_idea(&eligible_ideas, &id_b)?;
+
             let elo_a = elo_ratings.get(&id_a).unwrap_or(&1000.0);
             let elo_b = elo_ratings.get(&id_b).unwrap_or(&1000.0);
 
-            println!(
-                "--- Comparison {}/{} ---",
-                comparison_count + 1,
-                max_comparisons
-            );
-            println!();
-            println!("[A] {} (Elo: {:.0})", title_a, elo_a);
+            // The confidence comes from the fitted ratings, so blind mode hides it
+            if blind {
+                println!("--- Comparison {} ---", comparison_count + 1);
+            } else {
+                println!(
+                    "--- Comparison {} (top {} confidence: {:.0}%) ---",
+                    comparison_count + 1,
+                    k,
+                    confidence * 100.0
+                );
+            }
             println!();
-            println!("[B] {} (Elo: {:.0})", title_b, elo_b);
+            if blind {
+                println!("[A] {}", idea_a.title);
+                println!();
+                println!("[B] {}", idea_b.title);
+            } else {
+                println!("[A] {} (Elo: {:.0})", idea_a.title, elo_a);
+                println!();
+                println!("[B] {} (Elo: {:.0})", idea_b.title, elo_b);
+            }
             println!();
-            print!("Which is better? [A/B/S/Q]: ");
+            print!("Which is better? [A/B/T/N/S/U/Q]: ");
             io::stdout().flush()?;
 
-            let mut input = String::new();
-            io::stdin().read_line(&mut input)?;
-            let choice = input.trim().to_uppercase();
-
-            // Mark as compared regardless of choice
-            let pair_key = if id_a < id_b {
-                (id_a.clone(), id_b.clone())
-            } else {
-                (id_b.clone(), id_a.clone())
-            };
-
-            match choice.as_str() {
-                "A" => {
-                    compared.insert(pair_key);
-                    let r = read_optional_rationale(rationale)?;
-                    append_comparison(&mut preferences, &id_a, &id_b, &id_a, r.as_deref())?;
-                    update_elo(&mut preferences, &id_a, &id_b)?;
-                    comparison_count += 1;
-                    println!("-> {} wins\n", title_a.chars().take(40).collect::<String>());
+            match read_answer(&mut preferences, idea_a, idea_b, rationale, blind)? {
+                Answer::Recorded => comparison_count += 1,
+                Answer::Skipped => {
+                    skipped.insert(pair_key(&id_a, &id_b));
                 }
-                "B" => {
-                    compared.insert(pair_key);
-                    let r = read_optional_rationale(rationale)?;
-                    append_comparison(&mut preferences, &id_a, &id_b, &id_b, r.as_deref())?;
-                    update_elo(&mut preferences, &id_b, &id_a)?;
-                    comparison_count += 1;
-                    println!("-> {} wins\n", title_b.chars().take(40).collect::<String>());
-                }
-                "S" => {
-                    compared.insert(pair_key);
-                    println!("Skipped\n");
-                }
-                "Q" => {
-                    println!("Quitting tournament...\n");
-                    break;
-                }
-                _ => {
-                    println!("Invalid choice, try again\n");
-                    continue;
+                Answer::Undone(Some(_)) => {
+                    if undid_session_answer(&preferences, &mut session_start) {
+                        comparison_count -= 1;
+                    }
                 }
+                Answer::Undone(None) => {}
+                Answer::Quit => break,
+                Answer::Invalid => continue,
             }
 
             // Save after each comparison
-            fs::write(
-                &preferences_path,
-                serde_json::to_string_pretty(&preferences)?,
-            )?;
+            storage.save_preferences(run_id, &preferences)?;
         }
     } else {
         // Original exhaustive mode: compare all pairs
-        let mut pairs: Vec<(usize, usize)> = Vec::new();
-        for i in 0..eligible_ideas.len() {
-            for j in (i + 1)..eligible_ideas.len() {
-                pairs.push((i, j));
-            }
-        }
-
         println!("=== Interactive Tournament ===");
         println!("Compare ideas and pick your preference.");
-        println!("Commands: [A] Choose A | [B] Choose B | [S] Skip | [Q] Quit\n");
-
-        for (i, j) in pairs {
-            let idea_a = eligible_ideas[i];
-            let idea_b = eligible_ideas[j];
-
-            let id_a = idea_a
-                .get("id")
-                .and_then(|i| i.as_str())
-                .unwrap_or("unknown")
-                .to_string();
-            let id_b = idea_b
-                .get("id")
-                .and_then(|i| i.as_str())
-                .unwrap_or("unknown")
-                .to_string();
-
-            // Check if we've already compared these
-            let already_compared = {
-                let comparisons = preferences
-                    .get("comparisons")
-                    .and_then(|c| c.as_array())
-                    .ok_or_else(|| anyhow::anyhow!("Invalid preferences format"))?;
-                comparisons.iter().any(|c| {
-                    let ca = c.get("idea_a").and_then(|a| a.as_str());
-                    let cb = c.get("idea_b").and_then(|b| b.as_str());
-                    (ca == Some(&id_a) && cb == Some(&id_b))
-                        || (ca == Some(&id_b) && cb == Some(&id_a))
-                })
-            };
+        println!(
+            "Commands: [A] Choose A | [B] Choose B | {}\n",
+            OTHER_ANSWERS
+        );
 
-            if already_compared {
-                continue;
-            }
+        let mut skipped: HashSet<(String, String)> = HashSet::new();
 
-            let title_a = idea_a
-                .get("title")
-                .and_then(|t| t.as_str())
-                .unwrap_or("Unknown");
-            let title_b = idea_b
-                .get("title")
-                .and_then(|t| t.as_str())
-                .unwrap_or("Unknown");
-            let score_a = idea_a
-                .get("overall_score")
-                .and_then(|s| s.as_f64())
-                .unwrap_or(0.0);
-            let score_b = idea_b
-                .get("overall_score")
-                .and_then(|s| s.as_f64())
-                .unwrap_or(0.0);
+        while let Some((id_a, id_b)) =
+            next_unanswered_pair(&eligible_ideas, &preferences.comparisons, &skipped)
+                .map(|pair| present(pair, blind))
+        {
+            let idea_a = find_idea(&eligible_ideas, &id_a)?;
+            let idea_b = find_idea(&eligible_ideas, &id_b)?;
 
             println!("--- Comparison {} ---", comparison_count + 1);
             println!();
-            println!("[A] {} (score: {:.2})", title_a, score_a);
-            println!();
-            println!("[B] {} (score: {:.2})", title_b, score_b);
+            if blind {
+                println!("[A] {}", idea_a.title);
+                println!();
+                println!("[B] {}", idea_b.title);
+            } else {
+                println!(
+                    "[A] {} (score: {:.2})",
+                    idea_a.title,
+                    idea_a.overall_score.unwrap_or(0.0)
+                );
+                println!();
+                println!(
+                    "[B] {} (score: {:.2})",
+                    idea_b.title,
+                    idea_b.overall_score.unwrap_or(0.0)
+                );
+            }
             println!();
-            print!("Your choice [A/B/S/Q]: ");
+            print!("Your choice [A/B/T/N/S/U/Q]: ");
             io::stdout().flush()?;
 
-            let mut input = String::new();
-            io::stdin().read_line(&mut input)?;
-            let choice = input.trim().to_uppercase();
-
-            match choice.as_str() {
-                "A" => {
-                    let r = read_optional_rationale(rationale)?;
-                    append_comparison(&mut preferences, &id_a, &id_b, &id_a, r.as_deref())?;
-                    update_elo(&mut preferences, &id_a, &id_b)?;
-                    comparison_count += 1;
-                    println!(
-                        "Recorded: {} wins\n",
-                        title_a.chars().take(40).collect::<String>()
-                    );
-                }
-                "B" => {
-                    let r = read_optional_rationale(rationale)?;
-                    append_comparison(&mut preferences, &id_a, &id_b, &id_b, r.as_deref())?;
-                    update_elo(&mut preferences, &id_b, &id_a)?;
-                    comparison_count += 1;
-                    println!(
-                        "Recorded: {} wins\n",
-                        title_b.chars().take(40).collect::<String>()
-                    );
+            match read_answer(&mut preferences, idea_a, idea_b, rationale, blind)? {
+                Answer::Recorded => comparison_count += 1,
+                Answer::Skipped => {
+                    skipped.insert(pair_key(&id_a, &id_b));
                 }
-                "S" => {
-                    println!("Skipped\n");
-                }
-                "Q" => {
-                    println!("Quitting tournament...\n");
-                    break;
-                }
-                _ => {
-                    println!("Invalid choice, skipping\n");
+                Answer::Undone(Some(_)) => {
+                    if undid_session_answer(&preferences, &mut session_start) {
+                        comparison_count -= 1;
+                    }
                 }
+                Answer::Undone(None) => {}
+                Answer::Quit => break,
+                Answer::Invalid => continue,
             }
 
             // Save after each comparison
-            fs::write(
-                &preferences_path,
-                serde_json::to_string_pretty(&preferences)?,
-            )?;
+            storage.save_preferences(run_id, &preferences)?;
         }
     }
 
     // Show final rankings
     println!("=== Current Rankings (by Elo) ===\n");
 
-    let elo_ratings = preferences
-        .get("elo_ratings")
-        .and_then(|e| e.as_object())
-        .ok_or_else(|| anyhow::anyhow!("Invalid preferences format"))?;
-
-    let eligible_ids: std::collections::HashSet<&str> = eligible_ideas
-        .iter()
-        .filter_map(|idea| idea.get("id").and_then(|i| i.as_str()))
-        .collect();
-
-    let mut ranked: Vec<(&str, f64)> = elo_ratings
+    let mut ranked: Vec<(&Idea, f64)> = eligible_ideas
         .iter()
-        .filter(|(id, _)| eligible_ids.contains(id.as_str()))
-        .filter_map(|(id, rating)| rating.as_f64().map(|r| (id.as_str(), r)))
+        .filter_map(|idea| {
+            preferences
+                .elo_ratings
+                .get(&idea.id)
+                .map(|elo| (*idea, *elo))
+        })
         .collect();
 
     ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
 
-    for (rank, (id, elo)) in ranked.iter().enumerate() {
-        // Find the idea title
-        let title = eligible_ideas
-            .iter()
-            .find(|idea| idea.get("id").and_then(|i| i.as_str()) == Some(*id))
-            .and_then(|idea| idea.get("title").and_then(|t| t.as_str()))
-            .unwrap_or("Unknown");
-        let short_title: String = title.chars().take(50).collect();
-        println!("{}. [Elo: {:.0}] {}", rank + 1, elo, short_title);
+    let rejected = preferences.rejected();
+    for (rank, (idea, elo)) in ranked.iter().enumerate() {
+        let short_title: String = idea.title.chars().take(50).collect();
+        let flag = if rejected.contains(idea.id.as_str()) {
+            " (rejected)"
+        } else {
+            ""
+        };
+        println!("{}. [Elo: {:.0}] {}{}", rank + 1, elo, short_title, flag);
     }
 
+    // Elo depends on the order of the comparisons; the batch fit does not
+    let fit = ranking::fit(&preferences.comparisons);
+    println!(
+        "\n=== Bradley-Terry Ratings ({} comparisons, rating ± standard error) ===\n",
+        fit.comparisons
+    );
+    ranking::print_fit(&fit, |id| match state.idea(id) {
+        Some(idea) => idea.title.chars().take(50).collect(),
+        None => id.to_string(),
+    });
+
     println!("\nPreferences saved to: {}", preferences_path.display());
     println!("Comparisons made: {}", comparison_count);
 
     Ok(())
 }
 
+/// Order-independent key for a compared pair
+pub(crate) fn pair_key(a: &str, b: &str) -> (String, String) {
+    if a < b {
+        (a.to_string(), b.to_string())
+    } else {
+        (b.to_string(), a.to_string())
+    }
+}
+
+/// Keys of every pair that already has a recorded answer
+pub(crate) fn compared_pairs(comparisons: &[Comparison]) -> HashSet<(String, String)> {
+    comparisons
+        .iter()
+        .map(|c| pair_key(&c.idea_a, &c.idea_b))
+        .collect()
+}
+
+/// First pair in idea order that has no answer and was not skipped; an undo
+/// puts its pair back in line
+pub(crate) fn next_unanswered_pair(
+    ideas: &[&Idea],
+    comparisons: &[Comparison],
+    skipped: &HashSet<(String, String)>,
+) -> Option<(String, String)> {
+    let compared = compared_pairs(comparisons);
+    ideas.iter().enumerate().find_map(|(i, a)| {
+        ideas[i + 1..].iter().find_map(|b| {
+            let key = pair_key(&a.id, &b.id);
+            (!compared.contains(&key) && !skipped.contains(&key))
+                .then(|| (a.id.clone(), b.id.clone()))
+        })
+    })
+}
+
+/// Prompt keys shared by both tournament modes besides A and B
+const OTHER_ANSWERS: &str = "[T] Tie | [N] Neither acceptable | [S] Skip | [U] Undo | [Q] Quit";
+
+/// Outcome of one tournament prompt
+#[derive(Debug, PartialEq)]
+enum Answer {
+    /// A win, tie or "neither" was recorded
+    Recorded,
+    Skipped,
+    /// The last comparison was removed, if there was one
+    Undone(Option<Comparison>),
+    Quit,
+    Invalid,
+}
+
+/// Read the user's answer for a pair, apply it to `preferences` and say what
+/// happened. End of input counts as quitting.
+fn read_answer(
+    preferences: &mut Preferences,
+    idea_a: &Idea,
+    idea_b: &Idea,
+    rationale: bool,
+    blind: bool,
+) -> Result<Answer> {
+    let mut input = String::new();
+    if io::stdin().read_line(&mut input)? == 0 {
+        return Ok(Answer::Quit);
+    }
+    let short = |idea: &Idea| idea.title.chars().take(40).collect::<String>();
+
+    let (a, b) = (idea_a.id.as_str(), idea_b.id.as_str());
+    let (comparison, message) = match input.trim().to_uppercase().as_str() {
+        "A" => (
+            Comparison::decided(a, b, a),
+            format!("{} wins", short(idea_a)),
+        ),
+        "B" => (
+            Comparison::decided(a, b, b),
+            format!("{} wins", short(idea_b)),
+        ),
+        "T" => (Comparison::tie(a, b), "Tie".to_string()),
+        "N" => (
+            Comparison::neither(a, b),
+            "Neither acceptable; both ideas flagged".to_string(),
+        ),
+        "S" => {
+            println!("Skipped\n");
+            return Ok(Answer::Skipped);
+        }
+        "U" => {
+            let undone = undo_last(preferences);
+            match &undone {
+                Some(c) => println!(
+                    "Undid {} vs {} ({}); ratings recomputed\n",
+                    c.idea_a,
+                    c.idea_b,
+                    c.describe()
+                ),
+                None => println!("Nothing to undo\n"),
+            }
+            return Ok(Answer::Undone(undone));
+        }
+        "Q" => {
+            println!("Quitting tournament...\n");
+            return Ok(Answer::Quit);
+        }
+        _ => {
+            println!("Invalid choice, try again\n");
+            return Ok(Answer::Invalid);
+        }
+    };
+
+    let r = read_optional_rationale(rationale)?;
+    record_answer(preferences, comparison, r.as_deref(), blind);
+    println!("-> {}\n", message);
+    Ok(Answer::Recorded)
+}
+
+/// Append an answer for the pair as presented (A, B) and update the Elo ratings
+pub(crate) fn record_answer(
+    preferences: &mut Preferences,
+    comparison: Comparison,
+    rationale: Option<&str>,
+    blind: bool,
+) {
+    append_comparison(preferences, comparison, rationale);
+    if let Some(comparison) = preferences.comparisons.last_mut() {
+        comparison.blind = blind;
+        let comparison = comparison.clone();
+        apply_elo(preferences, &comparison);
+    }
+}
+
+/// Order in which a pair is presented as (A, B); blind mode flips a coin so
+/// that neither side is favoured by the order pairs are picked in
+pub(crate) fn present(pair: (String, String), blind: bool) -> (String, String) {
+    if blind && rand::random::<bool>() {
+        (pair.1, pair.0)
+    } else {
+        pair
+    }
+}
+
+/// Remove the most recent comparison and rebuild the Elo ratings without it
+pub(crate) fn undo_last(preferences: &mut Preferences) -> Option<Comparison> {
+    let undone = preferences.comparisons.pop()?;
+    recompute_elo(preferences);
+    Some(undone)
+}
+
+/// Whether the comparison just undone was recorded in this session, which
+/// began with `session_start` comparisons; an undo reaching into an earlier
+/// session moves `session_start` back instead
+pub(crate) fn undid_session_answer(preferences: &Preferences, session_start: &mut usize) -> bool {
+    let remaining = preferences.comparisons.len();
+    if remaining >= *session_start {
+        return true;
+    }
+    *session_start = remaining;
+    false
+}
+
+/// Replay every comparison from a fresh 1000 for each rated idea, so the
+/// ratings are as if removed comparisons had never happened
+fn recompute_elo(preferences: &mut Preferences) {
+    for elo in preferences.elo_ratings.values_mut() {
+        *elo = 1000.0;
+    }
+    for comparison in preferences.comparisons.clone() {
+        apply_elo(preferences, &comparison);
+    }
+}
+
+fn find_idea<'a>(ideas: &[&'a Idea], id: &str) -> Result<&'a Idea> {
+    ideas
+        .iter()
+        .find(|idea| idea.id == id)
+        .copied()
+        .ok_or_else(|| anyhow::anyhow!("Idea {} not found", id))
+}
+
 fn read_optional_rationale(enabled: bool) -> Result<Option<String>> {
     if !enabled {
         return Ok(None);
@@ -1203,59 +1005,144 @@ fn read_optional_rationale(enabled: bool) -> Result<Option<String>> {
     }
 }
 
-fn append_comparison(
-    preferences: &mut serde_json::Value,
-    idea_a: &str,
-    idea_b: &str,
-    winner: &str,
+pub(crate) fn append_comparison(
+    preferences: &mut Preferences,
+    comparison: Comparison,
     rationale: Option<&str>,
-) -> Result<()> {
-    let comparisons = preferences
-        .get_mut("comparisons")
-        .and_then(|c| c.as_array_mut())
-        .ok_or_else(|| anyhow::anyhow!("Invalid preferences format"))?;
-
-    let mut record = serde_json::json!({
-        "idea_a": idea_a,
-        "idea_b": idea_b,
-        "winner": winner
+) {
+    let rationale = rationale
+        .map(str::trim)
+        .filter(|r| !r.is_empty())
+        .map(str::to_string);
+
+    preferences.comparisons.push(Comparison {
+        rationale,
+        ..comparison
     });
+}
 
-    if let Some(rationale) = rationale {
-        let trimmed = rationale.trim();
-        if !trimmed.is_empty() {
-            record["rationale"] = serde_json::Value::String(trimmed.to_string());
-        }
-    }
+fn idea_has_complete_scores(idea: &Idea) -> bool {
+    idea.overall_score.is_some() && idea.scores.is_some()
+}
 
-    comparisons.push(record);
-    Ok(())
+/// Prior log-strength per point of blended score: before any comparison an
+/// idea scored one point higher is expected to win about 73% of the time
+const PRIOR_PER_SCORE_POINT: f64 = 1.0;
+
+/// How far (in log-strength) the user's preferences may stray from the scores.
+/// Deliberately loose: a user's choice is a far stronger signal than a score
+/// gap, so a single answer can reorder ideas the judge scored apart.
+const SCORE_PRIOR_VARIANCE: f64 = 36.0;
+
+/// Prior log-strengths for the Bradley-Terry fit: the judge's overall score
+/// blended with the criterion features, weighted by what the comparisons so
+/// far say about the user's priorities (uniform before the first decision)
+fn score_priors(
+    ideas: &[&Idea],
+    comparisons: &[Comparison],
+    state: &RunState,
+) -> BTreeMap<String, f64> {
+    let risk_mode = infer_risk_mode(state);
+    let scores_by_id = build_scores_by_id(state);
+    let pairs = judged_pairs(comparisons, &scores_by_id);
+    let indices: Vec<usize> = (0..pairs.len()).collect();
+    let w = weights_to_array(&fit_criterion_weights_pairwise_mw_on_indices(
+        &pairs,
+        &scores_by_id,
+        risk_mode,
+        &indices,
+    ));
+    let total: f64 = w.iter().sum();
+
+    let blended: Vec<(String, f64)> = ideas
+        .iter()
+        .map(|idea| {
+            let overall = idea.overall_score.unwrap_or(0.0) as f64;
+            let score = match &idea.scores {
+                Some(scores) => {
+                    let criteria = dot(&w, &scores_to_features(scores, risk_mode)) / total;
+                    (overall + criteria) / 2.0
+                }
+                None => overall,
+            };
+            (idea.id.clone(), score)
+        })
+        .collect();
+    let mean = blended.iter().map(|(_, s)| s).sum::<f64>() / blended.len().max(1) as f64;
+    blended
+        .into_iter()
+        .map(|(id, score)| (id, (score - mean) * PRIOR_PER_SCORE_POINT))
+        .collect()
 }
 
-fn idea_has_complete_scores(idea: &serde_json::Value) -> bool {
-    if idea.get("overall_score").and_then(|s| s.as_f64()).is_none() {
-        return false;
-    }
-    extract_scores(idea).is_some()
+/// What the adaptive pairwise tournament should do next
+#[derive(Debug, PartialEq)]
+pub(crate) enum PairwiseStep {
+    Ask {
+        pair: (String, String),
+        confidence: f64,
+    },
+    /// The top k is trustworthy; stop asking
+    Settled { top: Vec<String>, confidence: f64 },
+    /// Every pair has been compared or skipped
+    Exhausted,
 }
 
-/// Calculate the maximum number of comparisons for pairwise mode.
-/// Returns approximately 2n comparisons, which is enough to establish a ranking
-/// with the adaptive pair selection algorithm.
-fn calculate_pairwise_limit(n: usize) -> usize {
-    2 * n
+/// Fit the comparisons among `ideas` (with score-based priors) and either stop
+/// because the top `k` is settled or pick the next pair to ask about. The top
+/// `k` only counts as settled once each of its ideas has been compared.
+pub(crate) fn next_pairwise_step(
+    ideas: &[&Idea],
+    state: &RunState,
+    comparisons: &[Comparison],
+    compared: &HashSet<(String, String)>,
+    k: usize,
+) -> PairwiseStep {
+    let eligible: HashSet<&str> = ideas.iter().map(|idea| idea.id.as_str()).collect();
+    let relevant: Vec<Comparison> = comparisons
+        .iter()
+        .filter(|c| eligible.contains(c.idea_a.as_str()) && eligible.contains(c.idea_b.as_str()))
+        .cloned()
+        .collect();
+    let fit = ranking::fit_with_prior(
+        &relevant,
+        &score_priors(ideas, &relevant, state),
+        SCORE_PRIOR_VARIANCE,
+    );
+    let top = fit.top_k(k);
+
+    let leaders = &fit.ratings[..top.k];
+    if top.confidence >= ranking::TOP_K_CONFIDENCE
+        && leaders.iter().all(|r| r.wins + r.losses + r.ties > 0)
+    {
+        return PairwiseStep::Settled {
+            top: leaders.iter().map(|r| r.id.clone()).collect(),
+            confidence: top.confidence,
+        };
+    }
+
+    let ids: Vec<String> = ideas.iter().map(|idea| idea.id.clone()).collect();
+    match select_next_pair(&ids, &fit, &top, compared) {
+        Some(pair) => PairwiseStep::Ask {
+            pair,
+            confidence: top.confidence,
+        },
+        None => PairwiseStep::Exhausted,
+    }
 }
 
-/// Select the next best pair to compare for pairwise tournament.
-/// Returns the pair with closest Elo ratings that hasn't been compared yet.
-/// This minimizes comparisons needed to establish ranking (~2n instead of n²).
+/// Select the next pair to compare for pairwise tournament.
+/// Returns the uncompared pair with the largest expected information gain,
+/// weighted towards ideas whose place in the top k is still uncertain, so the
+/// clicks go where they can still change the top of the ranking.
 fn select_next_pair(
     ids: &[String],
-    elo_ratings: &std::collections::HashMap<String, f64>,
-    compared: &std::collections::HashSet<(String, String)>,
+    fit: &ranking::Fit,
+    top: &ranking::TopK,
+    compared: &HashSet<(String, String)>,
 ) -> Option<(String, String)> {
     let mut best_pair: Option<(String, String)> = None;
-    let mut smallest_diff = f64::MAX;
+    let mut best_value = f64::MIN;
 
     for i in 0..ids.len() {
         for j in (i + 1)..ids.len() {
@@ -1263,22 +1150,19 @@ fn select_next_pair(
             let id_b = &ids[j];
 
             // Check if already compared (order-independent)
-            let pair_key = if id_a < id_b {
-                (id_a.clone(), id_b.clone())
-            } else {
-                (id_b.clone(), id_a.clone())
-            };
-
-            if compared.contains(&pair_key) {
+            if compared.contains(&pair_key(id_a, id_b)) {
                 continue;
             }
+            let (Some(a), Some(b)) = (fit.index_of(id_a), fit.index_of(id_b)) else {
+                continue;
+            };
 
-            let elo_a = *elo_ratings.get(id_a).unwrap_or(&1000.0);
-            let elo_b = *elo_ratings.get(id_b).unwrap_or(&1000.0);
-            let diff = (elo_a - elo_b).abs();
+            // m(1 - m) peaks when membership in the top k is a coin flip
+            let uncertainty = |x: usize| top.membership[x] * (1.0 - top.membership[x]);
+            let value = fit.information_gain(a, b) * (uncertainty(a) + uncertainty(b) + 0.01);
 
-            if diff < smallest_diff {
-                smallest_diff = diff;
+            if value > best_value {
+                best_value = value;
                 best_pair = Some((id_a.clone(), id_b.clone()));
             }
         }
@@ -1287,55 +1171,45 @@ fn select_next_pair(
     best_pair
 }
 
-fn update_elo(preferences: &mut serde_json::Value, winner_id: &str, loser_id: &str) -> Result<()> {
-    let k_factor = 32.0;
+/// Elo update for one comparison: a tie is half a win for each side and a
+/// "neither" answer leaves both ratings alone
+pub(crate) fn apply_elo(preferences: &mut Preferences, comparison: &Comparison) {
+    let Some(score_a) = comparison.score_a() else {
+        return;
+    };
+    update_elo(preferences, &comparison.idea_a, &comparison.idea_b, score_a);
+}
 
-    let elo_ratings = preferences
-        .get_mut("elo_ratings")
-        .and_then(|e| e.as_object_mut())
-        .ok_or_else(|| anyhow::anyhow!("Invalid preferences format"))?;
+/// `score_a` is what A got out of the game: 1.0 for a win, 0.5 for a tie
+fn update_elo(preferences: &mut Preferences, id_a: &str, id_b: &str, score_a: f64) {
+    let k_factor = 32.0;
 
-    let winner_elo = elo_ratings
-        .get(winner_id)
-        .and_then(|e| e.as_f64())
-        .unwrap_or(1000.0);
-    let loser_elo = elo_ratings
-        .get(loser_id)
-        .and_then(|e| e.as_f64())
-        .unwrap_or(1000.0);
+    let ratings = &mut preferences.elo_ratings;
+    let elo_a = *ratings.get(id_a).unwrap_or(&1000.0);
+    let elo_b = *ratings.get(id_b).unwrap_or(&1000.0);
 
     // Calculate expected scores
-    let expected_winner = 1.0 / (1.0 + 10.0_f64.powf((loser_elo - winner_elo) / 400.0));
-    let expected_loser = 1.0 - expected_winner;
-
-    // Update ratings (winner gets 1.0, loser gets 0.0)
-    let new_winner_elo = winner_elo + k_factor * (1.0 - expected_winner);
-    let new_loser_elo = loser_elo + k_factor * (0.0 - expected_loser);
+    let expected_a = 1.0 / (1.0 + 10.0_f64.powf((elo_b - elo_a) / 400.0));
+    let expected_b = 1.0 - expected_a;
 
-    elo_ratings.insert(winner_id.to_string(), serde_json::json!(new_winner_elo));
-    elo_ratings.insert(loser_id.to_string(), serde_json::json!(new_loser_elo));
+    let new_elo_a = elo_a + k_factor * (score_a - expected_a);
+    let new_elo_b = elo_b + k_factor * ((1.0 - score_a) - expected_b);
 
-    Ok(())
+    ratings.insert(id_a.to_string(), new_elo_a);
+    ratings.insert(id_b.to_string(), new_elo_b);
 }
 
 /// Export preferences from a run to a portable profile
-pub fn profile_export(run_id: &str, output: Option<&str>) -> Result<()> {
-    let run_dir = PathBuf::from("runs").join(run_id);
-    let preferences_path = run_dir.join("preferences.json");
-    let state_path = run_dir.join("state.json");
-
-    if !preferences_path.exists() {
+pub fn profile_export(storage: &FileStorage, run_id: &str, output: Option<&str>) -> Result<()> {
+    let Some(preferences) = storage.load_preferences(run_id)? else {
         anyhow::bail!(
             "No preferences found for run {}. Run tournament first.",
             run_id
         );
-    }
-
-    let preferences: serde_json::Value =
-        serde_json::from_str(&fs::read_to_string(&preferences_path)?)?;
+    };
 
-    let state: Option<serde_json::Value> = if state_path.exists() {
-        Some(serde_json::from_str(&fs::read_to_string(&state_path)?)?)
+    let state: Option<RunState> = if storage.state_path(run_id).exists() {
+        Some(storage.load_state(run_id)?)
     } else {
         None
     };
@@ -1346,7 +1220,7 @@ pub fn profile_export(run_id: &str, output: Option<&str>) -> Result<()> {
 
     match output {
         Some(path) => {
-            fs::write(path, &json_output)?;
+            write_artifact(Path::new(path), json_output.as_bytes())?;
             println!("Profile exported to: {}", path);
         }
         None => {
@@ -1359,30 +1233,17 @@ pub fn profile_export(run_id: &str, output: Option<&str>) -> Result<()> {
 
 fn build_portable_profile(
     run_id: &str,
-    preferences: &serde_json::Value,
-    state: Option<&serde_json::Value>,
+    preferences: &Preferences,
+    state: Option<&RunState>,
 ) -> serde_json::Value {
-    // Extract comparison count and compute derived stats
-    let comparisons = preferences
-        .get("comparisons")
-        .and_then(|c| c.as_array())
-        .map(|c| c.len())
-        .unwrap_or(0);
-
-    let elo_ratings = preferences
-        .get("elo_ratings")
-        .and_then(|e| e.as_object())
-        .map(|e| e.len())
-        .unwrap_or(0);
-
     // Build portable profile with metadata
     let mut profile = serde_json::json!({
         "version": 1,
         "created_at": chrono::Utc::now().to_rfc3339(),
         "source_run": run_id,
         "stats": {
-            "comparisons": comparisons,
-            "ideas_rated": elo_ratings
+            "comparisons": preferences.comparisons.len(),
+            "ideas_rated": preferences.elo_ratings.len()
         },
         "preferences": preferences
     });
@@ -1404,27 +1265,19 @@ enum RiskMode {
     Invert,
 }
 
-fn infer_risk_mode(state: &serde_json::Value) -> RiskMode {
-    let ideas = state.get("ideas").and_then(|i| i.as_array());
-    let Some(ideas) = ideas else {
-        return RiskMode::AsBenefit;
-    };
-
+fn infer_risk_mode(state: &RunState) -> RiskMode {
     let mut abs_err_benefit = 0.0f64;
     let mut abs_err_invert = 0.0f64;
     let mut n = 0u64;
 
-    for idea in ideas {
-        let Some(scores) = extract_scores(idea) else {
-            continue;
-        };
-
-        let Some(overall) = idea.get("overall_score").and_then(|s| s.as_f64()) else {
+    for idea in &state.ideas {
+        let (Some(scores), Some(overall)) = (&idea.scores, idea.overall_score) else {
             continue;
         };
+        let overall = overall as f64;
 
-        let predicted_benefit = average_score(&scores, RiskMode::AsBenefit);
-        let predicted_invert = average_score(&scores, RiskMode::Invert);
+        let predicted_benefit = average_score(scores, RiskMode::AsBenefit);
+        let predicted_invert = average_score(scores, RiskMode::Invert);
         abs_err_benefit += (predicted_benefit - overall).abs();
         abs_err_invert += (predicted_invert - overall).abs();
         n += 1;
@@ -1458,39 +1311,17 @@ fn average_score(scores: &crate::data::Scores, risk_mode: RiskMode) -> f64 {
 }
 
 fn derive_preference_profile(
-    preferences: &serde_json::Value,
-    state: &serde_json::Value,
+    preferences: &Preferences,
+    state: &RunState,
 ) -> Option<serde_json::Value> {
-    let comparisons = preferences.get("comparisons")?.as_array()?;
-    if comparisons.is_empty() {
+    if preferences.comparisons.is_empty() {
         return None;
     }
 
     let risk_mode = infer_risk_mode(state);
     let scores_by_id = build_scores_by_id(state);
 
-    let mut pairs: Vec<(String, String)> = Vec::new();
-    for comp in comparisons {
-        let idea_a = comp.get("idea_a").and_then(|v| v.as_str());
-        let idea_b = comp.get("idea_b").and_then(|v| v.as_str());
-        let winner = comp.get("winner").and_then(|v| v.as_str());
-        let (Some(idea_a), Some(idea_b), Some(winner)) = (idea_a, idea_b, winner) else {
-            continue;
-        };
-
-        let loser = if winner == idea_a {
-            idea_b
-        } else if winner == idea_b {
-            idea_a
-        } else {
-            continue;
-        };
-
-        if scores_by_id.contains_key(winner) && scores_by_id.contains_key(loser) {
-            pairs.push((winner.to_string(), loser.to_string()));
-        }
-    }
-
+    let pairs = judged_pairs(&preferences.comparisons, &scores_by_id);
     if pairs.is_empty() {
         return None;
     }
@@ -1511,40 +1342,12 @@ fn derive_preference_profile(
     }))
 }
 
-fn build_scores_by_id(
-    state: &serde_json::Value,
-) -> std::collections::HashMap<String, crate::data::Scores> {
-    let mut out = std::collections::HashMap::new();
-    let ideas = state.get("ideas").and_then(|i| i.as_array());
-    let Some(ideas) = ideas else {
-        return out;
-    };
-
-    for idea in ideas {
-        let Some(id) = idea.get("id").and_then(|v| v.as_str()) else {
-            continue;
-        };
-        let Some(scores) = extract_scores(idea) else {
-            continue;
-        };
-        out.insert(id.to_string(), scores);
-    }
-
-    out
-}
-
-fn extract_scores(idea: &serde_json::Value) -> Option<crate::data::Scores> {
-    let scores = idea.get("scores")?.as_object()?;
-    Some(crate::data::Scores {
-        feasibility: scores.get("feasibility")?.as_f64()? as f32,
-        speed_to_value: scores.get("speed_to_value")?.as_f64()? as f32,
-        differentiation: scores.get("differentiation")?.as_f64()? as f32,
-        market_size: scores.get("market_size")?.as_f64()? as f32,
-        distribution: scores.get("distribution")?.as_f64()? as f32,
-        moats: scores.get("moats")?.as_f64()? as f32,
-        risk: scores.get("risk")?.as_f64()? as f32,
-        clarity: scores.get("clarity")?.as_f64()? as f32,
-    })
+fn build_scores_by_id(state: &RunState) -> HashMap<String, crate::data::Scores> {
+    state
+        .ideas
+        .iter()
+        .filter_map(|idea| idea.scores.clone().map(|scores| (idea.id.clone(), scores)))
+        .collect()
 }
 
 fn summarize_weights(weights: &crate::config::ScoringWeights) -> Vec<String> {
@@ -1577,9 +1380,35 @@ fn summarize_weights(weights: &crate::config::ScoringWeights) -> Vec<String> {
     ]
 }
 
+/// (a, b, outcome for a) of every decided or tied comparison between two
+/// scored ideas, oriented so a decided pair lists the winner first: the
+/// outcome is 1.0 for a win and 0.5 for a tie
+fn judged_pairs(
+    comparisons: &[Comparison],
+    scores_by_id: &HashMap<String, crate::data::Scores>,
+) -> Vec<(String, String, f64)> {
+    let mut pairs: Vec<(String, String, f64)> = Vec::new();
+    for comp in comparisons {
+        let (a, b) = (comp.idea_a.as_str(), comp.idea_b.as_str());
+        let Some(score_a) = comp.score_a() else {
+            continue;
+        };
+        let (first, second, outcome) = if score_a == 0.0 {
+            (b, a, 1.0)
+        } else {
+            (a, b, score_a)
+        };
+
+        if scores_by_id.contains_key(first) && scores_by_id.contains_key(second) {
+            pairs.push((first.to_string(), second.to_string(), outcome));
+        }
+    }
+    pairs
+}
+
 fn fit_criterion_weights_pairwise_mw(
-    pairs: &[(String, String)],
-    scores_by_id: &std::collections::HashMap<String, crate::data::Scores>,
+    pairs: &[(String, String, f64)],
+    scores_by_id: &HashMap<String, crate::data::Scores>,
     risk_mode: RiskMode,
     holdout_fraction: f64,
     seed: u64,
@@ -1615,8 +1444,8 @@ fn fit_criterion_weights_pairwise_mw(
 }
 
 fn fit_criterion_weights_pairwise_mw_on_indices(
-    pairs: &[(String, String)],
-    scores_by_id: &std::collections::HashMap<String, crate::data::Scores>,
+    pairs: &[(String, String, f64)],
+    scores_by_id: &HashMap<String, crate::data::Scores>,
     risk_mode: RiskMode,
     indices: &[usize],
 ) -> crate::config::ScoringWeights {
@@ -1627,18 +1456,30 @@ fn fit_criterion_weights_pairwise_mw_on_indices(
     let clamp_max = 10.0f64;
 
     for &idx in indices {
-        let (winner_id, loser_id) = &pairs[idx];
-        let (Some(winner), Some(loser)) = (scores_by_id.get(winner_id), scores_by_id.get(loser_id))
-        else {
+        let (a_id, b_id, outcome) = &pairs[idx];
+        let (Some(a), Some(b)) = (scores_by_id.get(a_id), scores_by_id.get(b_id)) else {
             continue;
         };
 
-        let f_w = scores_to_features(winner, risk_mode);
-        let f_l = scores_to_features(loser, risk_mode);
+        let f_a = scores_to_features(a, risk_mode);
+        let f_b = scores_to_features(b, risk_mode);
+
+        // A win pulls the weights towards the winner's strengths. A tie is half
+        // a win for each side: it pulls half as hard towards whichever idea the
+        // weights currently rank lower, and not at all if they already agree.
+        let step = if *outcome > 0.5 {
+            lr
+        } else {
+            match dot(&w, &f_a).partial_cmp(&dot(&w, &f_b)) {
+                Some(std::cmp::Ordering::Greater) => -0.5 * lr,
+                Some(std::cmp::Ordering::Less) => 0.5 * lr,
+                _ => 0.0,
+            }
+        };
 
         for i in 0..w.len() {
-            let delta = f_w[i] - f_l[i];
-            w[i] *= (lr * delta).exp();
+            let delta = f_a[i] - f_b[i];
+            w[i] *= (step * delta).exp();
             w[i] = w[i].clamp(clamp_min, clamp_max);
         }
 
@@ -1658,28 +1499,23 @@ fn fit_criterion_weights_pairwise_mw_on_indices(
 }
 
 fn evaluate_pairwise_accuracy(
-    pairs: &[(String, String)],
-    scores_by_id: &std::collections::HashMap<String, crate::data::Scores>,
+    pairs: &[(String, String, f64)],
+    scores_by_id: &HashMap<String, crate::data::Scores>,
     risk_mode: RiskMode,
     weights: &crate::config::ScoringWeights,
     indices: &[usize],
 ) -> f64 {
-    let w = [
-        weights.feasibility as f64,
-        weights.speed_to_value as f64,
-        weights.differentiation as f64,
-        weights.market_size as f64,
-        weights.distribution as f64,
-        weights.moats as f64,
-        weights.risk as f64,
-        weights.clarity as f64,
-    ];
+    let w = weights_to_array(weights);
 
     let mut correct = 0u64;
     let mut total = 0u64;
 
     for &idx in indices {
-        let (winner_id, loser_id) = &pairs[idx];
+        let (winner_id, loser_id, outcome) = &pairs[idx];
+        // A tie has no right answer to predict
+        if *outcome <= 0.5 {
+            continue;
+        }
         let (Some(winner), Some(loser)) = (scores_by_id.get(winner_id), scores_by_id.get(loser_id))
         else {
             continue;
@@ -1702,6 +1538,19 @@ fn evaluate_pairwise_accuracy(
     }
 }
 
+fn weights_to_array(weights: &crate::config::ScoringWeights) -> [f64; 8] {
+    [
+        weights.feasibility as f64,
+        weights.speed_to_value as f64,
+        weights.differentiation as f64,
+        weights.market_size as f64,
+        weights.distribution as f64,
+        weights.moats as f64,
+        weights.risk as f64,
+        weights.clarity as f64,
+    ]
+}
+
 fn normalize_in_place(w: &mut [f64; 8]) {
     let sum = w.iter().sum::<f64>();
     if sum <= 0.0 {
@@ -1736,10 +1585,8 @@ fn dot(w: &[f64; 8], f: &[f64; 8]) -> f64 {
 }
 
 /// Import a profile into a run
-pub fn profile_import(file: &str, run_id: &str) -> Result<()> {
-    let run_dir = PathBuf::from("runs").join(run_id);
-
-    if !run_dir.exists() {
+pub fn profile_import(storage: &FileStorage, file: &str, run_id: &str) -> Result<()> {
+    if !storage.run_exists(run_id)? {
         anyhow::bail!("Run {} not found", run_id);
     }
 
@@ -1757,14 +1604,19 @@ pub fn profile_import(file: &str, run_id: &str) -> Result<()> {
 
     let preferences = profile
         .get("preferences")
+        .cloned()
         .ok_or_else(|| anyhow::anyhow!("Invalid profile: missing preferences"))?;
+    let preferences: Preferences = crate::data::from_value(preferences)
+        .map_err(|e| anyhow::anyhow!("Invalid profile: preferences.{}", e))?;
+    for comparison in &preferences.comparisons {
+        comparison
+            .check()
+            .map_err(|e| anyhow::anyhow!("Invalid profile: {}", e))?;
+    }
 
-    // Write preferences to run
-    let preferences_path = run_dir.join("preferences.json");
-    fs::write(
-        &preferences_path,
-        serde_json::to_string_pretty(preferences)?,
-    )?;
+    // Write preferences to run (the previous file is kept as preferences.json.bak)
+    let _lock = storage.lock_run(run_id)?;
+    storage.save_preferences(run_id, &preferences)?;
 
     let source_run = profile
         .get("source_run")
@@ -1772,346 +1624,221 @@ pub fn profile_import(file: &str, run_id: &str) -> Result<()> {
         .unwrap_or("unknown");
 
     println!("Imported profile from {} into {}", source_run, run_id);
-    println!("Preferences written to: {}", preferences_path.display());
-
-    Ok(())
-}
-
-/// Render evolution tree visualization
-pub fn render_tree(run_id: &str, format: &str) -> Result<()> {
-    let run_dir = PathBuf::from("runs").join(run_id);
-    let state_path = run_dir.join("state.json");
-
-    if !state_path.exists() {
-        anyhow::bail!("Run {} not found", run_id);
-    }
-
-    let state: serde_json::Value = serde_json::from_str(&fs::read_to_string(&state_path)?)?;
-    let ideas = state
-        .get("ideas")
-        .and_then(|i| i.as_array())
-        .ok_or_else(|| anyhow::anyhow!("Invalid state: missing ideas"))?;
-
-    if ideas.is_empty() {
-        println!("No ideas in run {}", run_id);
-        return Ok(());
-    }
-
-    // Build parent -> children map
-    let mut children_map: std::collections::HashMap<String, Vec<&serde_json::Value>> =
-        std::collections::HashMap::new();
-    let mut roots: Vec<&serde_json::Value> = Vec::new();
-
-    for idea in ideas {
-        let parents = idea
-            .get("parents")
-            .and_then(|p| p.as_array())
-            .map(|p| p.iter().filter_map(|v| v.as_str()).collect::<Vec<_>>())
-            .unwrap_or_default();
-
-        if parents.is_empty() {
-            roots.push(idea);
-        } else {
-            for parent_id in parents {
-                children_map
-                    .entry(parent_id.to_string())
-                    .or_default()
-                    .push(idea);
-            }
-        }
-    }
-
-    match format {
-        "mermaid" => render_mermaid_tree(&roots, &children_map, run_id),
-        _ => render_ascii_tree(&roots, &children_map, run_id),
-    }
-}
-
-fn render_ascii_tree(
-    roots: &[&serde_json::Value],
-    children_map: &std::collections::HashMap<String, Vec<&serde_json::Value>>,
-    run_id: &str,
-) -> Result<()> {
-    println!("=== Evolution Tree: {} ===\n", run_id);
-
-    for root in roots {
-        print_idea_node(root, children_map, "", true);
-    }
-
-    // Legend
-    println!("\nLegend: [score] status title");
-    println!("  * = active, ~ = archived, x = eliminated");
-
-    Ok(())
-}
-
-fn print_idea_node(
-    idea: &serde_json::Value,
-    children_map: &std::collections::HashMap<String, Vec<&serde_json::Value>>,
-    prefix: &str,
-    is_last: bool,
-) {
-    let id = idea.get("id").and_then(|i| i.as_str()).unwrap_or("?");
-    let title = idea
-        .get("title")
-        .and_then(|t| t.as_str())
-        .unwrap_or("Unknown");
-    let score = idea
-        .get("overall_score")
-        .and_then(|s| s.as_f64())
-        .unwrap_or(0.0);
-    let status = idea.get("status").and_then(|s| s.as_str()).unwrap_or("?");
-
-    let status_char = match status {
-        "active" => "*",
-        "archived" => "~",
-        "eliminated" => "x",
-        _ => "?",
-    };
-
-    let connector = if is_last { "└── " } else { "├── " };
-    let short_title: String = title.chars().take(40).collect();
-    let title_display = if title.len() > 40 {
-        format!("{}...", short_title)
-    } else {
-        short_title
-    };
-
-    println!(
-        "{}{}{} [{:.1}] {} {}",
-        prefix, connector, status_char, score, id, title_display
-    );
-
-    // Print children
-    if let Some(children) = children_map.get(id) {
-        let new_prefix = format!("{}{}", prefix, if is_last { "    " } else { "│   " });
-        for (i, child) in children.iter().enumerate() {
-            let child_is_last = i == children.len() - 1;
-            print_idea_node(child, children_map, &new_prefix, child_is_last);
-        }
-    }
-}
-
-fn render_mermaid_tree(
-    roots: &[&serde_json::Value],
-    children_map: &std::collections::HashMap<String, Vec<&serde_json::Value>>,
-    run_id: &str,
-) -> Result<()> {
-    println!("```mermaid");
-    println!("flowchart TD");
     println!(
-        "    subgraph {}[\"Evolution: {}\"]",
-        run_id.replace('-', "_"),
-        run_id
+        "Preferences written to: {}",
+        storage.preferences_path(run_id).display()
     );
 
-    // Collect all nodes
-    let mut all_ideas: Vec<&serde_json::Value> = roots.to_vec();
-    for children in children_map.values() {
-        all_ideas.extend(children.iter());
-    }
-
-    // Print nodes with styling
-    for idea in &all_ideas {
-        let id = idea.get("id").and_then(|i| i.as_str()).unwrap_or("?");
-        let title = idea
-            .get("title")
-            .and_then(|t| t.as_str())
-            .unwrap_or("Unknown");
-        let score = idea
-            .get("overall_score")
-            .and_then(|s| s.as_f64())
-            .unwrap_or(0.0);
-        let status = idea.get("status").and_then(|s| s.as_str()).unwrap_or("?");
-
-        let short_title: String = title.chars().take(25).collect();
-        let safe_id = id.replace('-', "_");
-
-        let shape = match status {
-            "active" => format!("{}([\"{}\\n{:.1}\"])", safe_id, short_title, score),
-            "eliminated" => format!("{}{{\"{}\\n{:.1}\"}}", safe_id, short_title, score),
-            _ => format!("{}[\"{}\\n{:.1}\"]", safe_id, short_title, score),
-        };
-
-        println!("    {}", shape);
-    }
-
-    // Print edges
-    for (parent_id, children) in children_map {
-        let safe_parent = parent_id.replace('-', "_");
-        for child in children {
-            let child_id = child.get("id").and_then(|i| i.as_str()).unwrap_or("?");
-            let safe_child = child_id.replace('-', "_");
-            println!("    {} --> {}", safe_parent, safe_child);
-        }
-    }
-
-    // Styling
-    println!("    end");
-    println!("    classDef active fill:#90EE90,stroke:#228B22");
-    println!("    classDef archived fill:#D3D3D3,stroke:#808080");
-    println!("    classDef eliminated fill:#FFB6C1,stroke:#DC143C");
-
-    // Apply classes
-    for idea in &all_ideas {
-        let id = idea.get("id").and_then(|i| i.as_str()).unwrap_or("?");
-        let status = idea.get("status").and_then(|s| s.as_str()).unwrap_or("?");
-        let safe_id = id.replace('-', "_");
-
-        println!("    class {} {}", safe_id, status);
-    }
-
-    println!("```");
-
     Ok(())
 }
 
 /// Show profile information for a run
-pub fn profile_show(run_id: &str) -> Result<()> {
-    let run_dir = PathBuf::from("runs").join(run_id);
-    let preferences_path = run_dir.join("preferences.json");
-
-    if !preferences_path.exists() {
+pub fn profile_show(storage: &FileStorage, run_id: &str) -> Result<()> {
+    let Some(preferences) = storage.load_preferences(run_id)? else {
         println!("No preferences found for run {}", run_id);
         println!(
             "Run 'evoidea tournament --run-id {}' to generate preferences",
             run_id
         );
         return Ok(());
-    }
-
-    let preferences: serde_json::Value =
-        serde_json::from_str(&fs::read_to_string(&preferences_path)?)?;
-
-    let comparisons = preferences
-        .get("comparisons")
-        .and_then(|c| c.as_array())
-        .map(|c| c.len())
-        .unwrap_or(0);
-
-    let elo_ratings = preferences.get("elo_ratings").and_then(|e| e.as_object());
+    };
 
     println!("=== Profile for {} ===\n", run_id);
-    println!("Comparisons: {}", comparisons);
+    println!("Comparisons: {}", preferences.comparisons.len());
 
-    if let Some(ratings) = elo_ratings {
-        println!("Ideas rated: {}", ratings.len());
-        println!("\nElo Rankings:");
+    println!("Ideas rated: {}", preferences.elo_ratings.len());
+    println!("\nElo Rankings:");
 
-        let mut ranked: Vec<(&str, f64)> = ratings
-            .iter()
-            .filter_map(|(id, elo)| elo.as_f64().map(|e| (id.as_str(), e)))
-            .collect();
-        ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    let mut ranked: Vec<(&str, f64)> = preferences
+        .elo_ratings
+        .iter()
+        .map(|(id, elo)| (id.as_str(), *elo))
+        .collect();
+    ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
 
-        for (rank, (id, elo)) in ranked.iter().enumerate() {
-            let short_id = if id.len() > 30 { &id[..30] } else { id };
-            println!("  {}. [{:.0}] {}", rank + 1, elo, short_id);
-        }
+    for (rank, (id, elo)) in ranked.iter().enumerate() {
+        let short_id: String = id.chars().take(30).collect();
+        println!("  {}. [{:.0}] {}", rank + 1, elo, short_id);
     }
 
+    let fit = ranking::fit(&preferences.comparisons);
+    println!(
+        "\nBradley-Terry Ratings ({} comparisons, rating ± standard error):",
+        fit.comparisons
+    );
+    ranking::print_fit(&fit, |id| id.chars().take(30).collect());
+
     Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::data::Outcome;
     use tempfile::TempDir;
 
+    fn state_from(mut value: serde_json::Value) -> RunState {
+        value["run_id"] = "run-test".into();
+        value["iteration"] = 1.into();
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn preferences_from(value: serde_json::Value) -> Preferences {
+        serde_json::from_value(value).unwrap()
+    }
+
     #[test]
     fn test_list_runs_empty_dir() {
         let temp_dir = TempDir::new().unwrap();
-        let result = list_runs(temp_dir.path().to_str().unwrap());
+        let result = list_runs(&FileStorage::new(temp_dir.path()));
         assert!(result.is_ok());
     }
 
     #[test]
     fn test_list_runs_nonexistent_dir() {
-        let result = list_runs("/nonexistent/path");
+        let result = list_runs(&FileStorage::new("/nonexistent/path"));
         assert!(result.is_ok()); // Should handle gracefully
     }
 
     #[test]
-    fn test_select_next_pair_picks_closest_elo() {
-        // Given 4 items with Elo ratings, should pick the pair with closest ratings
-        let mut elo_ratings = std::collections::HashMap::new();
-        elo_ratings.insert("a".to_string(), 1000.0);
-        elo_ratings.insert("b".to_string(), 1050.0); // closest to a
-        elo_ratings.insert("c".to_string(), 1200.0);
-        elo_ratings.insert("d".to_string(), 1500.0);
-
-        let compared: std::collections::HashSet<(String, String)> =
-            std::collections::HashSet::new();
-        let ids: Vec<String> = vec!["a", "b", "c", "d"]
-            .into_iter()
-            .map(String::from)
-            .collect();
+    fn test_profile_import_rejects_winner_outside_the_pair() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = FileStorage::new(temp_dir.path());
+        std::fs::create_dir_all(storage.run_dir("run-1")).unwrap();
+        let profile = |comparison: serde_json::Value| {
+            let path = temp_dir.path().join("profile.json");
+            let profile = serde_json::json!({
+                "version": 1,
+                "preferences": { "comparisons": [comparison], "elo_ratings": {} }
+            });
+            std::fs::write(&path, profile.to_string()).unwrap();
+            path.to_string_lossy().to_string()
+        };
 
-        let pair = select_next_pair(&ids, &elo_ratings, &compared);
+        // A draw is spelled with `outcome`, never with a magic winner id
+        let file = profile(serde_json::json!({"idea_a": "a", "idea_b": "b", "winner": "tie"}));
+        let err = profile_import(&storage, &file, "run-1").unwrap_err();
+        assert!(err.to_string().contains("Invalid profile"), "{}", err);
+        assert!(storage.load_preferences("run-1").unwrap().is_none());
 
-        assert!(pair.is_some());
-        let (id1, id2) = pair.unwrap();
-        // Should pick a and b (closest ratings: 50 diff)
-        assert!((id1 == "a" && id2 == "b") || (id1 == "b" && id2 == "a"));
+        let file = profile(serde_json::json!({"idea_a": "a", "idea_b": "b", "outcome": "tie"}));
+        profile_import(&storage, &file, "run-1").unwrap();
+        let preferences = storage.load_preferences("run-1").unwrap().unwrap();
+        assert_eq!(preferences.comparisons[0].outcome, Outcome::Tie);
     }
 
-    #[test]
-    fn test_select_next_pair_skips_already_compared() {
-        let mut elo_ratings = std::collections::HashMap::new();
-        elo_ratings.insert("a".to_string(), 1000.0);
-        elo_ratings.insert("b".to_string(), 1050.0);
-        elo_ratings.insert("c".to_string(), 1100.0);
-
-        // a-b already compared
-        let mut compared: std::collections::HashSet<(String, String)> =
-            std::collections::HashSet::new();
-        compared.insert(("a".to_string(), "b".to_string()));
+    /// Scored active ideas idea-000.. with overall scores from `scores`
+    fn scored_state(scores: &[f32]) -> RunState {
+        let ideas: Vec<serde_json::Value> = scores
+            .iter()
+            .enumerate()
+            .map(|(i, score)| {
+                serde_json::json!({
+                    "id": format!("idea-{:03}", i),
+                    "origin": "generated",
+                    "title": format!("Idea {}", i),
+                    "status": "active",
+                    "scores": {"feasibility": score, "speed_to_value": score, "differentiation": score, "market_size": score,
+                               "distribution": score, "moats": score, "risk": score, "clarity": score},
+                    "overall_score": score
+                })
+            })
+            .collect();
+        state_from(serde_json::json!({ "ideas": ideas }))
+    }
 
-        let ids: Vec<String> = vec!["a", "b", "c"].into_iter().map(String::from).collect();
+    #[test]
+    fn test_select_next_pair_targets_the_top_k_boundary() {
+        // With k = 1, the two leading ideas decide the winner; the tail does not matter
+        let state = scored_state(&[8.0, 7.9, 4.0, 3.9]);
+        let ideas: Vec<&Idea> = state.ideas.iter().collect();
+        let step = next_pairwise_step(&ideas, &state, &[], &HashSet::new(), 1);
+
+        let PairwiseStep::Ask { pair, .. } = step else {
+            panic!("expected a question, got {:?}", step);
+        };
+        assert_eq!(pair, ("idea-000".to_string(), "idea-001".to_string()));
+    }
 
-        let pair = select_next_pair(&ids, &elo_ratings, &compared);
+    #[test]
+    fn test_select_next_pair_skips_already_compared() {
+        let state = scored_state(&[8.0, 7.9, 4.0]);
+        let ideas: Vec<&Idea> = state.ideas.iter().collect();
+        let compared = HashSet::from([pair_key("idea-000", "idea-001")]);
+        let step = next_pairwise_step(&ideas, &state, &[], &compared, 1);
 
-        assert!(pair.is_some());
-        let (id1, id2) = pair.unwrap();
-        // Should pick b-c (next closest, 50 diff) since a-b is done
-        assert!((id1 == "b" && id2 == "c") || (id1 == "c" && id2 == "b"));
+        let PairwiseStep::Ask { pair, .. } = step else {
+            panic!("expected a question, got {:?}", step);
+        };
+        assert_ne!(pair_key(&pair.0, &pair.1), pair_key("idea-000", "idea-001"));
     }
 
     #[test]
     fn test_select_next_pair_returns_none_when_done() {
-        let mut elo_ratings = std::collections::HashMap::new();
-        elo_ratings.insert("a".to_string(), 1000.0);
-        elo_ratings.insert("b".to_string(), 1050.0);
-
-        // Only pair already compared
-        let mut compared: std::collections::HashSet<(String, String)> =
-            std::collections::HashSet::new();
-        compared.insert(("a".to_string(), "b".to_string()));
-
-        let ids: Vec<String> = vec!["a", "b"].into_iter().map(String::from).collect();
+        let state = scored_state(&[5.0, 5.0]);
+        let ideas: Vec<&Idea> = state.ideas.iter().collect();
+        let compared = HashSet::from([pair_key("idea-000", "idea-001")]);
+        assert_eq!(
+            next_pairwise_step(&ideas, &state, &[], &compared, 1),
+            PairwiseStep::Exhausted
+        );
+    }
 
-        let pair = select_next_pair(&ids, &elo_ratings, &compared);
+    #[test]
+    fn test_pairwise_finds_top_3_in_few_comparisons() {
+        // The judge's scores are roughly right, but the user actually prefers
+        // idea-003 over idea-002; a consistent user answers by true strength
+        let scores = [8.0, 7.6, 7.4, 7.2, 6.8, 6.5, 6.1, 5.8, 5.2, 4.9];
+        let truth = |id: &str| match id {
+            "idea-003" => 7.5,
+            "idea-002" => 7.1,
+            _ => scores[id[5..].parse::<usize>().unwrap()] as f64,
+        };
+        let state = scored_state(&scores);
+        let ideas: Vec<&Idea> = state.ideas.iter().collect();
+        let mut preferences = Preferences::default();
+        let mut compared = HashSet::new();
+
+        let top = loop {
+            match next_pairwise_step(&ideas, &state, &preferences.comparisons, &compared, 3) {
+                PairwiseStep::Ask { pair: (a, b), .. } => {
+                    let winner = if truth(&a) > truth(&b) { &a } else { &b };
+                    append_comparison(&mut preferences, Comparison::decided(&a, &b, winner), None);
+                    compared.insert(pair_key(&a, &b));
+                }
+                PairwiseStep::Settled { top, .. } => break top,
+                PairwiseStep::Exhausted => panic!("never settled"),
+            }
+        };
 
-        assert!(pair.is_none());
+        let mut top = top;
+        top.sort();
+        assert_eq!(top, vec!["idea-000", "idea-001", "idea-003"]);
+        // Far fewer than the 45 pairs of an exhaustive tournament
+        assert!(
+            preferences.comparisons.len() <= 20,
+            "took {} comparisons",
+            preferences.comparisons.len()
+        );
     }
 
     #[test]
     fn test_pairwise_elo_updates_after_comparison() {
         // After a pairwise comparison, Elo ratings should update correctly
-        let mut preferences = serde_json::json!({
+        let mut preferences = preferences_from(serde_json::json!({
             "comparisons": [],
             "elo_ratings": {
                 "idea-001": 1000.0,
                 "idea-002": 1000.0
             }
-        });
+        }));
 
         // idea-001 wins
-        update_elo(&mut preferences, "idea-001", "idea-002").unwrap();
+        update_elo(&mut preferences, "idea-001", "idea-002", 1.0);
 
-        let ratings = preferences.get("elo_ratings").unwrap();
-        let winner_elo = ratings.get("idea-001").unwrap().as_f64().unwrap();
-        let loser_elo = ratings.get("idea-002").unwrap().as_f64().unwrap();
+        let ratings = &preferences.elo_ratings;
+        let winner_elo = ratings["idea-001"];
+        let loser_elo = ratings["idea-002"];
 
         // Winner should gain, loser should lose
         assert!(winner_elo > 1000.0);
@@ -2121,33 +1848,117 @@ mod tests {
     }
 
     #[test]
-    fn test_pairwise_comparison_limit_is_reasonable() {
-        // For n items, pairwise mode should need ~2n comparisons to converge
-        // (not the O(n²) of full pairwise)
-        let n = 10;
-        let ids: Vec<String> = (0..n).map(|i| format!("idea-{:03}", i)).collect();
-        let mut elo_ratings = std::collections::HashMap::new();
-        for id in &ids {
-            elo_ratings.insert(id.clone(), 1000.0);
+    fn test_tie_is_half_a_win_in_elo() {
+        let mut preferences = Preferences::default();
+        append_comparison(&mut preferences, Comparison::tie("a", "b"), None);
+        let comparison = preferences.comparisons[0].clone();
+        apply_elo(&mut preferences, &comparison);
+        assert!((preferences.elo_ratings["a"] - 1000.0).abs() < 1e-9);
+        assert!((preferences.elo_ratings["b"] - 1000.0).abs() < 1e-9);
+
+        // Against a stronger idea a tie is a gain for the weaker one
+        preferences.elo_ratings.insert("a".to_string(), 1100.0);
+        update_elo(&mut preferences, "a", "b", 0.5);
+        assert!(preferences.elo_ratings["a"] < 1100.0);
+        assert!(preferences.elo_ratings["b"] > 1000.0);
+    }
+
+    #[test]
+    fn test_neither_flags_both_ideas_without_rating_them() {
+        let mut preferences = Preferences::default();
+        append_comparison(&mut preferences, Comparison::neither("a", "b"), None);
+        let comparison = preferences.comparisons[0].clone();
+        apply_elo(&mut preferences, &comparison);
+        assert!(preferences.elo_ratings.is_empty());
+        assert_eq!(
+            preferences.rejected().into_iter().collect::<Vec<_>>(),
+            ["a", "b"]
+        );
+    }
+
+    #[test]
+    fn test_undo_recomputes_elo_from_scratch() {
+        let record = |preferences: &mut Preferences, comparison: Comparison| {
+            append_comparison(preferences, comparison, None);
+            let comparison = preferences.comparisons.last().unwrap().clone();
+            apply_elo(preferences, &comparison);
+        };
+        let mut expected = Preferences::default();
+        record(&mut expected, Comparison::decided("a", "b", "a"));
+        record(&mut expected, Comparison::tie("b", "c"));
+
+        let mut preferences = expected.clone();
+        record(&mut preferences, Comparison::decided("a", "c", "c"));
+        let undone = undo_last(&mut preferences).unwrap();
+        assert_eq!(undone.winner, "c");
+        assert_eq!(preferences.comparisons, expected.comparisons);
+        for (id, elo) in &expected.elo_ratings {
+            assert!((preferences.elo_ratings[id] - elo).abs() < 1e-9, "{}", id);
         }
 
-        let max_comparisons = calculate_pairwise_limit(n);
+        undo_last(&mut preferences);
+        undo_last(&mut preferences);
+        assert!(undo_last(&mut preferences).is_none());
+        assert!(preferences.elo_ratings.values().all(|elo| *elo == 1000.0));
+    }
+
+    #[test]
+    fn test_undo_counts_only_answers_from_this_session() {
+        let mut preferences = Preferences::default();
+        append_comparison(&mut preferences, Comparison::decided("a", "b", "a"), None);
+        let mut session_start = preferences.comparisons.len();
+
+        append_comparison(&mut preferences, Comparison::decided("b", "c", "c"), None);
+        undo_last(&mut preferences);
+        assert!(undid_session_answer(&preferences, &mut session_start));
+
+        // The next undo removes the earlier session's answer
+        undo_last(&mut preferences);
+        assert!(!undid_session_answer(&preferences, &mut session_start));
+        assert_eq!(session_start, 0);
 
-        // Should be around 2n, definitely less than n*(n-1)/2
-        assert!(max_comparisons <= 3 * n);
-        assert!(max_comparisons < n * (n - 1) / 2);
+        append_comparison(&mut preferences, Comparison::tie("a", "c"), None);
+        undo_last(&mut preferences);
+        assert!(undid_session_answer(&preferences, &mut session_start));
+    }
+
+    #[test]
+    fn test_blind_answers_randomize_sides_and_are_marked() {
+        let pair = ("a".to_string(), "b".to_string());
+        assert!((0..50).all(|_| present(pair.clone(), false) == pair));
+        let flipped = (0..200)
+            .filter(|_| present(pair.clone(), true) != pair)
+            .count();
+        assert!(flipped > 50 && flipped < 150, "flipped {} of 200", flipped);
+
+        let mut preferences = Preferences::default();
+        record_answer(
+            &mut preferences,
+            Comparison::decided("b", "a", "b"),
+            None,
+            true,
+        );
+        record_answer(
+            &mut preferences,
+            Comparison::decided("a", "b", "a"),
+            None,
+            false,
+        );
+        assert!(preferences.comparisons[0].blind);
+        assert_eq!(preferences.comparisons[0].idea_a, "b");
+        assert!(!preferences.comparisons[1].blind);
     }
 
     #[test]
     fn test_derive_preference_profile_returns_none_without_comparisons() {
-        let preferences = serde_json::json!({
+        let preferences = preferences_from(serde_json::json!({
             "comparisons": [],
             "elo_ratings": {}
-        });
+        }));
 
-        let state = serde_json::json!({
+        let state = state_from(serde_json::json!({
             "ideas": []
-        });
+        }));
 
         let derived = derive_preference_profile(&preferences, &state);
         assert!(derived.is_none());
@@ -2158,27 +1969,33 @@ mod tests {
         // In current run artifacts, higher "risk" score means safer (benefit) and contributes
         // positively to overall_score (i.e., no inversion). This test ensures we infer that mode
         // and learn a higher weight for risk when the user consistently prefers the safer idea.
-        let state = serde_json::json!({
+        let state = state_from(serde_json::json!({
             "ideas": [
                 {
                     "id": "safe",
+                    "origin": "generated",
+                    "title": "Safe",
+                    "status": "active",
                     "scores": {"feasibility": 5, "speed_to_value": 5, "differentiation": 5, "market_size": 5, "distribution": 5, "moats": 5, "risk": 9, "clarity": 5},
                     "overall_score": 5.5
                 },
                 {
                     "id": "risky",
+                    "origin": "generated",
+                    "title": "Risky",
+                    "status": "active",
                     "scores": {"feasibility": 5, "speed_to_value": 5, "differentiation": 5, "market_size": 5, "distribution": 5, "moats": 5, "risk": 1, "clarity": 5},
                     "overall_score": 4.5
                 }
             ]
-        });
+        }));
 
-        let preferences = serde_json::json!({
+        let preferences = preferences_from(serde_json::json!({
             "comparisons": [
                 { "idea_a": "safe", "idea_b": "risky", "winner": "safe" }
             ],
             "elo_ratings": {}
-        });
+        }));
 
         let derived = derive_preference_profile(&preferences, &state).expect("derived");
         let weights = derived.get("criterion_weights").expect("criterion_weights");
@@ -2220,82 +2037,87 @@ mod tests {
     }
 
     #[test]
-    fn test_validate_state_invariants_flags_unscored_active_ideas() {
-        let state = serde_json::json!({
-            "ideas": [
-                {
-                    "id": "idea-1",
-                    "origin": "refined",
-                    "parents": ["idea-0"],
-                    "status": "active"
-                }
-            ]
-        });
-
-        let errors = validate_state_idea_invariants(&state);
-        assert!(errors.iter().any(|e| e.contains("missing/invalid scores")));
-        assert!(errors
-            .iter()
-            .any(|e| e.contains("missing/invalid overall_score")));
-    }
-
-    #[test]
-    fn test_validate_state_invariants_accepts_scored_active_ideas() {
-        let state = serde_json::json!({
+    fn test_tie_pulls_learned_weights_back_towards_equal() {
+        let state = state_from(serde_json::json!({
             "ideas": [
-                {
-                    "id": "idea-1",
-                    "origin": "generated",
-                    "parents": [],
-                    "status": "active",
-                    "scores": {"feasibility": 5, "speed_to_value": 5, "differentiation": 5, "market_size": 5, "distribution": 5, "moats": 5, "risk": 5, "clarity": 5},
-                    "overall_score": 5.0
-                }
+                {"id": "safe", "origin": "generated", "title": "Safe", "status": "active",
+                 "scores": {"feasibility": 5, "speed_to_value": 5, "differentiation": 5, "market_size": 5, "distribution": 5, "moats": 5, "risk": 9, "clarity": 5},
+                 "overall_score": 5.5},
+                {"id": "risky", "origin": "generated", "title": "Risky", "status": "active",
+                 "scores": {"feasibility": 5, "speed_to_value": 5, "differentiation": 5, "market_size": 5, "distribution": 5, "moats": 5, "risk": 1, "clarity": 5},
+                 "overall_score": 4.5}
             ]
-        });
+        }));
+        let risk_weight = |comparisons: serde_json::Value| {
+            let preferences = preferences_from(
+                serde_json::json!({"comparisons": comparisons, "elo_ratings": {}}),
+            );
+            let derived = derive_preference_profile(&preferences, &state).expect("derived");
+            derived["criterion_weights"]["risk"].as_f64().unwrap()
+        };
 
-        let errors = validate_state_idea_invariants(&state);
-        assert!(errors.is_empty());
+        let win = serde_json::json!({"idea_a": "safe", "idea_b": "risky", "winner": "safe"});
+        let tie = serde_json::json!({"idea_a": "risky", "idea_b": "safe", "outcome": "tie"});
+        let won = risk_weight(serde_json::json!([win]));
+        let then_tied = risk_weight(serde_json::json!([win, tie]));
+        assert!(then_tied < won);
+        assert!(then_tied > 1.0 / 8.0);
     }
 
     #[test]
     fn test_append_comparison_includes_rationale_when_provided() {
-        let mut preferences = serde_json::json!({
-            "comparisons": [],
-            "elo_ratings": {}
-        });
+        let mut preferences = Preferences::default();
 
-        append_comparison(&mut preferences, "a", "b", "a", Some("because")).unwrap();
+        append_comparison(
+            &mut preferences,
+            Comparison::decided("a", "b", "a"),
+            Some("because"),
+        );
 
-        let comparisons = preferences
-            .get("comparisons")
-            .and_then(|c| c.as_array())
-            .unwrap();
+        let comparisons = &preferences.comparisons;
 
         assert_eq!(comparisons.len(), 1);
-        assert_eq!(
-            comparisons[0].get("rationale").and_then(|v| v.as_str()),
-            Some("because")
-        );
+        assert_eq!(comparisons[0].rationale.as_deref(), Some("because"));
     }
 
     #[test]
     fn test_append_comparison_omits_rationale_when_none_or_empty() {
-        let mut preferences = serde_json::json!({
-            "comparisons": [],
-            "elo_ratings": {}
-        });
+        let mut preferences = Preferences::default();
 
-        append_comparison(&mut preferences, "a", "b", "a", None).unwrap();
-        append_comparison(&mut preferences, "a", "b", "b", Some("   ")).unwrap();
+        append_comparison(&mut preferences, Comparison::decided("a", "b", "a"), None);
+        append_comparison(
+            &mut preferences,
+            Comparison::decided("a", "b", "b"),
+            Some("   "),
+        );
 
-        let comparisons = preferences
-            .get("comparisons")
-            .and_then(|c| c.as_array())
-            .unwrap();
+        let comparisons = &preferences.comparisons;
 
         assert_eq!(comparisons.len(), 2);
-        assert!(comparisons[0].get("rationale").is_none());
-        assert!(comparisons[1].get("rationale").is_none());
+        assert!(comparisons[0].rationale.is_none());
+        assert!(comparisons[1].rationale.is_none());
+    }
+
+    #[test]
+    fn test_decision_log_renders_legacy_final() {
+        let result = FinalResult::from_value(serde_json::json!({
+            "run_id": "run-legacy",
+            "best": {
+                "idea_id": "idea-3",
+                "title": "Legacy Winner",
+                "summary": "Summary",
+                "facets": { "audience": "devs", "risks": "churn" },
+                "overall_score": 7.5,
+                "why_won": ["clear wedge", "cheap to build"]
+            },
+            "runners_up": []
+        }))
+        .unwrap();
+
+        let output = generate_decision_log(&result, None, None);
+        assert!(output.contains("# Decision Log: Legacy Winner"));
+        assert!(output.contains("**Target Audience:** devs"));
+        assert!(output.contains("- clear wedge"));
+        assert!(!output.contains("Unknown"));
     }
 }
@@ -0,0 +1,243 @@
+use anyhow::Result;
+
+use crate::lineage::winning_lineage;
+use crate::stats::run_stats;
+use crate::storage::Storage;
+
+/// Characters used to draw charts; `ASCII` is for terminals without Unicode
+struct Glyphs {
+    bars: [char; 8],
+    best: char,
+    mean: char,
+    axis: char,
+    corner: char,
+    rule: char,
+    tick: char,
+    arrow: &'static str,
+}
+
+const UNICODE: Glyphs = Glyphs {
+    bars: ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'],
+    best: '●',
+    mean: '·',
+    axis: '┤',
+    corner: '└',
+    rule: '─',
+    tick: '┬',
+    arrow: "→",
+};
+
+const ASCII: Glyphs = Glyphs {
+    bars: ['_', '.', '-', ':', '=', '+', '*', '#'],
+    best: '*',
+    mean: 'o',
+    axis: '|',
+    corner: '+',
+    rule: '-',
+    tick: '+',
+    arrow: "->",
+};
+
+/// Rows of the line chart
+const CHART_HEIGHT: usize = 10;
+
+fn value_range<'a>(values: impl IntoIterator<Item = &'a Option<f32>>) -> Option<(f32, f32)> {
+    values
+        .into_iter()
+        .flatten()
+        .fold(None, |range, &v| match range {
+            None => Some((v, v)),
+            Some((lo, hi)) => Some((lo.min(v), hi.max(v))),
+        })
+}
+
+/// One bar per value scaled to `lo..=hi`; missing values are blank
+fn sparkline(values: &[Option<f32>], (lo, hi): (f32, f32), bars: &[char; 8]) -> String {
+    values
+        .iter()
+        .map(|value| match value {
+            None => ' ',
+            Some(_) if (hi - lo).abs() < f32::EPSILON => bars[3],
+            Some(v) => {
+                let level = ((v - lo) / (hi - lo) * 7.0).round() as usize;
+                bars[level.min(7)]
+            }
+        })
+        .collect()
+}
+
+/// Line chart of several series over iterations 1..=n, later series drawn on top
+fn line_chart(series: &[(char, &[Option<f32>])], glyphs: &Glyphs) -> Vec<String> {
+    let Some((mut lo, mut hi)) = value_range(series.iter().flat_map(|(_, values)| values.iter()))
+    else {
+        return Vec::new();
+    };
+    if hi - lo < 0.5 {
+        lo -= 0.25;
+        hi += 0.25;
+    }
+    let points = series.iter().map(|(_, v)| v.len()).max().unwrap_or(0);
+    let step = if points <= 20 { 4 } else { 2 };
+    let width = points * step + 1;
+
+    let mut grid = vec![vec![' '; width]; CHART_HEIGHT];
+    for (marker, values) in series {
+        for (i, value) in values.iter().enumerate() {
+            if let Some(v) = value {
+                let row = ((hi - v) / (hi - lo) * (CHART_HEIGHT - 1) as f32).round() as usize;
+                grid[row.min(CHART_HEIGHT - 1)][i * step + step / 2] = *marker;
+            }
+        }
+    }
+
+    let mut lines: Vec<String> = grid
+        .into_iter()
+        .enumerate()
+        .map(|(row, cells)| {
+            let y = hi - (hi - lo) * row as f32 / (CHART_HEIGHT - 1) as f32;
+            let line: String = cells.into_iter().collect();
+            format!("{:>6.2} {}{}", y, glyphs.axis, line.trim_end())
+        })
+        .collect();
+
+    let mut rule = String::new();
+    let mut labels = String::new();
+    for i in 0..points {
+        let center = i * step + step / 2;
+        while rule.chars().count() < center {
+            rule.push(glyphs.rule);
+        }
+        rule.push(glyphs.tick);
+        let label = (i + 1).to_string();
+        // Skip labels that would run into the previous one
+        if labels.len() < center {
+            labels.push_str(&" ".repeat(center - labels.len()));
+            labels.push_str(&label);
+        }
+    }
+    while rule.chars().count() < width {
+        rule.push(glyphs.rule);
+    }
+    lines.push(format!("{:>6} {}{}", "", glyphs.corner, rule));
+    lines.push(format!("{:>6}  {}", "", labels));
+    lines
+}
+
+fn first_last(values: &[Option<f32>], arrow: &str) -> String {
+    let defined: Vec<f32> = values.iter().flatten().copied().collect();
+    match (defined.first(), defined.last()) {
+        (Some(first), Some(last)) => format!("{:.2} {} {:.2}", first, arrow, last),
+        _ => "-".to_string(),
+    }
+}
+
+/// Print charts of best/mean score per iteration and the criteria of the winning lineage
+pub fn plot_run(storage: &dyn Storage, run_id: &str, ascii: bool) -> Result<()> {
+    let glyphs = if ascii { &ASCII } else { &UNICODE };
+    let stats = run_stats(storage, run_id)?;
+    let best: Vec<Option<f32>> = stats.iter().map(|s| s.best_score).collect();
+    let mean: Vec<Option<f32>> = stats.iter().map(|s| s.mean_score).collect();
+
+    println!(
+        "Run {}: score evolution over {} iterations\n",
+        run_id,
+        stats.len()
+    );
+    let Some(range) = value_range(best.iter().chain(&mean)) else {
+        println!("No scored ideas yet");
+        return Ok(());
+    };
+    println!(
+        "best {}  {}",
+        sparkline(&best, range, &glyphs.bars),
+        first_last(&best, glyphs.arrow)
+    );
+    println!(
+        "mean {}  {}\n",
+        sparkline(&mean, range, &glyphs.bars),
+        first_last(&mean, glyphs.arrow)
+    );
+    for line in line_chart(&[(glyphs.mean, &mean), (glyphs.best, &best)], glyphs) {
+        println!("{}", line);
+    }
+    println!(
+        "{:>6}  {} best so far   {} mean of the iteration's scored ideas",
+        "", glyphs.best, glyphs.mean
+    );
+
+    let state = storage.load_state(run_id)?;
+    let Some(best_id) = state.best_idea_id.as_deref() else {
+        return Ok(());
+    };
+    let lineage = winning_lineage(&state, best_id);
+    let ids: Vec<&str> = lineage.iter().map(|idea| idea.id.as_str()).collect();
+    println!(
+        "\nWinning lineage ({} ideas): {}",
+        lineage.len(),
+        ids.join(&format!(" {} ", glyphs.arrow))
+    );
+
+    let overall: Vec<Option<f32>> = lineage.iter().map(|idea| idea.overall_score).collect();
+    let criteria: Vec<(&str, Vec<Option<f32>>)> = crate::data::Scores::NAMES
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let values = lineage
+                .iter()
+                .map(|idea| idea.scores.as_ref().map(|s| s.criteria()[i].1))
+                .collect();
+            (*name, values)
+        })
+        .collect();
+    let Some(range) = value_range(criteria.iter().flat_map(|(_, v)| v).chain(&overall)) else {
+        return Ok(());
+    };
+    println!(
+        "{:<16} {}  {}",
+        "overall",
+        sparkline(&overall, range, &glyphs.bars),
+        first_last(&overall, glyphs.arrow)
+    );
+    for (name, values) in &criteria {
+        println!(
+            "{:<16} {}  {}",
+            name,
+            sparkline(values, range, &glyphs.bars),
+            first_last(values, glyphs.arrow)
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sparkline_scales_and_skips_missing() {
+        let values = [Some(0.0), Some(5.0), None, Some(10.0)];
+        assert_eq!(sparkline(&values, (0.0, 10.0), &UNICODE.bars), "▁▅ █");
+        assert_eq!(sparkline(&values, (0.0, 10.0), &ASCII.bars), "_= #");
+        assert_eq!(
+            sparkline(&[Some(3.0), Some(3.0)], (3.0, 3.0), &UNICODE.bars),
+            "▄▄"
+        );
+    }
+
+    #[test]
+    fn test_line_chart_places_markers_by_value() {
+        let best = [Some(6.0), Some(8.0)];
+        let mean = [Some(5.0), None];
+        let lines = line_chart(&[('o', &mean), ('*', &best)], &ASCII);
+        assert_eq!(lines.len(), CHART_HEIGHT + 2);
+        // Highest value on the top row, lowest on the bottom row
+        assert!(lines[0].starts_with("  8.00 |") && lines[0].ends_with('*'));
+        assert!(
+            lines[CHART_HEIGHT - 1].starts_with("  5.00 |")
+                && lines[CHART_HEIGHT - 1].ends_with('o')
+        );
+        assert_eq!(lines[CHART_HEIGHT].trim(), "+--+---+--");
+        assert_eq!(lines[CHART_HEIGHT + 1].trim(), "1   2");
+    }
+}
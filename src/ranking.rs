@@ -0,0 +1,596 @@
+use rand::{Rng, SeedableRng};
+use std::collections::BTreeMap;
+
+use crate::data::Comparison;
+
+/// Variance of the zero-mean Gaussian prior on log-strengths. It keeps ideas
+/// that won (or lost) every comparison at a finite strength and makes the fit
+/// identifiable without pinning a reference idea.
+const PRIOR_VARIANCE: f64 = 4.0;
+
+/// Log-strengths are reported on the Elo scale (1000 = average, +400 = 10:1 odds)
+const ELO_BASE: f64 = 1000.0;
+const ELO_SCALE: f64 = 400.0 / std::f64::consts::LN_10;
+
+/// Neighbours in the ranking count as separated above this confidence
+pub const SETTLED_CONFIDENCE: f64 = 0.9;
+
+/// A top-k set counts as trustworthy once this share of it is expected to be right
+pub const TOP_K_CONFIDENCE: f64 = 0.9;
+
+/// Posterior draws used to estimate top-k membership
+const POSTERIOR_SAMPLES: usize = 4000;
+
+/// Widening of the Gaussian approximation used as the sampling proposal
+const PROPOSAL_SCALE: f64 = 1.5;
+
+const MAX_ITERATIONS: usize = 100;
+const TOLERANCE: f64 = 1e-9;
+
+/// Fitted strength of one idea
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rating {
+    pub id: String,
+    /// Strength on the Elo scale
+    pub rating: f64,
+    /// Standard error of `rating`
+    pub std_error: f64,
+    pub wins: u32,
+    pub losses: u32,
+    pub ties: u32,
+}
+
+/// Batch Bradley-Terry fit over every comparison, strongest idea first
+#[derive(Debug, Clone)]
+pub struct Fit {
+    pub ratings: Vec<Rating>,
+    pub comparisons: usize,
+    /// Covariance of the log-strengths, indexed like `ratings`
+    covariance: Vec<Vec<f64>>,
+    theta: Vec<f64>,
+    /// Decided comparisons as (i, j, score of i), indexed like `ratings`
+    games: Vec<(usize, usize, f64)>,
+    prior_mean: Vec<f64>,
+    prior_variance: f64,
+}
+
+impl Fit {
+    /// Probability that `ratings[i]` is truly stronger than `ratings[j]`
+    pub fn confidence(&self, i: usize, j: usize) -> f64 {
+        let c = &self.covariance;
+        let variance = (c[i][i] + c[j][j] - 2.0 * c[i][j]).max(f64::MIN_POSITIVE);
+        normal_cdf((self.theta[i] - self.theta[j]) / variance.sqrt())
+    }
+
+    /// Position of an idea in `ratings`
+    pub fn index_of(&self, id: &str) -> Option<usize> {
+        self.ratings.iter().position(|r| r.id == id)
+    }
+
+    /// Expected reduction in posterior entropy (nats) from one more comparison
+    /// of `ratings[i]` and `ratings[j]`. Observing the outcome adds
+    /// p(1 - p) to the precision along θi - θj, so the gain is
+    /// ½·ln(1 + p(1 - p)·Var(θi - θj)), largest for close, uncertain pairs.
+    pub fn information_gain(&self, i: usize, j: usize) -> f64 {
+        let c = &self.covariance;
+        let variance = (c[i][i] + c[j][j] - 2.0 * c[i][j]).max(0.0);
+        // Win probability averaged over the posterior (probit approximation)
+        let p = logistic(
+            (self.theta[i] - self.theta[j]) / (1.0 + std::f64::consts::PI * variance / 8.0).sqrt(),
+        );
+        0.5 * (1.0 + p * (1.0 - p) * variance).ln()
+    }
+
+    /// Log posterior density of log-strengths `theta` (up to a constant)
+    fn log_posterior(&self, theta: &[f64]) -> f64 {
+        let prior: f64 = theta
+            .iter()
+            .zip(&self.prior_mean)
+            .map(|(t, m)| -(t - m).powi(2) / (2.0 * self.prior_variance))
+            .sum();
+        let likelihood: f64 = self
+            .games
+            .iter()
+            .map(|&(a, b, score)| {
+                let d = theta[a] - theta[b];
+                score * log_logistic(d) + (1.0 - score) * log_logistic(-d)
+            })
+            .sum();
+        prior + likelihood
+    }
+
+    /// Probability of each idea being in the top `k`, and the expected share
+    /// of the current top `k` (the first `k` ratings) that truly belongs there.
+    ///
+    /// The Gaussian approximation behind `confidence` is too timid when every
+    /// comparison agrees (the likelihood is flat on the winning side), so this
+    /// samples a widened Gaussian around the fit and reweights each draw by
+    /// the exact posterior (self-normalized importance sampling).
+    pub fn top_k(&self, k: usize) -> TopK {
+        let n = self.ratings.len();
+        let k = k.min(n);
+        let cholesky = cholesky(&self.covariance);
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+
+        let mut draws: Vec<(f64, Vec<usize>)> = Vec::with_capacity(POSTERIOR_SAMPLES);
+        for _ in 0..POSTERIOR_SAMPLES {
+            let z: Vec<f64> = (0..n).map(|_| standard_normal(&mut rng)).collect();
+            let sample: Vec<f64> = (0..n)
+                .map(|i| {
+                    self.theta[i]
+                        + PROPOSAL_SCALE * (0..=i).map(|j| cholesky[i][j] * z[j]).sum::<f64>()
+                })
+                .collect();
+            // Proposal density is exp(-|z|²/2) up to the same constant for every draw
+            let log_weight =
+                self.log_posterior(&sample) + z.iter().map(|z| z * z).sum::<f64>() / 2.0;
+            let mut order: Vec<usize> = (0..n).collect();
+            order.sort_by(|&a, &b| {
+                sample[b]
+                    .partial_cmp(&sample[a])
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            });
+            order.truncate(k);
+            draws.push((log_weight, order));
+        }
+
+        let max = draws.iter().map(|(w, _)| *w).fold(f64::MIN, f64::max);
+        let mut membership = vec![0.0; n];
+        let mut total = 0.0;
+        for (log_weight, top) in &draws {
+            let weight = (log_weight - max).exp();
+            total += weight;
+            for &i in top {
+                membership[i] += weight;
+            }
+        }
+
+        let membership: Vec<f64> = membership.into_iter().map(|m| m / total).collect();
+        let confidence = membership[..k].iter().sum::<f64>() / k.max(1) as f64;
+        TopK {
+            k,
+            membership,
+            confidence,
+        }
+    }
+
+    /// Neighbouring pairs (i, i + 1) that are not yet separated with
+    /// `SETTLED_CONFIDENCE`, with their confidence
+    pub fn unsettled(&self) -> Vec<(usize, usize, f64)> {
+        (1..self.ratings.len())
+            .map(|i| (i - 1, i, self.confidence(i - 1, i)))
+            .filter(|(_, _, confidence)| *confidence < SETTLED_CONFIDENCE)
+            .collect()
+    }
+}
+
+/// Posterior view of which ideas form the top `k`
+#[derive(Debug, Clone)]
+pub struct TopK {
+    pub k: usize,
+    /// Probability of being in the top `k`, indexed like `Fit::ratings`
+    pub membership: Vec<f64>,
+    /// Expected fraction of the first `k` ratings that are in the true top
+    /// `k`. Unlike the chance of the exact set being right, this can reach a
+    /// high level with one comparison per pair.
+    pub confidence: f64,
+}
+
+/// Maximum-likelihood Bradley-Terry strengths (with a weak Gaussian prior)
+/// from all comparisons at once, so the result does not depend on the order
+/// the comparisons were made in. A tie counts as half a win for each side;
+/// comparisons whose winner is neither idea are ignored.
+pub fn fit(comparisons: &[Comparison]) -> Fit {
+    fit_with_prior(comparisons, &BTreeMap::new(), PRIOR_VARIANCE)
+}
+
+/// Like `fit`, but centres the prior of each idea in `prior` on the given
+/// log-strength instead of 0, with `prior_variance` saying how far the true
+/// strengths may stray from it. Ideas in `prior` are rated even before their
+/// first comparison.
+pub fn fit_with_prior(
+    comparisons: &[Comparison],
+    prior: &BTreeMap<String, f64>,
+    prior_variance: f64,
+) -> Fit {
+    // (a, b, score of a): 1 = a won, 0 = b won, 0.5 = tie
+    let decided: Vec<(&str, &str, f64)> = comparisons
+        .iter()
+        .filter(|c| c.idea_a != c.idea_b)
+        .filter_map(|c| Some((c.idea_a.as_str(), c.idea_b.as_str(), c.score_a()?)))
+        .collect();
+
+    let mut index: BTreeMap<&str, usize> = BTreeMap::new();
+    for id in prior.keys() {
+        let next = index.len();
+        index.insert(id.as_str(), next);
+    }
+    for &(a, b, _) in &decided {
+        for id in [a, b] {
+            let next = index.len();
+            index.entry(id).or_insert(next);
+        }
+    }
+    let n = index.len();
+
+    let mut games: Vec<(usize, usize, f64)> = Vec::new();
+    let mut records = vec![(0u32, 0u32, 0u32); n];
+    for &(a, b, score) in &decided {
+        let (a, b) = (index[a], index[b]);
+        games.push((a, b, score));
+        let (winner, loser) = match score {
+            s if s > 0.5 => (a, b),
+            s if s < 0.5 => (b, a),
+            _ => {
+                records[a].2 += 1;
+                records[b].2 += 1;
+                continue;
+            }
+        };
+        records[winner].0 += 1;
+        records[loser].1 += 1;
+    }
+
+    // Newton-Raphson on the log-posterior; the negated Hessian is positive
+    // definite thanks to the prior, and its inverse is the covariance
+    let mut mean = vec![0.0; n];
+    for (id, &i) in &index {
+        mean[i] = prior.get(*id).copied().unwrap_or(0.0);
+    }
+    let mut theta = mean.clone();
+    let mut covariance = vec![vec![0.0; n]; n];
+    for _ in 0..MAX_ITERATIONS {
+        let mut gradient: Vec<f64> = theta
+            .iter()
+            .zip(&mean)
+            .map(|(t, m)| -(t - m) / prior_variance)
+            .collect();
+        let mut information = vec![vec![0.0; n]; n];
+        for (i, row) in information.iter_mut().enumerate() {
+            row[i] = 1.0 / prior_variance;
+        }
+        for &(a, b, score) in &games {
+            let p = logistic(theta[a] - theta[b]);
+            gradient[a] += score - p;
+            gradient[b] -= score - p;
+            let w = p * (1.0 - p);
+            information[a][a] += w;
+            information[b][b] += w;
+            information[a][b] -= w;
+            information[b][a] -= w;
+        }
+
+        covariance = invert(information);
+        let step: Vec<f64> = covariance
+            .iter()
+            .map(|row| row.iter().zip(&gradient).map(|(c, g)| c * g).sum())
+            .collect();
+        for (t, s) in theta.iter_mut().zip(&step) {
+            *t += s;
+        }
+        if step.iter().all(|s| s.abs() < TOLERANCE) {
+            break;
+        }
+    }
+
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&a, &b| {
+        theta[b]
+            .partial_cmp(&theta[a])
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    let ids: Vec<&str> = {
+        let mut ids = vec![""; n];
+        for (id, &i) in &index {
+            ids[i] = id;
+        }
+        ids
+    };
+
+    Fit {
+        ratings: order
+            .iter()
+            .map(|&i| Rating {
+                id: ids[i].to_string(),
+                rating: ELO_BASE + theta[i] * ELO_SCALE,
+                std_error: covariance[i][i].sqrt() * ELO_SCALE,
+                wins: records[i].0,
+                losses: records[i].1,
+                ties: records[i].2,
+            })
+            .collect(),
+        comparisons: games.len(),
+        covariance: order
+            .iter()
+            .map(|&i| order.iter().map(|&j| covariance[i][j]).collect())
+            .collect(),
+        theta: order.iter().map(|&i| theta[i]).collect(),
+        games: {
+            let mut position = vec![0; n];
+            for (p, &i) in order.iter().enumerate() {
+                position[i] = p;
+            }
+            games
+                .iter()
+                .map(|&(a, b, score)| (position[a], position[b], score))
+                .collect()
+        },
+        prior_mean: order.iter().map(|&i| mean[i]).collect(),
+        prior_variance,
+    }
+}
+
+fn logistic(x: f64) -> f64 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+/// ln(logistic(x)) without overflow for large |x|
+fn log_logistic(x: f64) -> f64 {
+    if x >= 0.0 {
+        -(-x).exp().ln_1p()
+    } else {
+        x - x.exp().ln_1p()
+    }
+}
+
+/// Standard normal CDF via the Abramowitz-Stegun approximation of erf (error < 1.5e-7)
+fn normal_cdf(z: f64) -> f64 {
+    let x = z.abs() / std::f64::consts::SQRT_2;
+    let t = 1.0 / (1.0 + 0.3275911 * x);
+    let poly = t
+        * (0.254829592
+            + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+    let erf = 1.0 - poly * (-x * x).exp();
+    if z >= 0.0 {
+        0.5 * (1.0 + erf)
+    } else {
+        0.5 * (1.0 - erf)
+    }
+}
+
+/// Draw from N(0, 1) (Box-Muller)
+fn standard_normal(rng: &mut impl Rng) -> f64 {
+    let u1: f64 = 1.0 - rng.gen::<f64>();
+    let u2: f64 = rng.gen();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+}
+
+/// Lower-triangular L with L·Lᵀ = m, for a symmetric positive definite m
+fn cholesky(m: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    let n = m.len();
+    let mut l = vec![vec![0.0; n]; n];
+    for i in 0..n {
+        for j in 0..=i {
+            let sum: f64 = (0..j).map(|k| l[i][k] * l[j][k]).sum();
+            l[i][j] = if i == j {
+                (m[i][i] - sum).max(0.0).sqrt()
+            } else if l[j][j] > 0.0 {
+                (m[i][j] - sum) / l[j][j]
+            } else {
+                0.0
+            };
+        }
+    }
+    l
+}
+
+/// Inverse of a symmetric positive definite matrix (Gauss-Jordan elimination)
+fn invert(mut m: Vec<Vec<f64>>) -> Vec<Vec<f64>> {
+    let n = m.len();
+    let mut inv: Vec<Vec<f64>> = (0..n)
+        .map(|i| (0..n).map(|j| if i == j { 1.0 } else { 0.0 }).collect())
+        .collect();
+    for col in 0..n {
+        let pivot = (col..n)
+            .max_by(|&a, &b| {
+                m[a][col]
+                    .abs()
+                    .partial_cmp(&m[b][col].abs())
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+            .unwrap_or(col);
+        m.swap(col, pivot);
+        inv.swap(col, pivot);
+        let p = m[col][col];
+        for j in 0..n {
+            m[col][j] /= p;
+            inv[col][j] /= p;
+        }
+        for row in 0..n {
+            if row != col {
+                let factor = m[row][col];
+                if factor != 0.0 {
+                    for j in 0..n {
+                        m[row][j] -= factor * m[col][j];
+                        inv[row][j] -= factor * inv[col][j];
+                    }
+                }
+            }
+        }
+    }
+    inv
+}
+
+/// Print the fitted ratings and whether the ranking is settled; `label` turns
+/// an idea id into the text shown for it
+pub fn print_fit(fit: &Fit, label: impl Fn(&str) -> String) {
+    if fit.ratings.is_empty() {
+        println!("No decided comparisons yet");
+        return;
+    }
+    for (rank, r) in fit.ratings.iter().enumerate() {
+        println!(
+            "{}. [{:.0} ± {:.0}] {}  ({}W {}L {}T)",
+            rank + 1,
+            r.rating,
+            r.std_error,
+            label(&r.id).trim_end(),
+            r.wins,
+            r.losses,
+            r.ties
+        );
+    }
+
+    let unsettled = fit.unsettled();
+    if unsettled.is_empty() {
+        println!(
+            "\nRanking is settled: every idea beats the next one with at least {:.0}% confidence.",
+            SETTLED_CONFIDENCE * 100.0
+        );
+    } else {
+        println!(
+            "\nRanking needs more comparisons; {} of {} neighbouring pairs are not separated yet:",
+            unsettled.len(),
+            fit.ratings.len() - 1
+        );
+        for (i, j, confidence) in unsettled {
+            println!(
+                "  {}. {} vs {}. {} ({:.0}% confident)",
+                i + 1,
+                fit.ratings[i].id,
+                j + 1,
+                fit.ratings[j].id,
+                confidence * 100.0
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn comparison(a: &str, b: &str, winner: &str) -> Comparison {
+        Comparison::decided(a, b, winner)
+    }
+
+    fn ids(fit: &Fit) -> Vec<&str> {
+        fit.ratings.iter().map(|r| r.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_fit_ranks_consistent_preferences() {
+        let mut comparisons = Vec::new();
+        for _ in 0..3 {
+            comparisons.push(comparison("b", "a", "a"));
+            comparisons.push(comparison("b", "c", "b"));
+            comparisons.push(comparison("a", "c", "a"));
+        }
+        let fit = fit(&comparisons);
+        assert_eq!(ids(&fit), vec!["a", "b", "c"]);
+        assert_eq!(fit.comparisons, 9);
+        assert_eq!((fit.ratings[0].wins, fit.ratings[0].losses), (6, 0));
+        // The prior centres strengths on 1000
+        let mean = fit.ratings.iter().map(|r| r.rating).sum::<f64>() / 3.0;
+        assert!((mean - 1000.0).abs() < 1e-6);
+        assert!(fit.confidence(0, 2) > fit.confidence(0, 1));
+        assert!(fit.confidence(0, 1) > 0.5);
+        assert!((fit.confidence(0, 1) + fit.confidence(1, 0) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_fit_is_independent_of_comparison_order() {
+        let mut comparisons = vec![
+            comparison("a", "b", "a"),
+            comparison("b", "c", "c"),
+            comparison("a", "c", "a"),
+            comparison("c", "b", "b"),
+            comparison("a", "b", "b"),
+        ];
+        let forward = fit(&comparisons);
+        comparisons.reverse();
+        let backward = fit(&comparisons);
+        assert_eq!(ids(&forward), ids(&backward));
+        for (f, b) in forward.ratings.iter().zip(&backward.ratings) {
+            assert!((f.rating - b.rating).abs() < 1e-6);
+            assert!((f.std_error - b.std_error).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_ties_and_settling() {
+        let tied = fit(&[Comparison::tie("a", "b")]);
+        assert!((tied.ratings[0].rating - tied.ratings[1].rating).abs() < 1e-6);
+        assert_eq!(tied.ratings[0].ties, 1);
+        assert_eq!(tied.unsettled().len(), 1);
+
+        // One win is not enough to settle a pair; ten in a row is
+        let once = fit(&[comparison("a", "b", "a")]);
+        assert_eq!(once.unsettled().len(), 1);
+        let many: Vec<Comparison> = (0..10).map(|_| comparison("a", "b", "a")).collect();
+        let settled = fit(&many);
+        assert!(settled.unsettled().is_empty());
+        assert!(settled.ratings[0].std_error < once.ratings[0].std_error);
+
+        // Unknown winners are ignored
+        let ignored = fit(&[comparison("a", "b", "c")]);
+        assert_eq!(ignored.comparisons, 0);
+        assert!(ignored.ratings.is_empty());
+    }
+
+    #[test]
+    fn test_prior_ranks_uncompared_ideas() {
+        let prior = BTreeMap::from([
+            ("a".to_string(), 1.0),
+            ("b".to_string(), 0.0),
+            ("c".to_string(), -1.0),
+        ]);
+        let fit = fit_with_prior(&[], &prior, PRIOR_VARIANCE);
+        assert_eq!(ids(&fit), vec!["a", "b", "c"]);
+        assert_eq!(fit.comparisons, 0);
+
+        // Evidence overrides the prior
+        let comparisons: Vec<Comparison> = (0..6).map(|_| comparison("a", "c", "c")).collect();
+        let fit = fit_with_prior(&comparisons, &prior, PRIOR_VARIANCE);
+        assert!(fit.index_of("c") < fit.index_of("a"));
+    }
+
+    #[test]
+    fn test_information_gain_prefers_close_uncertain_pairs() {
+        let prior = BTreeMap::from([
+            ("a".to_string(), 0.1),
+            ("b".to_string(), 0.0),
+            ("c".to_string(), -3.0),
+        ]);
+        let comparisons: Vec<Comparison> = (0..8).map(|_| comparison("b", "c", "b")).collect();
+        let fit = fit_with_prior(&comparisons, &prior, PRIOR_VARIANCE);
+        let (a, b, c) = (
+            fit.index_of("a").unwrap(),
+            fit.index_of("b").unwrap(),
+            fit.index_of("c").unwrap(),
+        );
+        // a-b is close and untested; b-c has been settled many times over
+        assert!(fit.information_gain(a, b) > fit.information_gain(b, c));
+        assert!(fit.information_gain(a, b) > fit.information_gain(a, c));
+    }
+
+    #[test]
+    fn test_top_k_confidence_grows_with_evidence() {
+        let names = ["a", "b", "c", "d", "e"];
+        let mut comparisons = Vec::new();
+        let flat: BTreeMap<String, f64> = names.iter().map(|n| (n.to_string(), 0.0)).collect();
+        let before = fit_with_prior(&[comparison("a", "b", "a")], &flat, PRIOR_VARIANCE).top_k(2);
+        for _ in 0..4 {
+            for (i, x) in names.iter().enumerate() {
+                for y in &names[i + 1..] {
+                    comparisons.push(comparison(x, y, x));
+                }
+            }
+        }
+        let fit = fit(&comparisons);
+        let top = fit.top_k(2);
+        assert_eq!(ids(&fit)[..2], ["a", "b"]);
+        assert!(top.confidence > TOP_K_CONFIDENCE);
+        assert!(top.confidence > before.confidence);
+        assert!(top.membership[0] > 0.99 && top.membership[4] < 0.01);
+        let total: f64 = top.membership.iter().sum();
+        assert!((total - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_normal_cdf() {
+        assert!((normal_cdf(0.0) - 0.5).abs() < 1e-7);
+        assert!((normal_cdf(1.96) - 0.975).abs() < 1e-4);
+        assert!((normal_cdf(-1.0) - 0.158655).abs() < 1e-5);
+    }
+}
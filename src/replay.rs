@@ -0,0 +1,255 @@
+use anyhow::{Context, Result};
+
+use crate::data::{
+    from_value, ConstraintViolation, EventType, FinalResult, HistoryEvent, Idea, IdeaStatus,
+    RunState, Scores,
+};
+use crate::evolution::replay_best;
+use crate::storage::Storage;
+
+/// Rebuild the run state as it stood at the end of `iteration`, using only the
+/// events in history.ndjson
+pub fn replay_state(run_id: &str, history: &[HistoryEvent], iteration: u32) -> Result<RunState> {
+    let last = history.last().map(|e| e.iteration).unwrap_or(0);
+    if iteration > last {
+        anyhow::bail!(
+            "Run {} only reaches iteration {} in history.ndjson",
+            run_id,
+            last
+        );
+    }
+
+    let mut state = RunState::new(run_id);
+    state.iteration = iteration;
+    for (line, event) in history.iter().enumerate() {
+        if event.iteration > iteration {
+            break;
+        }
+        apply_event(&mut state, event)
+            .with_context(|| format!("Cannot replay history.ndjson line {}", line + 1))?;
+    }
+    replay_best(&mut state, history, iteration);
+
+    Ok(state)
+}
+
+/// Load history.ndjson and replay it up to `iteration`
+pub fn load_snapshot(storage: &dyn Storage, run_id: &str, iteration: u32) -> Result<RunState> {
+    if !storage.run_exists(run_id)? {
+        anyhow::bail!("Run {} not found", run_id);
+    }
+    let history = storage.load_history(run_id)?;
+    replay_state(run_id, &history, iteration)
+}
+
+/// Summarize a replayed state the way final.json summarizes a finished run
+pub fn snapshot_result(state: &RunState) -> Result<FinalResult> {
+    let best_idea = state
+        .best_idea_id
+        .as_deref()
+        .and_then(|id| state.idea(id))
+        .cloned()
+        .ok_or_else(|| anyhow::anyhow!("No scored ideas at iteration {}", state.iteration))?;
+
+    Ok(FinalResult {
+        run_id: state.run_id.clone(),
+        best_idea,
+        runner_up: state.runner_up().cloned(),
+        iterations_completed: state.iteration,
+        stop_reason: format!("replayed at iteration {}", state.iteration),
+        why_won: Vec::new(),
+    })
+}
+
+fn apply_event(state: &mut RunState, event: &HistoryEvent) -> Result<()> {
+    let payload = &event.payload;
+    let list = |key: &str| {
+        payload
+            .get(key)
+            .and_then(|v| v.as_array())
+            .cloned()
+            .unwrap_or_default()
+    };
+
+    match event.event_type {
+        EventType::Generated | EventType::Crossover | EventType::Mutated | EventType::Refined => {
+            for value in list("ideas") {
+                let idea: Idea = from_value(value)?;
+                state.ideas.retain(|i| i.id != idea.id);
+                state.ideas.push(idea);
+            }
+            archive(state, &list("archived"));
+        }
+        EventType::Scored => {
+            for result in list("results") {
+                let id = id_of(&result)?;
+                let scores: Scores = from_value(result.get("scores").cloned().unwrap_or_default())?;
+                let notes = judge_notes(payload, id);
+                let idea = idea_mut(state, id)?;
+                idea.scores = Some(scores);
+                idea.overall_score = result
+                    .get("overall_score")
+                    .and_then(|s| s.as_f64())
+                    .map(|s| s as f32);
+                idea.judge_notes = notes;
+            }
+            for eliminated in list("eliminated") {
+                let id = id_of(&eliminated)?;
+                let violations: Vec<ConstraintViolation> =
+                    from_value(eliminated.get("violations").cloned().unwrap_or_default())?;
+                let idea = idea_mut(state, id)?;
+                idea.overall_score = Some(0.0);
+                idea.status = IdeaStatus::Eliminated { violations };
+            }
+        }
+        EventType::Selected => archive(state, &list("archived")),
+        EventType::Stopped => {}
+    }
+
+    Ok(())
+}
+
+fn archive(state: &mut RunState, ids: &[serde_json::Value]) {
+    for id in ids.iter().filter_map(|id| id.as_str()) {
+        if let Some(idea) = state.ideas.iter_mut().find(|i| i.id == id) {
+            idea.status = IdeaStatus::Archived;
+        }
+    }
+}
+
+fn id_of(value: &serde_json::Value) -> Result<&str> {
+    value
+        .get("id")
+        .and_then(|id| id.as_str())
+        .ok_or_else(|| anyhow::anyhow!("entry without an id: {}", value))
+}
+
+fn idea_mut<'a>(state: &'a mut RunState, id: &str) -> Result<&'a mut Idea> {
+    state
+        .ideas
+        .iter_mut()
+        .find(|i| i.id == id)
+        .ok_or_else(|| anyhow::anyhow!("event refers to unknown idea {}", id))
+}
+
+/// Judge notes are only in the raw critic output, not in the scored results
+fn judge_notes(payload: &serde_json::Value, id: &str) -> Option<String> {
+    payload
+        .pointer("/output/patches")?
+        .as_array()?
+        .iter()
+        .find(|patch| patch.get("id").and_then(|v| v.as_str()) == Some(id))?
+        .get("judge_notes")?
+        .as_str()
+        .map(String::from)
+}
+
+/// Print the population snapshot at `iteration`
+pub fn replay_run(storage: &dyn Storage, run_id: &str, iteration: u32, format: &str) -> Result<()> {
+    let state = load_snapshot(storage, run_id, iteration)?;
+
+    match format {
+        "json" => println!("{}", serde_json::to_string_pretty(&state)?),
+        "text" => print_snapshot(&state),
+        _ => anyhow::bail!("Unknown format: {} (supported: text, json)", format),
+    }
+
+    Ok(())
+}
+
+fn print_snapshot(state: &RunState) {
+    let archived = state
+        .ideas
+        .iter()
+        .filter(|i| i.status == IdeaStatus::Archived)
+        .count();
+    println!("Run {} at iteration {}", state.run_id, state.iteration);
+    println!(
+        "Ideas: {} ({} active, {} archived, {} eliminated)",
+        state.ideas.len(),
+        state.active_ideas().count(),
+        archived,
+        state.eliminated_ideas().count()
+    );
+
+    match state.best_idea_id.as_deref().and_then(|id| state.idea(id)) {
+        Some(best) => println!(
+            "Best: {} {} [{:.2}] (stagnation: {})",
+            best.id,
+            best.title,
+            best.overall_score.unwrap_or(0.0),
+            state.stagnation_counter
+        ),
+        None => println!("Best: - (nothing scored yet)"),
+    }
+
+    let mut active: Vec<&Idea> = state.active_ideas().collect();
+    active.sort_by(|a, b| {
+        b.overall_score
+            .partial_cmp(&a.overall_score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    if !active.is_empty() {
+        println!("\nActive ideas:");
+        for idea in active {
+            let score = idea
+                .overall_score
+                .map(|s| format!("{:.2}", s))
+                .unwrap_or_else(|| "-".to_string());
+            println!("  [{}] {} {}", score, idea.id, idea.title);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evolution::tests::evolve_mock_run;
+    use crate::storage::FileStorage;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_replay_last_iteration_matches_state() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = FileStorage::new(temp_dir.path());
+        let result = evolve_mock_run(&storage, "run-test", 3);
+
+        let state = storage.load_state("run-test").unwrap();
+        let replayed = load_snapshot(&storage, "run-test", 3).unwrap();
+        assert_eq!(replayed, state);
+
+        let snapshot = snapshot_result(&replayed).unwrap();
+        assert_eq!(snapshot.best_idea, result.best_idea);
+        assert_eq!(snapshot.runner_up, result.runner_up);
+    }
+
+    #[test]
+    fn test_replay_earlier_iteration_stops_at_its_events() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = FileStorage::new(temp_dir.path());
+        evolve_mock_run(&storage, "run-test", 3);
+
+        let history = storage.load_history("run-test").unwrap();
+        let snapshot = replay_state("run-test", &history, 1).unwrap();
+        assert_eq!(snapshot.iteration, 1);
+        assert!(snapshot.ideas.iter().all(|i| i.gen <= 1));
+        let created: usize = history
+            .iter()
+            .filter(|e| e.iteration <= 1)
+            .map(|e| e.created_ids().len())
+            .sum();
+        assert_eq!(snapshot.ideas.len(), created);
+        assert!(snapshot.best_idea_id.is_some());
+
+        let empty = replay_state("run-test", &history, 0).unwrap();
+        assert!(empty.ideas.is_empty());
+        assert!(snapshot_result(&empty).is_err());
+
+        let err = replay_state("run-test", &history, 4).unwrap_err();
+        assert!(
+            err.to_string().contains("only reaches iteration 3"),
+            "{}",
+            err
+        );
+    }
+}
@@ -0,0 +1,358 @@
+use serde_json::Value;
+
+/// JSON Schemas bundled from the `schemas/` directory at build time
+const BUNDLED: [(&str, &str); 8] = [
+    (
+        "generator",
+        include_str!("../schemas/generator.output.schema.json"),
+    ),
+    (
+        "critic",
+        include_str!("../schemas/critic.output.schema.json"),
+    ),
+    (
+        "merger",
+        include_str!("../schemas/merger.output.schema.json"),
+    ),
+    (
+        "mutator",
+        include_str!("../schemas/mutator.output.schema.json"),
+    ),
+    (
+        "refiner",
+        include_str!("../schemas/refiner.output.schema.json"),
+    ),
+    ("final", include_str!("../schemas/final.output.schema.json")),
+    ("idea", include_str!("../schemas/idea.schema.json")),
+    (
+        "run-final",
+        include_str!("../schemas/run-final.schema.json"),
+    ),
+];
+
+/// A single schema violation, located by JSON pointer
+#[derive(Debug, Clone, PartialEq)]
+pub struct Violation {
+    pub path: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let path = if self.path.is_empty() {
+            "/"
+        } else {
+            &self.path
+        };
+        write!(f, "{}: {}", path, self.message)
+    }
+}
+
+/// Load a bundled schema by short name (e.g. "generator", "critic")
+pub fn bundled(name: &str) -> anyhow::Result<Value> {
+    let (_, raw) = BUNDLED
+        .iter()
+        .find(|(n, _)| *n == name)
+        .ok_or_else(|| anyhow::anyhow!("Unknown schema: {}", name))?;
+    Ok(serde_json::from_str(raw)?)
+}
+
+/// Validate `instance` against `schema`.
+/// Supports the subset of JSON Schema used by the bundled schemas: type, enum, const,
+/// properties, required, additionalProperties, items, minItems/maxItems,
+/// minimum/maximum, minLength/maxLength, anyOf and `$ref` to another bundled
+/// schema by file name (e.g. "idea.schema.json").
+pub fn validate(schema: &Value, instance: &Value) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    validate_at(schema, instance, "", &mut violations);
+    violations
+}
+
+fn validate_at(schema: &Value, instance: &Value, path: &str, out: &mut Vec<Violation>) {
+    let Some(schema) = schema.as_object() else {
+        return;
+    };
+
+    if let Some(reference) = schema.get("$ref").and_then(|r| r.as_str()) {
+        match resolve_ref(reference) {
+            Some(target) => validate_at(&target, instance, path, out),
+            None => out.push(Violation {
+                path: path.to_string(),
+                message: format!("unresolved $ref {}", reference),
+            }),
+        }
+        return;
+    }
+
+    if let Some(branches) = schema.get("anyOf").and_then(|a| a.as_array()) {
+        let matches_any = branches.iter().any(|branch| {
+            let mut scratch = Vec::new();
+            validate_at(branch, instance, path, &mut scratch);
+            scratch.is_empty()
+        });
+        if !matches_any {
+            out.push(Violation {
+                path: path.to_string(),
+                message: format!(
+                    "value does not match any of {} allowed schemas",
+                    branches.len()
+                ),
+            });
+        }
+        return;
+    }
+
+    let mut push = |message: String| {
+        out.push(Violation {
+            path: path.to_string(),
+            message,
+        })
+    };
+
+    if let Some(expected) = schema.get("type") {
+        let types: Vec<&str> = match expected {
+            Value::String(t) => vec![t.as_str()],
+            Value::Array(ts) => ts.iter().filter_map(|t| t.as_str()).collect(),
+            _ => Vec::new(),
+        };
+        if !types.is_empty() && !types.iter().any(|t| matches_type(t, instance)) {
+            push(format!(
+                "expected {}, found {}",
+                types.join(" or "),
+                type_name(instance)
+            ));
+            return;
+        }
+    }
+
+    if let Some(allowed) = schema.get("enum").and_then(|e| e.as_array()) {
+        if !allowed.contains(instance) {
+            push(format!(
+                "value {} is not one of {}",
+                instance,
+                Value::from(allowed.clone())
+            ));
+        }
+    }
+
+    if let Some(expected) = schema.get("const") {
+        if expected != instance {
+            push(format!("value {} does not equal {}", instance, expected));
+        }
+    }
+
+    if let Some(n) = instance.as_f64() {
+        if let Some(min) = schema.get("minimum").and_then(|m| m.as_f64()) {
+            if n < min {
+                push(format!("{} is less than minimum {}", n, min));
+            }
+        }
+        if let Some(max) = schema.get("maximum").and_then(|m| m.as_f64()) {
+            if n > max {
+                push(format!("{} is greater than maximum {}", n, max));
+            }
+        }
+    }
+
+    if let Some(s) = instance.as_str() {
+        let len = s.chars().count() as u64;
+        if let Some(min) = schema.get("minLength").and_then(|m| m.as_u64()) {
+            if len < min {
+                push(format!("string shorter than {} characters", min));
+            }
+        }
+        if let Some(max) = schema.get("maxLength").and_then(|m| m.as_u64()) {
+            if len > max {
+                push(format!("string longer than {} characters", max));
+            }
+        }
+    }
+
+    if let Some(items) = instance.as_array() {
+        if let Some(min) = schema.get("minItems").and_then(|m| m.as_u64()) {
+            if (items.len() as u64) < min {
+                push(format!(
+                    "expected at least {} items, found {}",
+                    min,
+                    items.len()
+                ));
+            }
+        }
+        if let Some(max) = schema.get("maxItems").and_then(|m| m.as_u64()) {
+            if (items.len() as u64) > max {
+                push(format!(
+                    "expected at most {} items, found {}",
+                    max,
+                    items.len()
+                ));
+            }
+        }
+        if let Some(item_schema) = schema.get("items") {
+            for (i, item) in items.iter().enumerate() {
+                validate_at(item_schema, item, &format!("{}/{}", path, i), out);
+            }
+        }
+    }
+
+    if let Some(obj) = instance.as_object() {
+        let properties = schema.get("properties").and_then(|p| p.as_object());
+
+        if let Some(required) = schema.get("required").and_then(|r| r.as_array()) {
+            for key in required.iter().filter_map(|k| k.as_str()) {
+                if !obj.contains_key(key) {
+                    out.push(Violation {
+                        path: path.to_string(),
+                        message: format!("missing required property '{}'", key),
+                    });
+                }
+            }
+        }
+
+        for (key, value) in obj {
+            let child_path = format!("{}/{}", path, escape_pointer(key));
+            match properties.and_then(|p| p.get(key)) {
+                Some(prop_schema) => validate_at(prop_schema, value, &child_path, out),
+                None => match schema.get("additionalProperties") {
+                    Some(Value::Bool(false)) => out.push(Violation {
+                        path: child_path,
+                        message: "additional property not allowed".to_string(),
+                    }),
+                    Some(extra @ Value::Object(_)) => validate_at(extra, value, &child_path, out),
+                    _ => {}
+                },
+            }
+        }
+    }
+}
+
+/// Resolve a `$ref` such as "idea.schema.json" to the bundled schema of that name
+fn resolve_ref(reference: &str) -> Option<Value> {
+    let name = reference.strip_suffix(".schema.json")?;
+    bundled(name).ok()
+}
+
+fn matches_type(expected: &str, instance: &Value) -> bool {
+    match expected {
+        "object" => instance.is_object(),
+        "array" => instance.is_array(),
+        "string" => instance.is_string(),
+        "number" => instance.is_number(),
+        "integer" => {
+            instance.is_i64()
+                || instance.is_u64()
+                || instance.as_f64().is_some_and(|n| n.fract() == 0.0)
+        }
+        "boolean" => instance.is_boolean(),
+        "null" => instance.is_null(),
+        _ => true,
+    }
+}
+
+fn type_name(instance: &Value) -> &'static str {
+    match instance {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Escape a key for use in a JSON pointer (RFC 6901)
+fn escape_pointer(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn facets() -> Value {
+        serde_json::json!({
+            "audience": "devs",
+            "jtbd": "ship",
+            "differentiator": "fast",
+            "monetization": "saas",
+            "distribution": "hn",
+            "risks": "none"
+        })
+    }
+
+    #[test]
+    fn test_all_bundled_schemas_parse() {
+        for (name, _) in BUNDLED {
+            assert!(bundled(name).is_ok(), "{} failed to parse", name);
+        }
+    }
+
+    #[test]
+    fn test_valid_generator_output_passes() {
+        let schema = bundled("generator").unwrap();
+        let output = serde_json::json!({
+            "ideas": [{ "title": "T", "summary": "S", "facets": facets() }]
+        });
+        assert!(validate(&schema, &output).is_empty());
+    }
+
+    #[test]
+    fn test_violations_report_json_pointer_paths() {
+        let schema = bundled("generator").unwrap();
+        let mut bad_facets = facets();
+        bad_facets.as_object_mut().unwrap().remove("risks");
+        let output = serde_json::json!({
+            "ideas": [{ "title": 1, "summary": "S", "facets": bad_facets, "extra": true }]
+        });
+
+        let violations = validate(&schema, &output);
+        let paths: Vec<&str> = violations.iter().map(|v| v.path.as_str()).collect();
+        assert!(paths.contains(&"/ideas/0/title"));
+        assert!(paths.contains(&"/ideas/0/facets"));
+        assert!(paths.contains(&"/ideas/0/extra"));
+    }
+
+    #[test]
+    fn test_run_final_resolves_idea_refs() {
+        let schema = bundled("run-final").unwrap();
+        let idea = serde_json::json!({
+            "id": "idea-001",
+            "origin": "generated",
+            "title": "T",
+            "status": "active",
+            "facets": facets()
+        });
+        let output = serde_json::json!({
+            "run_id": "run-1",
+            "best_idea": idea,
+            "runner_up": null
+        });
+        assert!(validate(&schema, &output).is_empty());
+
+        let output = serde_json::json!({
+            "run_id": "run-1",
+            "best_idea": { "id": "idea-001", "origin": "cloned", "title": "T", "status": "active" },
+            "runner_up": 3
+        });
+        let violations = validate(&schema, &output);
+        let paths: Vec<&str> = violations.iter().map(|v| v.path.as_str()).collect();
+        assert_eq!(paths, vec!["/best_idea/origin", "/runner_up"]);
+    }
+
+    #[test]
+    fn test_score_range_and_enum_are_checked() {
+        let schema = bundled("mutator").unwrap();
+        let output = serde_json::json!({
+            "mutation_type": "pricing",
+            "idea": { "title": "T", "summary": "S", "facets": facets() }
+        });
+        let violations = validate(&schema, &output);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].path, "/mutation_type");
+
+        let critic = bundled("critic").unwrap();
+        let output = serde_json::json!({ "patches": [] });
+        assert!(validate(&critic, &output)
+            .iter()
+            .any(|v| v.message.contains("at least 1")));
+    }
+}
@@ -2,7 +2,6 @@ use crate::config::ScoringWeights;
 use crate::data::Scores;
 
 /// Calculate overall score using weighted sum.
-#[allow(dead_code)]
 /// Risk is inverted: (10 - risk) * weight
 pub fn calculate_overall_score(scores: &Scores, weights: &ScoringWeights) -> f32 {
     let weighted_sum = scores.feasibility * weights.feasibility
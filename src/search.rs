@@ -0,0 +1,561 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+
+use crate::config::tokenize;
+use crate::data::{Facets, FinalResult, Idea, RunState};
+use crate::storage::{write_atomic, Storage};
+
+/// Cache of extracted documents and their term statistics, kept in the runs directory
+const INDEX_FILE: &str = ".search-index.json";
+const INDEX_VERSION: u32 = 2;
+
+/// Scope key of a search over every field
+const ALL_FIELDS: &str = "*";
+
+const STATUSES: [&str; 3] = ["active", "archived", "eliminated"];
+
+// BM25 parameters (the usual defaults)
+const K1: f64 = 1.2;
+const B: f64 = 0.75;
+
+/// One idea of one run, flattened into searchable text fields
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Document {
+    pub run_id: String,
+    pub idea_id: String,
+    pub title: String,
+    pub overall_score: Option<f32>,
+    pub status: String,
+    /// `title`, `summary` and `facets.<name>` texts
+    pub fields: BTreeMap<String, String>,
+    /// Tokenized `fields`, so ranking never re-reads the text
+    pub terms: BTreeMap<String, FieldTerms>,
+}
+
+/// Bag of words of one field
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct FieldTerms {
+    /// Number of words
+    pub len: u32,
+    /// Occurrences of each distinct word
+    pub tf: BTreeMap<String, u32>,
+}
+
+impl FieldTerms {
+    fn new(text: &str) -> Self {
+        let mut terms = Self::default();
+        for word in tokenize(text) {
+            terms.len += 1;
+            *terms.tf.entry(word).or_insert(0) += 1;
+        }
+        terms
+    }
+}
+
+impl Document {
+    pub fn from_idea(run_id: &str, idea: &Idea) -> Self {
+        let mut fields = BTreeMap::from([
+            ("title".to_string(), idea.title.clone()),
+            ("summary".to_string(), idea.summary.clone()),
+        ]);
+        for (name, text) in idea.facets.fields() {
+            fields.insert(format!("facets.{}", name), text.to_string());
+        }
+        let terms = fields
+            .iter()
+            .map(|(key, text)| (key.clone(), FieldTerms::new(text)))
+            .collect();
+
+        Self {
+            run_id: run_id.to_string(),
+            idea_id: idea.id.clone(),
+            title: idea.title.clone(),
+            overall_score: idea.overall_score,
+            status: idea.status.as_str().to_string(),
+            fields,
+            terms,
+        }
+    }
+
+    /// Texts of the fields selected by `field` (all fields if `None`)
+    fn texts<'a>(&'a self, field: Option<&'a str>) -> impl Iterator<Item = (&'a str, &'a str)> {
+        self.fields
+            .iter()
+            .filter(move |(key, _)| field_matches(key, field))
+            .map(|(key, text)| (key.as_str(), text.as_str()))
+    }
+
+    /// Term statistics of the fields selected by `field`
+    fn field_terms<'a>(&'a self, field: Option<&'a str>) -> impl Iterator<Item = &'a FieldTerms> {
+        self.terms
+            .iter()
+            .filter(move |(key, _)| field_matches(key, field))
+            .map(|(_, terms)| terms)
+    }
+
+    /// Words in the fields selected by `field`
+    fn len(&self, field: Option<&str>) -> u32 {
+        self.field_terms(field).map(|t| t.len).sum()
+    }
+
+    /// Occurrences of `term` in the fields selected by `field`
+    fn tf(&self, term: &str, field: Option<&str>) -> u32 {
+        self.field_terms(field).filter_map(|t| t.tf.get(term)).sum()
+    }
+}
+
+/// Scopes a search can be restricted to that include field `key`
+fn scopes_of(key: &str) -> Vec<&str> {
+    let mut scopes = vec![ALL_FIELDS, key];
+    if key.starts_with("facets.") {
+        scopes.push("facets");
+    }
+    scopes
+}
+
+/// Number of documents containing each term, per search scope
+/// (`*`, `facets` or a single field key)
+pub type DocFreqs = BTreeMap<String, BTreeMap<String, u32>>;
+
+fn doc_freqs(documents: &[Document]) -> DocFreqs {
+    let mut df = DocFreqs::new();
+    for doc in documents {
+        let mut seen: HashSet<(&str, &str)> = HashSet::new();
+        for (key, terms) in &doc.terms {
+            for scope in scopes_of(key) {
+                for term in terms.tf.keys() {
+                    if seen.insert((scope, term)) {
+                        *df.entry(scope.to_string())
+                            .or_default()
+                            .entry(term.clone())
+                            .or_insert(0) += 1;
+                    }
+                }
+            }
+        }
+    }
+    df
+}
+
+/// Documents of one or more runs with their cached document frequencies
+pub struct Corpus {
+    pub documents: Vec<Document>,
+    /// Document frequencies of each run, summed per query term when ranking
+    doc_freqs: Vec<DocFreqs>,
+}
+
+impl Corpus {
+    /// Corpus of documents that are not backed by the index
+    #[cfg(test)]
+    pub fn new(documents: Vec<Document>) -> Self {
+        let doc_freqs = vec![doc_freqs(&documents)];
+        Self {
+            documents,
+            doc_freqs,
+        }
+    }
+
+    fn df(&self, term: &str, field: Option<&str>) -> u32 {
+        let scope = field.unwrap_or(ALL_FIELDS);
+        self.doc_freqs
+            .iter()
+            .filter_map(|df| df.get(scope)?.get(term))
+            .sum()
+    }
+}
+
+fn field_matches(key: &str, field: Option<&str>) -> bool {
+    match field {
+        None => true,
+        Some("facets") => key.starts_with("facets."),
+        Some(field) => key == field,
+    }
+}
+
+/// Every idea of a run: the state.json population, or the final.json winners if
+/// the run has no state
+pub fn run_documents(
+    run_id: &str,
+    state: Option<&RunState>,
+    result: Option<&FinalResult>,
+) -> Vec<Document> {
+    match (state, result) {
+        (Some(state), _) => state
+            .ideas
+            .iter()
+            .map(|idea| Document::from_idea(run_id, idea))
+            .collect(),
+        (None, Some(result)) => std::iter::once(&result.best_idea)
+            .chain(result.runner_up.as_ref())
+            .map(|idea| Document::from_idea(run_id, idea))
+            .collect(),
+        (None, None) => Vec::new(),
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Index {
+    version: u32,
+    runs: BTreeMap<String, IndexedRun>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct IndexedRun {
+    /// Size and mtime of state.json and final.json when the run was indexed
+    stamp: String,
+    documents: Vec<Document>,
+    doc_freqs: DocFreqs,
+}
+
+/// Documents of every run. With a runs directory, only runs whose artifacts
+/// changed since the cached index was written are re-read and re-indexed.
+/// Returns the corpus and the ids of unreadable runs.
+pub fn load_documents(storage: &dyn Storage) -> Result<(Corpus, Vec<String>)> {
+    let index_path = storage.runs_dir().map(|dir| dir.join(INDEX_FILE));
+    let mut index = index_path
+        .as_ref()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|raw| serde_json::from_str::<Index>(&raw).ok())
+        .filter(|index| index.version == INDEX_VERSION)
+        .unwrap_or(Index {
+            version: INDEX_VERSION,
+            runs: BTreeMap::new(),
+        });
+
+    let run_ids = storage.run_ids()?;
+    let mut changed = index.runs.keys().any(|id| !run_ids.contains(id));
+    index.runs.retain(|id, _| run_ids.contains(id));
+
+    let mut skipped = Vec::new();
+    for run_id in &run_ids {
+        let stamp = storage.run_stamp(run_id);
+        let cached = index.runs.get(run_id);
+        if stamp.is_some() && cached.map(|run| &run.stamp) == stamp.as_ref() {
+            continue;
+        }
+        let loaded = (|| -> Result<Vec<Document>> {
+            let state = match storage.has_state(run_id)? {
+                true => Some(storage.load_state(run_id)?),
+                false => None,
+            };
+            let result = match storage.has_final(run_id)? {
+                true => Some(storage.load_final(run_id)?),
+                false => None,
+            };
+            Ok(run_documents(run_id, state.as_ref(), result.as_ref()))
+        })();
+        changed = true;
+        match loaded {
+            Ok(documents) => {
+                let doc_freqs = doc_freqs(&documents);
+                let run = IndexedRun {
+                    stamp: stamp.unwrap_or_default(),
+                    documents,
+                    doc_freqs,
+                };
+                index.runs.insert(run_id.clone(), run);
+            }
+            Err(_) => {
+                index.runs.remove(run_id);
+                skipped.push(run_id.clone());
+            }
+        }
+    }
+
+    let runs_dir_exists = storage.runs_dir().is_some_and(Path::exists);
+    if let Some(path) = index_path.filter(|_| changed && runs_dir_exists) {
+        write_atomic(&path, serde_json::to_string(&index)?.as_bytes())?;
+    }
+
+    let mut corpus = Corpus {
+        documents: Vec::new(),
+        doc_freqs: Vec::new(),
+    };
+    for run in index.runs.into_values() {
+        corpus.documents.extend(run.documents);
+        corpus.doc_freqs.push(run.doc_freqs);
+    }
+    Ok((corpus, skipped))
+}
+
+/// BM25 relevance of every document to `query`, restricted to `field`, scored from
+/// the cached term statistics. Returns (document index, score) for documents with a
+/// positive score, best first.
+pub fn rank(corpus: &Corpus, query: &str, field: Option<&str>) -> Vec<(usize, f64)> {
+    let terms: HashSet<String> = tokenize(query).into_iter().collect();
+    let docs = &corpus.documents;
+    if terms.is_empty() || docs.is_empty() {
+        return Vec::new();
+    }
+
+    let n = docs.len() as f64;
+    let lens: Vec<f64> = docs.iter().map(|d| d.len(field) as f64).collect();
+    let avg_len = (lens.iter().sum::<f64>() / n).max(1.0);
+    let idf: HashMap<&str, f64> = terms
+        .iter()
+        .map(|term| {
+            let df = corpus.df(term, field) as f64;
+            (term.as_str(), (1.0 + (n - df + 0.5) / (df + 0.5)).ln())
+        })
+        .collect();
+
+    let mut ranked: Vec<(usize, f64)> = docs
+        .iter()
+        .enumerate()
+        .map(|(i, doc)| {
+            let norm = K1 * (1.0 - B + B * lens[i] / avg_len);
+            let score = idf
+                .iter()
+                .map(|(term, idf)| {
+                    let tf = doc.tf(term, field) as f64;
+                    idf * tf * (K1 + 1.0) / (tf + norm)
+                })
+                .sum::<f64>();
+            (i, score)
+        })
+        .filter(|(_, score)| *score > 0.0)
+        .collect();
+    ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    ranked
+}
+
+/// Up to ~16 words of the selected field that mentions the most query terms,
+/// prefixed with the field name
+fn snippet(doc: &Document, query: &str, field: Option<&str>) -> String {
+    let terms: HashSet<String> = tokenize(query).into_iter().collect();
+    let hits = |text: &str| tokenize(text).iter().filter(|w| terms.contains(*w)).count();
+    let Some((key, text)) = doc
+        .texts(field)
+        .filter(|(key, text)| (*key != "title" || field == Some("title")) && hits(text) > 0)
+        .max_by_key(|(_, text)| hits(text))
+    else {
+        return String::new();
+    };
+
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let first = words.iter().position(|w| hits(w) > 0).unwrap_or(0);
+    let start = first.saturating_sub(5);
+    let end = (start + 16).min(words.len());
+    format!(
+        "{}: {}{}{}",
+        key,
+        if start > 0 { "..." } else { "" },
+        words[start..end].join(" "),
+        if end < words.len() { "..." } else { "" }
+    )
+}
+
+/// Search ideas across every run and print the best matches
+pub fn search_runs(
+    storage: &dyn Storage,
+    query: &str,
+    field: Option<&str>,
+    min_score: Option<f32>,
+    status: Option<&str>,
+    limit: usize,
+) -> Result<()> {
+    if let Some(field) = field {
+        let known = field == "facets"
+            || field == "title"
+            || field == "summary"
+            || field
+                .strip_prefix("facets.")
+                .is_some_and(|f| Facets::NAMES.contains(&f));
+        if !known {
+            anyhow::bail!(
+                "Unknown field: {} (supported: title, summary, facets, facets.{{{}}})",
+                field,
+                Facets::NAMES.join(",")
+            );
+        }
+    }
+    if let Some(status) = status {
+        if !STATUSES.contains(&status) {
+            anyhow::bail!(
+                "Unknown status: {} (supported: {})",
+                status,
+                STATUSES.join(", ")
+            );
+        }
+    }
+    if tokenize(query).is_empty() {
+        anyhow::bail!("Search query has no words: {:?}", query);
+    }
+    if let Some(dir) = storage.runs_dir().filter(|dir| !dir.exists()) {
+        println!("No runs directory found at: {}", dir.display());
+        return Ok(());
+    }
+
+    let (corpus, skipped) = load_documents(storage)?;
+    let documents = &corpus.documents;
+    let hits: Vec<(usize, f64)> = rank(&corpus, query, field)
+        .into_iter()
+        .filter(|(i, _)| {
+            let doc = &documents[*i];
+            status.is_none_or(|s| doc.status == s)
+                && min_score.is_none_or(|min| doc.overall_score.is_some_and(|s| s >= min))
+        })
+        .collect();
+
+    let runs: HashSet<&str> = documents.iter().map(|d| d.run_id.as_str()).collect();
+    if hits.is_empty() {
+        println!(
+            "No ideas match \"{}\" ({} ideas in {} runs searched)",
+            query,
+            documents.len(),
+            runs.len()
+        );
+    } else {
+        println!(
+            "{} ideas match \"{}\" ({} ideas in {} runs searched)\n",
+            hits.len(),
+            query,
+            documents.len(),
+            runs.len()
+        );
+        for (rank, (i, relevance)) in hits.iter().take(limit).enumerate() {
+            let doc = &documents[*i];
+            let score = doc
+                .overall_score
+                .map(|s| format!("{:.2}", s))
+                .unwrap_or_else(|| "-".to_string());
+            println!(
+                "{:>2}. [{}] {} {} {} (relevance {:.2}, {})",
+                rank + 1,
+                score,
+                doc.run_id,
+                doc.idea_id,
+                doc.title,
+                relevance,
+                doc.status
+            );
+            let snippet = snippet(doc, query, field);
+            if !snippet.is_empty() {
+                println!("    {}", snippet);
+            }
+        }
+    }
+
+    if !skipped.is_empty() {
+        println!(
+            "\nSkipped {} unreadable runs: {} (see `evoidea validate`)",
+            skipped.len(),
+            skipped.join(", ")
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RunConfig;
+    use crate::data::{Facets, IdeaStatus, Origin};
+    use crate::storage::FileStorage;
+    use tempfile::TempDir;
+
+    fn doc(id: &str, title: &str, summary: &str, audience: &str) -> Document {
+        let idea = Idea {
+            id: id.to_string(),
+            gen: 0,
+            origin: Origin::Generated,
+            parents: Vec::new(),
+            title: title.to_string(),
+            summary: summary.to_string(),
+            facets: Facets {
+                audience: audience.to_string(),
+                ..Default::default()
+            },
+            scores: None,
+            overall_score: None,
+            judge_notes: None,
+            status: IdeaStatus::Active,
+        };
+        Document::from_idea("run-1", &idea)
+    }
+
+    #[test]
+    fn test_rank_prefers_rarer_and_repeated_terms() {
+        let corpus = Corpus::new(vec![
+            doc(
+                "idea-1",
+                "Git-native issue tracker",
+                "Issues live in the repo",
+                "developers",
+            ),
+            doc("idea-2", "Team chat", "Chat for developers", "developers"),
+            doc(
+                "idea-3",
+                "Issue triage bot",
+                "Labels new issues",
+                "maintainers",
+            ),
+        ]);
+
+        let ranked = rank(&corpus, "git issue tracker", None);
+        assert_eq!(ranked[0].0, 0);
+        assert_eq!(ranked.len(), 2);
+
+        // Restricting the field ignores matches elsewhere
+        assert_eq!(
+            rank(&corpus, "developers", Some("facets.audience")).len(),
+            2
+        );
+        assert!(rank(&corpus, "developers", Some("title")).is_empty());
+        assert_eq!(rank(&corpus, "maintainers", Some("facets")).len(), 1);
+
+        assert_eq!(
+            snippet(&corpus.documents[2], "issues", None),
+            "summary: Labels new issues"
+        );
+    }
+
+    #[test]
+    fn test_index_is_cached_and_refreshed_on_change() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = FileStorage::new(temp_dir.path());
+        for (run_id, prompt) in [("run-a", "Issue tracker"), ("run-b", "Meal planner")] {
+            let config = RunConfig {
+                run_id: run_id.to_string(),
+                prompt: prompt.to_string(),
+                max_rounds: 1,
+                ..Default::default()
+            };
+            crate::evolution::evolve(config, &storage).unwrap();
+        }
+
+        let (corpus, skipped) = load_documents(&storage).unwrap();
+        assert!(skipped.is_empty());
+        assert!(temp_dir.path().join(INDEX_FILE).exists());
+        let hits = rank(&corpus, "tracker", Some("title"));
+        assert!(!hits.is_empty());
+        assert!(hits
+            .iter()
+            .all(|(i, _)| corpus.documents[*i].run_id == "run-a"));
+
+        // Ranking from the cached statistics matches ranking freshly indexed documents
+        let (cached, _) = load_documents(&storage).unwrap();
+        let fresh = Corpus::new(cached.documents.clone());
+        for field in [None, Some("title"), Some("facets")] {
+            assert_eq!(
+                rank(&cached, "issue tracker", field),
+                rank(&fresh, "issue tracker", field)
+            );
+        }
+
+        let mut state = storage.load_state("run-b").unwrap();
+        state.ideas[0].title = "Recipe tracker".to_string();
+        storage.save_state(&state).unwrap();
+        std::fs::create_dir_all(storage.run_dir("run-c")).unwrap();
+        std::fs::write(storage.state_path("run-c"), "{").unwrap();
+
+        let (corpus, skipped) = load_documents(&storage).unwrap();
+        assert_eq!(skipped, vec!["run-c"]);
+        let hits = rank(&corpus, "tracker", Some("title"));
+        assert!(hits
+            .iter()
+            .any(|(i, _)| corpus.documents[*i].run_id == "run-b"));
+    }
+}
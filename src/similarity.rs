@@ -0,0 +1,320 @@
+use anyhow::Result;
+use std::collections::HashMap;
+
+use crate::data::{Idea, RunState};
+use crate::search::{self, Document};
+use crate::storage::Storage;
+
+/// Cosine similarity above which two ideas count as near-duplicates
+pub const DEFAULT_THRESHOLD: f64 = 0.8;
+
+/// Unit-length TF-IDF vectors over title, summary and facets. Terms that occur in
+/// almost every document carry little weight, so template wording shared by a
+/// whole population does not make its ideas look alike.
+pub fn tfidf_vectors(documents: &[Document]) -> Vec<HashMap<String, f64>> {
+    let counts: Vec<HashMap<String, f64>> = documents
+        .iter()
+        .map(|doc| {
+            let mut tf = HashMap::new();
+            for terms in doc.terms.values() {
+                for (word, count) in &terms.tf {
+                    *tf.entry(word.clone()).or_insert(0.0) += *count as f64;
+                }
+            }
+            tf
+        })
+        .collect();
+
+    let mut df: HashMap<&str, f64> = HashMap::new();
+    for tf in &counts {
+        for term in tf.keys() {
+            *df.entry(term.as_str()).or_insert(0.0) += 1.0;
+        }
+    }
+    let n = documents.len() as f64;
+
+    counts
+        .iter()
+        .map(|tf| {
+            let mut vector: HashMap<String, f64> = tf
+                .iter()
+                .map(|(term, count)| {
+                    let idf = ((n + 1.0) / df[term.as_str()]).ln();
+                    (term.clone(), count * idf)
+                })
+                .collect();
+            let norm = vector.values().map(|w| w * w).sum::<f64>().sqrt();
+            if norm > 0.0 {
+                vector.values_mut().for_each(|w| *w /= norm);
+            }
+            vector
+        })
+        .collect()
+}
+
+/// All pairs (i, j, similarity) with i < j and cosine similarity of at least
+/// `threshold`, most similar first. Only documents accepted by `include` are paired,
+/// but every document contributes to the term weights.
+pub fn similar_pairs(
+    documents: &[Document],
+    threshold: f64,
+    include: impl Fn(&Document) -> bool,
+) -> Vec<(usize, usize, f64)> {
+    let vectors = tfidf_vectors(documents);
+    let included: Vec<usize> = (0..documents.len())
+        .filter(|&i| include(&documents[i]))
+        .collect();
+
+    // Accumulate dot products through an inverted index so only pairs that share
+    // a term are ever touched
+    let mut postings: HashMap<&str, Vec<(usize, f64)>> = HashMap::new();
+    for &i in &included {
+        for (term, weight) in &vectors[i] {
+            postings
+                .entry(term.as_str())
+                .or_default()
+                .push((i, *weight));
+        }
+    }
+    let mut dots: HashMap<(usize, usize), f64> = HashMap::new();
+    for list in postings.values() {
+        for (a, &(i, wi)) in list.iter().enumerate() {
+            for &(j, wj) in &list[a + 1..] {
+                *dots.entry((i.min(j), i.max(j))).or_insert(0.0) += wi * wj;
+            }
+        }
+    }
+
+    let mut pairs: Vec<(usize, usize, f64)> = dots
+        .into_iter()
+        .filter(|(_, sim)| *sim >= threshold)
+        .map(|((i, j), sim)| (i, j, sim.min(1.0)))
+        .collect();
+    pairs.sort_by(|a, b| {
+        b.2.partial_cmp(&a.2)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then((a.0, a.1).cmp(&(b.0, b.1)))
+    });
+    pairs
+}
+
+/// Group documents connected by similar pairs; clusters of two or more, largest first
+pub fn clusters(len: usize, pairs: &[(usize, usize, f64)]) -> Vec<Vec<usize>> {
+    let mut parent: Vec<usize> = (0..len).collect();
+    fn root(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+    for &(i, j, _) in pairs {
+        let (a, b) = (root(&mut parent, i), root(&mut parent, j));
+        parent[a.max(b)] = a.min(b);
+    }
+
+    let mut groups: HashMap<usize, Vec<usize>> = HashMap::new();
+    for i in 0..len {
+        let r = root(&mut parent, i);
+        groups.entry(r).or_default().push(i);
+    }
+    let mut clusters: Vec<Vec<usize>> = groups.into_values().filter(|g| g.len() > 1).collect();
+    clusters.sort_by(|a, b| b.len().cmp(&a.len()).then(a[0].cmp(&b[0])));
+    clusters
+}
+
+/// Near-duplicate pairs among the active ideas of a run
+pub fn similar_active_ideas(state: &RunState, threshold: f64) -> Vec<(&Idea, &Idea, f64)> {
+    let documents: Vec<Document> = state
+        .ideas
+        .iter()
+        .map(|idea| Document::from_idea(&state.run_id, idea))
+        .collect();
+    similar_pairs(&documents, threshold, |doc| doc.status == "active")
+        .into_iter()
+        .map(|(i, j, sim)| (&state.ideas[i], &state.ideas[j], sim))
+        .collect()
+}
+
+/// Print clusters of near-duplicate ideas in one run, or across all runs
+pub fn dedup_runs(
+    storage: &dyn Storage,
+    run_id: Option<&str>,
+    threshold: f64,
+    include_inactive: bool,
+) -> Result<()> {
+    if !(0.0..=1.0).contains(&threshold) {
+        anyhow::bail!("--threshold must be between 0 and 1, got {}", threshold);
+    }
+
+    let documents = match run_id {
+        Some(run_id) => {
+            if !storage.has_state(run_id)? {
+                anyhow::bail!("Run {} has no state.json", run_id);
+            }
+            let state = storage.load_state(run_id)?;
+            search::run_documents(run_id, Some(&state), None)
+        }
+        None => {
+            let (corpus, skipped) = search::load_documents(storage)?;
+            if !skipped.is_empty() {
+                println!(
+                    "Skipped {} unreadable runs: {}\n",
+                    skipped.len(),
+                    skipped.join(", ")
+                );
+            }
+            corpus.documents
+        }
+    };
+
+    let pairs = similar_pairs(&documents, threshold, |doc| {
+        include_inactive || doc.status == "active"
+    });
+    let groups = clusters(documents.len(), &pairs);
+    let scope = match run_id {
+        Some(run_id) => format!("run {}", run_id),
+        None => "all runs".to_string(),
+    };
+    let ideas = if include_inactive {
+        "ideas"
+    } else {
+        "active ideas"
+    };
+
+    if groups.is_empty() {
+        println!(
+            "No near-duplicate {} in {} (threshold {:.2})",
+            ideas, scope, threshold
+        );
+        return Ok(());
+    }
+
+    let duplicates: usize = groups.iter().map(|g| g.len() - 1).sum();
+    println!(
+        "{} clusters of near-duplicate {} in {} (threshold {:.2}); {} ideas are redundant\n",
+        groups.len(),
+        ideas,
+        scope,
+        threshold,
+        duplicates
+    );
+    for (n, group) in groups.iter().enumerate() {
+        println!("Cluster {} ({} ideas):", n + 1, group.len());
+        let anchor = group[0];
+        for &i in group {
+            let doc = &documents[i];
+            let score = doc
+                .overall_score
+                .map(|s| format!("{:.2}", s))
+                .unwrap_or_else(|| "-".to_string());
+            let similarity = pairs
+                .iter()
+                .find(|(a, b, _)| (*a, *b) == (anchor.min(i), anchor.max(i)))
+                .map(|(_, _, sim)| format!(" ~{:.2}", sim))
+                .unwrap_or_default();
+            let run = if run_id.is_some() {
+                String::new()
+            } else {
+                format!("{} ", doc.run_id)
+            };
+            println!(
+                "  [{}] {}{} {} ({}){}",
+                score, run, doc.idea_id, doc.title, doc.status, similarity
+            );
+        }
+        println!();
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{Facets, IdeaStatus, Origin};
+
+    fn idea(id: &str, title: &str, summary: &str, audience: &str) -> Idea {
+        Idea {
+            id: id.to_string(),
+            gen: 0,
+            origin: Origin::Generated,
+            parents: Vec::new(),
+            title: title.to_string(),
+            summary: summary.to_string(),
+            facets: Facets {
+                audience: audience.to_string(),
+                risks: "Crowded market".to_string(),
+                ..Default::default()
+            },
+            scores: None,
+            overall_score: None,
+            judge_notes: None,
+            status: IdeaStatus::Active,
+        }
+    }
+
+    #[test]
+    fn test_clusters_reworded_duplicates() {
+        let mut state = RunState::new("run-1");
+        state.ideas = vec![
+            idea(
+                "idea-1",
+                "Git-native issue tracker",
+                "Issues stored as files in the repository",
+                "open source maintainers",
+            ),
+            idea(
+                "idea-2",
+                "Issue tracker that lives in git",
+                "Issues stored as files in the repository",
+                "open source maintainers",
+            ),
+            idea(
+                "idea-3",
+                "Meal planner",
+                "Weekly recipes from what is in the fridge",
+                "busy parents",
+            ),
+            idea(
+                "idea-4",
+                "Repository issue tracker",
+                "Issues stored as files in the git repository",
+                "open source maintainers",
+            ),
+        ];
+
+        let pairs = similar_active_ideas(&state, 0.5);
+        assert!(!pairs.is_empty());
+        assert!(pairs
+            .iter()
+            .all(|(a, b, _)| a.id != "idea-3" && b.id != "idea-3"));
+
+        let documents: Vec<Document> = state
+            .ideas
+            .iter()
+            .map(|i| Document::from_idea("run-1", i))
+            .collect();
+        let pairs = similar_pairs(&documents, 0.5, |_| true);
+        assert_eq!(clusters(documents.len(), &pairs), vec![vec![0, 1, 3]]);
+
+        // Inactive ideas still weigh terms but are never paired
+        state.ideas[1].status = IdeaStatus::Archived;
+        assert!(similar_active_ideas(&state, 0.5)
+            .iter()
+            .all(|(a, b, _)| a.id != "idea-2" && b.id != "idea-2"));
+    }
+
+    #[test]
+    fn test_identical_ideas_are_fully_similar() {
+        let mut state = RunState::new("run-1");
+        state.ideas = vec![
+            idea("idea-1", "Same", "Same text", "devs"),
+            idea("idea-2", "Same", "Same text", "devs"),
+            idea("idea-3", "Other", "Unrelated words", "cooks"),
+        ];
+        let pairs = similar_active_ideas(&state, DEFAULT_THRESHOLD);
+        assert_eq!(pairs.len(), 1);
+        assert!((pairs[0].2 - 1.0).abs() < 1e-9);
+    }
+}
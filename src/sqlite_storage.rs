@@ -0,0 +1,567 @@
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde_json::{json, Map, Value};
+use std::path::Path;
+
+use crate::config::RunConfig;
+use crate::data::{from_value, FinalResult, HistoryEvent, Idea, Preferences, RunState};
+use crate::storage::{FileStorage, Storage};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS runs (
+    run_id TEXT PRIMARY KEY,
+    created_at TEXT,
+    config_json TEXT NOT NULL,
+    iteration INTEGER,
+    best_idea_id TEXT,
+    best_score REAL,
+    stagnation_counter INTEGER NOT NULL DEFAULT 0,
+    preferences_json TEXT
+);
+CREATE TABLE IF NOT EXISTS ideas (
+    run_id TEXT NOT NULL REFERENCES runs(run_id),
+    id TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    gen INTEGER NOT NULL,
+    origin TEXT NOT NULL,
+    parents_json TEXT NOT NULL,
+    title TEXT NOT NULL,
+    summary TEXT NOT NULL,
+    facets_json TEXT NOT NULL,
+    status TEXT NOT NULL,
+    violations_json TEXT,
+    overall_score REAL,
+    judge_notes TEXT,
+    PRIMARY KEY (run_id, id)
+);
+CREATE TABLE IF NOT EXISTS scores (
+    run_id TEXT NOT NULL,
+    idea_id TEXT NOT NULL,
+    criterion TEXT NOT NULL,
+    value REAL NOT NULL,
+    PRIMARY KEY (run_id, idea_id, criterion)
+);
+CREATE TABLE IF NOT EXISTS events (
+    run_id TEXT NOT NULL REFERENCES runs(run_id),
+    seq INTEGER NOT NULL,
+    ts TEXT NOT NULL,
+    iteration INTEGER NOT NULL,
+    type TEXT NOT NULL,
+    payload_json TEXT NOT NULL,
+    PRIMARY KEY (run_id, seq)
+);
+CREATE TABLE IF NOT EXISTS finals (
+    run_id TEXT PRIMARY KEY REFERENCES runs(run_id),
+    final_json TEXT NOT NULL
+);
+";
+
+/// Storage for many runs in one SQLite database, following docs/05_STORAGE.md.
+/// Ideas, scores and events get their own rows so they can be queried with SQL.
+pub struct SqliteStorage {
+    conn: Connection,
+}
+
+impl SqliteStorage {
+    /// Open (or create) the database at `path` and make sure the tables exist
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let conn = Connection::open(path)
+            .with_context(|| format!("Failed to open database: {:?}", path))?;
+        conn.execute_batch("PRAGMA journal_mode = WAL; PRAGMA synchronous = NORMAL;")?;
+        conn.execute_batch(SCHEMA)
+            .with_context(|| format!("Failed to create tables in {:?}", path))?;
+        Ok(Self { conn })
+    }
+
+    /// Copy one run directory into the database. Nothing is written if any
+    /// artifact fails to load.
+    pub fn import_run(&self, files: &FileStorage, run_id: &str) -> Result<()> {
+        self.in_savepoint(|| {
+            self.init_run(&files.load_config(run_id)?)?;
+            if files.has_state(run_id)? {
+                self.save_state(&files.load_state(run_id)?)?;
+            }
+            for event in files.load_history(run_id)? {
+                self.append_event(run_id, &event)?;
+            }
+            if let Some(preferences) = files.load_preferences(run_id)? {
+                self.save_preferences(run_id, &preferences)?;
+            }
+            if files.has_final(run_id)? {
+                self.save_final(&files.load_final(run_id)?)?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Run `f` inside a savepoint, which nests inside an outer savepoint or
+    /// starts a transaction of its own
+    fn in_savepoint<T>(&self, f: impl FnOnce() -> Result<T>) -> Result<T> {
+        self.conn.execute_batch("SAVEPOINT evoidea")?;
+        match f() {
+            Ok(value) => {
+                self.conn.execute_batch("RELEASE evoidea")?;
+                Ok(value)
+            }
+            Err(e) => {
+                self.conn
+                    .execute_batch("ROLLBACK TO evoidea; RELEASE evoidea")?;
+                Err(e)
+            }
+        }
+    }
+
+    fn require_run(&self, run_id: &str) -> Result<()> {
+        if !self.run_exists(run_id)? {
+            anyhow::bail!("Run not found: {}", run_id);
+        }
+        Ok(())
+    }
+
+    fn load_ideas(&self, run_id: &str) -> Result<Vec<Idea>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, gen, origin, parents_json, title, summary, facets_json, status,
+                    violations_json, overall_score, judge_notes
+             FROM ideas WHERE run_id = ?1 ORDER BY position",
+        )?;
+        // JSON columns come back as strings and are parsed below
+        let rows = stmt.query_map(params![run_id], |row| {
+            Ok(json!({
+                "id": row.get::<_, String>(0)?,
+                "gen": row.get::<_, u32>(1)?,
+                "origin": row.get::<_, String>(2)?,
+                "parents": row.get::<_, String>(3)?,
+                "title": row.get::<_, String>(4)?,
+                "summary": row.get::<_, String>(5)?,
+                "facets": row.get::<_, String>(6)?,
+                "status": row.get::<_, String>(7)?,
+                "violations": row.get::<_, Option<String>>(8)?.unwrap_or_else(|| "[]".into()),
+                "overall_score": row.get::<_, Option<f64>>(9)?,
+                "judge_notes": row.get::<_, Option<String>>(10)?,
+            }))
+        })?;
+
+        let mut ideas = Vec::new();
+        for row in rows {
+            let mut record = row?;
+            for key in ["parents", "facets", "violations"] {
+                let raw = record[key].as_str().unwrap_or_default().to_string();
+                record[key] = serde_json::from_str(&raw)?;
+            }
+            let id = record["id"].as_str().unwrap_or_default().to_string();
+            record["scores"] = self.load_scores(run_id, &id)?;
+            ideas.push(
+                from_value(record).with_context(|| format!("Invalid idea {} in {}", id, run_id))?,
+            );
+        }
+        Ok(ideas)
+    }
+
+    /// Scores of one idea as a JSON object, or null if it was never scored
+    fn load_scores(&self, run_id: &str, idea_id: &str) -> Result<Value> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT criterion, value FROM scores WHERE run_id = ?1 AND idea_id = ?2",
+        )?;
+        let scores = stmt
+            .query_map(params![run_id, idea_id], |row| {
+                Ok((row.get::<_, String>(0)?, json!(row.get::<_, f64>(1)?)))
+            })?
+            .collect::<rusqlite::Result<Map<String, Value>>>()?;
+        Ok(if scores.is_empty() {
+            Value::Null
+        } else {
+            Value::Object(scores)
+        })
+    }
+}
+
+impl Storage for SqliteStorage {
+    fn run_ids(&self) -> Result<Vec<String>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT run_id FROM runs ORDER BY run_id")?;
+        let ids = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(ids)
+    }
+
+    fn run_exists(&self, run_id: &str) -> Result<bool> {
+        let found: Option<i64> = self
+            .conn
+            .query_row(
+                "SELECT 1 FROM runs WHERE run_id = ?1",
+                params![run_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(found.is_some())
+    }
+
+    /// `iteration` stays NULL until the first state is saved
+    fn has_state(&self, run_id: &str) -> Result<bool> {
+        let found: Option<i64> = self
+            .conn
+            .query_row(
+                "SELECT 1 FROM runs WHERE run_id = ?1 AND iteration IS NOT NULL",
+                params![run_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(found.is_some())
+    }
+
+    fn has_final(&self, run_id: &str) -> Result<bool> {
+        let found: Option<i64> = self
+            .conn
+            .query_row(
+                "SELECT 1 FROM finals WHERE run_id = ?1",
+                params![run_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(found.is_some())
+    }
+
+    fn init_run(&self, config: &RunConfig) -> Result<()> {
+        if self.run_exists(&config.run_id)? {
+            anyhow::bail!("Run {} already exists", config.run_id);
+        }
+        self.conn.execute(
+            "INSERT INTO runs (run_id, created_at, config_json) VALUES (?1, ?2, ?3)",
+            params![
+                config.run_id,
+                config.created_at,
+                serde_json::to_string(config)?
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn save_config(&self, config: &RunConfig) -> Result<()> {
+        self.require_run(&config.run_id)?;
+        self.conn.execute(
+            "UPDATE runs SET config_json = ?2 WHERE run_id = ?1",
+            params![config.run_id, serde_json::to_string(config)?],
+        )?;
+        Ok(())
+    }
+
+    /// Replace the run's ideas and scores in one transaction
+    fn save_state(&self, state: &RunState) -> Result<()> {
+        self.require_run(&state.run_id)?;
+        self.in_savepoint(|| {
+            let run_id = &state.run_id;
+            self.conn.execute(
+                "UPDATE runs SET iteration = ?2, best_idea_id = ?3, best_score = ?4,
+                        stagnation_counter = ?5
+                 WHERE run_id = ?1",
+                params![
+                    run_id,
+                    state.iteration,
+                    state.best_idea_id,
+                    state.best_score,
+                    state.stagnation_counter
+                ],
+            )?;
+            self.conn
+                .execute("DELETE FROM ideas WHERE run_id = ?1", params![run_id])?;
+            self.conn
+                .execute("DELETE FROM scores WHERE run_id = ?1", params![run_id])?;
+
+            let mut insert_idea = self.conn.prepare_cached(
+                "INSERT INTO ideas (run_id, id, position, gen, origin, parents_json, title,
+                        summary, facets_json, status, violations_json, overall_score, judge_notes)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+            )?;
+            let mut insert_score = self.conn.prepare_cached(
+                "INSERT INTO scores (run_id, idea_id, criterion, value) VALUES (?1, ?2, ?3, ?4)",
+            )?;
+            for (position, idea) in state.ideas.iter().enumerate() {
+                // Serialize once so status and violations use the same layout as state.json
+                let record = serde_json::to_value(idea)?;
+                let text = |key: &str| record.get(key).and_then(Value::as_str).unwrap_or_default();
+                insert_idea.execute(params![
+                    run_id,
+                    idea.id,
+                    position as i64,
+                    idea.gen,
+                    text("origin"),
+                    record["parents"].to_string(),
+                    idea.title,
+                    idea.summary,
+                    record["facets"].to_string(),
+                    idea.status.as_str(),
+                    record.get("violations").map(Value::to_string),
+                    idea.overall_score,
+                    idea.judge_notes,
+                ])?;
+                if let Some(scores) = record.get("scores").and_then(Value::as_object) {
+                    for (criterion, value) in scores {
+                        insert_score.execute(params![
+                            run_id,
+                            idea.id,
+                            criterion,
+                            value.as_f64()
+                        ])?;
+                    }
+                }
+            }
+            Ok(())
+        })
+    }
+
+    fn append_event(&self, run_id: &str, event: &HistoryEvent) -> Result<()> {
+        self.require_run(run_id)?;
+        self.conn.execute(
+            "INSERT INTO events (run_id, seq, ts, iteration, type, payload_json)
+             SELECT ?1, COALESCE(MAX(seq), 0) + 1, ?2, ?3, ?4, ?5 FROM events WHERE run_id = ?1",
+            params![
+                run_id,
+                event.ts,
+                event.iteration,
+                event.event_type.as_str(),
+                event.payload.to_string()
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn save_final(&self, result: &FinalResult) -> Result<()> {
+        self.require_run(&result.run_id)?;
+        self.conn.execute(
+            "INSERT OR REPLACE INTO finals (run_id, final_json) VALUES (?1, ?2)",
+            params![result.run_id, serde_json::to_string(result)?],
+        )?;
+        Ok(())
+    }
+
+    fn clear_final(&self, run_id: &str) -> Result<()> {
+        self.conn
+            .execute("DELETE FROM finals WHERE run_id = ?1", params![run_id])?;
+        Ok(())
+    }
+
+    fn save_preferences(&self, run_id: &str, preferences: &Preferences) -> Result<()> {
+        self.require_run(run_id)?;
+        self.conn.execute(
+            "UPDATE runs SET preferences_json = ?2 WHERE run_id = ?1",
+            params![run_id, serde_json::to_string(preferences)?],
+        )?;
+        Ok(())
+    }
+
+    fn load_config(&self, run_id: &str) -> Result<RunConfig> {
+        let config: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT config_json FROM runs WHERE run_id = ?1",
+                params![run_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        let config = config.ok_or_else(|| anyhow::anyhow!("Run not found: {}", run_id))?;
+        from_value(serde_json::from_str(&config)?)
+            .with_context(|| format!("Invalid config of {}", run_id))
+    }
+
+    fn load_state(&self, run_id: &str) -> Result<RunState> {
+        let row = self
+            .conn
+            .query_row(
+                "SELECT iteration, best_idea_id, best_score, stagnation_counter
+                 FROM runs WHERE run_id = ?1",
+                params![run_id],
+                |row| {
+                    Ok((
+                        row.get::<_, Option<u32>>(0)?,
+                        row.get::<_, Option<String>>(1)?,
+                        row.get::<_, Option<f64>>(2)?,
+                        row.get::<_, u32>(3)?,
+                    ))
+                },
+            )
+            .optional()?;
+        let (iteration, best_idea_id, best_score, stagnation_counter) =
+            row.ok_or_else(|| anyhow::anyhow!("Run not found: {}", run_id))?;
+        let iteration =
+            iteration.ok_or_else(|| anyhow::anyhow!("Run {} has no saved state", run_id))?;
+
+        Ok(RunState {
+            run_id: run_id.to_string(),
+            iteration,
+            ideas: self.load_ideas(run_id)?,
+            best_idea_id,
+            best_score: best_score.map(|s| s as f32),
+            stagnation_counter,
+        })
+    }
+
+    fn load_history(&self, run_id: &str) -> Result<Vec<HistoryEvent>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT seq, ts, iteration, type, payload_json FROM events
+             WHERE run_id = ?1 ORDER BY seq",
+        )?;
+        let rows = stmt.query_map(params![run_id], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, u32>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, String>(4)?,
+            ))
+        })?;
+
+        let mut events = Vec::new();
+        for row in rows {
+            let (seq, ts, iteration, event_type, payload) = row?;
+            let event = json!({
+                "ts": ts,
+                "iteration": iteration,
+                "type": event_type,
+                "payload": serde_json::from_str::<Value>(&payload)?,
+            });
+            events.push(
+                from_value(event)
+                    .with_context(|| format!("Invalid event {} of {}", seq, run_id))?,
+            );
+        }
+        Ok(events)
+    }
+
+    fn load_final(&self, run_id: &str) -> Result<FinalResult> {
+        let result: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT final_json FROM finals WHERE run_id = ?1",
+                params![run_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        let result = result.ok_or_else(|| anyhow::anyhow!("Run {} has no final result", run_id))?;
+        FinalResult::from_value(serde_json::from_str(&result)?)
+            .with_context(|| format!("Invalid final result of {}", run_id))
+    }
+
+    fn load_preferences(&self, run_id: &str) -> Result<Option<Preferences>> {
+        let preferences: Option<Option<String>> = self
+            .conn
+            .query_row(
+                "SELECT preferences_json FROM runs WHERE run_id = ?1",
+                params![run_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        preferences
+            .flatten()
+            .map(|p| from_value(serde_json::from_str(&p)?))
+            .transpose()
+    }
+}
+
+/// Import run directories into a SQLite database, skipping runs it already has
+pub fn import_runs(files: &FileStorage, db_path: &str, run_id: Option<&str>) -> Result<()> {
+    let db = SqliteStorage::open(db_path)?;
+    let run_ids = match run_id {
+        Some(id) => {
+            if !files.run_exists(id)? {
+                anyhow::bail!("Run not found: {}", id);
+            }
+            vec![id.to_string()]
+        }
+        None => files.run_ids()?,
+    };
+
+    let (mut imported, mut skipped, mut failed) = (0, 0, 0);
+    for id in &run_ids {
+        if db.run_exists(id)? {
+            skipped += 1;
+            continue;
+        }
+        match db.import_run(files, id) {
+            Ok(()) => imported += 1,
+            Err(e) => {
+                failed += 1;
+                println!("  - {}: {:#}", id, e);
+            }
+        }
+    }
+
+    println!(
+        "Imported {} runs into {} ({} already present, {} failed)",
+        imported, db_path, skipped, failed
+    );
+    if failed > 0 {
+        anyhow::bail!("{} of {} runs could not be imported", failed, run_ids.len());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evolution::tests::{evolve_mock_run, mock_run_config};
+    use crate::evolution::{evolve, resume, RoundsLimit};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_import_round_trips_run_directory() {
+        let temp_dir = TempDir::new().unwrap();
+        let files = FileStorage::new(temp_dir.path().join("runs"));
+        evolve_mock_run(&files, "run-a", 2);
+        evolve_mock_run(&files, "run-b", 2);
+        let db_path = temp_dir.path().join("runs.db");
+        let db_path = db_path.to_str().unwrap();
+
+        import_runs(&files, db_path, None).unwrap();
+        // Already imported runs are skipped rather than duplicated
+        import_runs(&files, db_path, Some("run-a")).unwrap();
+
+        let db = SqliteStorage::open(db_path).unwrap();
+        assert_eq!(db.run_ids().unwrap(), vec!["run-a", "run-b"]);
+        for id in ["run-a", "run-b"] {
+            assert_eq!(db.load_config(id).unwrap(), files.load_config(id).unwrap());
+            assert_eq!(db.load_state(id).unwrap(), files.load_state(id).unwrap());
+            assert_eq!(
+                db.load_history(id).unwrap(),
+                files.load_history(id).unwrap()
+            );
+            assert_eq!(db.load_final(id).unwrap(), files.load_final(id).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_evolve_and_resume_against_sqlite() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = SqliteStorage::open(temp_dir.path().join("runs.db")).unwrap();
+
+        evolve_mock_run(&db, "run-test", 2);
+        assert!(db.has_final("run-test").unwrap());
+        let result = resume("run-test", Some(RoundsLimit::Extra(1)), &db).unwrap();
+        assert_eq!(result.iterations_completed, 3);
+
+        let state = db.load_state("run-test").unwrap();
+        let history = db.load_history("run-test").unwrap();
+        let replayed = crate::replay::replay_state("run-test", &history, 3).unwrap();
+        assert_eq!(replayed, state);
+        assert!(evolve(mock_run_config("run-test", 2), &db).is_err());
+    }
+
+    #[test]
+    fn test_search_reads_documents_from_the_database() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = SqliteStorage::open(temp_dir.path().join("runs.db")).unwrap();
+        evolve_mock_run(&db, "run-a", 2);
+
+        let (corpus, skipped) = crate::search::load_documents(&db).unwrap();
+        assert!(skipped.is_empty());
+        assert_eq!(
+            corpus.documents.len(),
+            db.load_state("run-a").unwrap().ideas.len()
+        );
+        assert!(!crate::search::rank(&corpus, "developer tools", None).is_empty());
+        // No runs directory, so no index file is written next to the database
+        assert!(!temp_dir.path().join(".search-index.json").exists());
+    }
+}
@@ -0,0 +1,286 @@
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+use crate::config::tokenize;
+use crate::data::{EventType, Idea, RunState, Scores};
+use crate::replay::replay_state;
+use crate::storage::Storage;
+
+/// Diversity and convergence of the generation scored in one iteration
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct IterationStats {
+    pub iteration: u32,
+    /// Ideas scored in this iteration (constraint eliminations excluded)
+    pub ideas: usize,
+    pub mean_score: Option<f32>,
+    pub max_score: Option<f32>,
+    pub stddev_score: Option<f32>,
+    /// Standard deviation of each criterion across the scored ideas
+    pub criterion_spread: BTreeMap<String, f32>,
+    /// Mean pairwise Jaccard similarity of facet words (0 = disjoint, 1 = identical)
+    pub facet_similarity: Option<f32>,
+    pub distinct_audiences: usize,
+    pub distinct_monetization: usize,
+    pub best_score: Option<f32>,
+    pub stagnation_counter: u32,
+}
+
+/// Mean and population standard deviation, or `None` for an empty sample
+fn mean_stddev(values: &[f32]) -> Option<(f32, f32)> {
+    if values.is_empty() {
+        return None;
+    }
+    let n = values.len() as f32;
+    let mean = values.iter().sum::<f32>() / n;
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f32>() / n;
+    Some((mean, variance.sqrt()))
+}
+
+fn facet_words(idea: &Idea) -> HashSet<String> {
+    idea.facets
+        .fields()
+        .iter()
+        .flat_map(|(_, text)| tokenize(text))
+        .collect()
+}
+
+fn mean_pairwise_jaccard(ideas: &[&Idea]) -> Option<f32> {
+    let sets: Vec<HashSet<String>> = ideas.iter().map(|idea| facet_words(idea)).collect();
+    let mut total = 0.0;
+    let mut pairs = 0;
+    for (i, a) in sets.iter().enumerate() {
+        for b in &sets[i + 1..] {
+            let union = a.union(b).count();
+            if union > 0 {
+                total += a.intersection(b).count() as f32 / union as f32;
+            }
+            pairs += 1;
+        }
+    }
+    (pairs > 0).then(|| total / pairs as f32)
+}
+
+fn distinct(ideas: &[&Idea], facet: impl Fn(&Idea) -> &str) -> usize {
+    ideas
+        .iter()
+        .map(|idea| facet(idea).trim().to_lowercase())
+        .filter(|value| !value.is_empty())
+        .collect::<BTreeSet<_>>()
+        .len()
+}
+
+/// Metrics of one population; best score and stagnation are taken from `state`
+pub fn population_stats(state: &RunState, population: &[&Idea]) -> IterationStats {
+    let overall: Vec<f32> = population.iter().filter_map(|i| i.overall_score).collect();
+    let summary = mean_stddev(&overall);
+
+    let mut by_criterion: BTreeMap<String, Vec<f32>> = BTreeMap::new();
+    for scores in population.iter().filter_map(|idea| idea.scores.as_ref()) {
+        for (name, value) in scores.criteria() {
+            by_criterion
+                .entry(name.to_string())
+                .or_default()
+                .push(value);
+        }
+    }
+
+    IterationStats {
+        iteration: state.iteration,
+        ideas: population.len(),
+        mean_score: summary.map(|(mean, _)| mean),
+        max_score: overall.iter().copied().reduce(f32::max),
+        stddev_score: summary.map(|(_, stddev)| stddev),
+        criterion_spread: by_criterion
+            .into_iter()
+            .filter_map(|(name, values)| Some((name, mean_stddev(&values)?.1)))
+            .collect(),
+        facet_similarity: mean_pairwise_jaccard(population),
+        distinct_audiences: distinct(population, |idea| &idea.facets.audience),
+        distinct_monetization: distinct(population, |idea| &idea.facets.monetization),
+        best_score: state.best_score,
+        stagnation_counter: state.stagnation_counter,
+    }
+}
+
+/// Stats for every iteration, replayed from history.ndjson. Runs without a
+/// history fall back to the scored active ideas of state.json.
+pub fn run_stats(storage: &dyn Storage, run_id: &str) -> Result<Vec<IterationStats>> {
+    if !storage.run_exists(run_id)? {
+        anyhow::bail!("Run {} not found", run_id);
+    }
+    let history = storage.load_history(run_id)?;
+    let last = history.last().map(|e| e.iteration).unwrap_or(0);
+    if last == 0 {
+        let state = storage.load_state(run_id)?;
+        let scored: Vec<&Idea> = state
+            .active_ideas()
+            .filter(|idea| idea.overall_score.is_some())
+            .collect();
+        return Ok(vec![population_stats(&state, &scored)]);
+    }
+
+    (1..=last)
+        .map(|n| {
+            let state = replay_state(run_id, &history, n)?;
+            let scored: HashSet<&str> = history
+                .iter()
+                .filter(|e| e.iteration == n && e.event_type == EventType::Scored)
+                .flat_map(|e| e.payload["results"].as_array().into_iter().flatten())
+                .filter_map(|result| result["id"].as_str())
+                .collect();
+            let population: Vec<&Idea> = state
+                .ideas
+                .iter()
+                .filter(|idea| scored.contains(idea.id.as_str()))
+                .collect();
+            Ok(population_stats(&state, &population))
+        })
+        .collect()
+}
+
+/// Print per-iteration diversity and convergence metrics
+pub fn print_stats(storage: &dyn Storage, run_id: &str, format: &str) -> Result<()> {
+    let stats = run_stats(storage, run_id)?;
+
+    match format {
+        "json" => println!("{}", serde_json::to_string_pretty(&stats)?),
+        "text" => print_text(run_id, &stats),
+        _ => anyhow::bail!("Unknown format: {} (supported: text, json)", format),
+    }
+    Ok(())
+}
+
+fn print_text(run_id: &str, stats: &[IterationStats]) {
+    let num = |v: Option<f32>| v.map(|v| format!("{:.2}", v)).unwrap_or("-".into());
+
+    println!("Run {}: {} iterations\n", run_id, stats.len());
+    println!(
+        "{:<5} {:>6} {:>6} {:>6} {:>7} {:>9} {:>9} {:>12} {:>6} {:>10}",
+        "ITER",
+        "IDEAS",
+        "MEAN",
+        "MAX",
+        "STDDEV",
+        "FACET SIM",
+        "AUDIENCES",
+        "MONETIZATION",
+        "BEST",
+        "STAGNATION"
+    );
+    for s in stats {
+        println!(
+            "{:<5} {:>6} {:>6} {:>6} {:>7} {:>9} {:>9} {:>12} {:>6} {:>10}",
+            s.iteration,
+            s.ideas,
+            num(s.mean_score),
+            num(s.max_score),
+            num(s.stddev_score),
+            num(s.facet_similarity),
+            s.distinct_audiences,
+            s.distinct_monetization,
+            num(s.best_score),
+            s.stagnation_counter
+        );
+    }
+
+    let criteria = criterion_columns(stats);
+    if criteria.is_empty() {
+        return;
+    }
+    println!("\nCriterion spread (stddev across the ideas scored in each iteration):");
+    print!("{:<5}", "ITER");
+    for name in &criteria {
+        print!(" {:>w$}", name.to_uppercase(), w = name.len());
+    }
+    println!();
+    for s in stats {
+        print!("{:<5}", s.iteration);
+        for name in &criteria {
+            print!(
+                " {:>w$}",
+                num(s.criterion_spread.get(*name).copied()),
+                w = name.len()
+            );
+        }
+        println!();
+    }
+}
+
+/// Criteria with a spread in any iteration, in the canonical `Scores::NAMES` order
+fn criterion_columns(stats: &[IterationStats]) -> Vec<&'static str> {
+    Scores::NAMES
+        .into_iter()
+        .filter(|name| stats.iter().any(|s| s.criterion_spread.contains_key(*name)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evolution::tests::evolve_mock_run;
+    use crate::storage::FileStorage;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_mean_stddev_and_jaccard() {
+        assert_eq!(mean_stddev(&[]), None);
+        assert_eq!(
+            mean_stddev(&[2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0]),
+            Some((5.0, 2.0))
+        );
+
+        let state: RunState = serde_json::from_value(serde_json::json!({
+            "run_id": "run-1",
+            "iteration": 1,
+            "ideas": [
+                {"id": "idea-1", "origin": "generated", "title": "A", "status": "active",
+                 "facets": {"audience": "Solo developers", "monetization": "Subscription"}},
+                {"id": "idea-2", "origin": "generated", "title": "B", "status": "active",
+                 "facets": {"audience": "solo developers ", "monetization": "Ads"}},
+                {"id": "idea-3", "origin": "generated", "title": "C", "status": "archived",
+                 "facets": {"audience": "Parents", "monetization": "Ads"}}
+            ]
+        }))
+        .unwrap();
+
+        let active: Vec<&Idea> = state.active_ideas().collect();
+        let stats = population_stats(&state, &active);
+        assert_eq!(stats.ideas, 2);
+        assert_eq!(stats.mean_score, None);
+        assert_eq!(stats.distinct_audiences, 1);
+        assert_eq!(stats.distinct_monetization, 2);
+        // {solo, developers, subscription} vs {solo, developers, ads}
+        assert_eq!(stats.facet_similarity, Some(0.5));
+    }
+
+    #[test]
+    fn test_run_stats_cover_every_iteration() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = FileStorage::new(temp_dir.path());
+        evolve_mock_run(&storage, "run-test", 3);
+
+        let stats = run_stats(&storage, "run-test").unwrap();
+        assert_eq!(
+            stats.iter().map(|s| s.iteration).collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+        let history = storage.load_history("run-test").unwrap();
+        for s in &stats {
+            let results: usize = history
+                .iter()
+                .filter(|e| e.iteration == s.iteration && e.event_type == EventType::Scored)
+                .filter_map(|e| e.payload["results"].as_array())
+                .map(|r| r.len())
+                .sum();
+            assert_eq!(s.ideas, results);
+            assert!(s.max_score >= s.mean_score);
+            assert!(s.stddev_score.is_some());
+            assert_eq!(s.criterion_spread.len(), 8);
+        }
+        // Spread columns follow the rubric order, not the alphabetical map order
+        assert_eq!(criterion_columns(&stats), Scores::NAMES.to_vec());
+        // The best score never drops from one iteration to the next
+        assert!(stats.windows(2).all(|w| w[1].best_score >= w[0].best_score));
+    }
+}
@@ -1,14 +1,57 @@
 use anyhow::{Context, Result};
 use std::fs;
+use std::io::Write;
 use std::path::{Path, PathBuf};
 
-/// Read-only file storage for accessing run artifacts
-#[allow(dead_code)]
+use crate::config::RunConfig;
+use crate::data::{FinalResult, HistoryEvent, Preferences, RunState};
+
+/// Persistence for run artifacts. `FileStorage` is the default; `SqliteStorage`
+/// (feature `sqlite`) keeps many runs in one database.
+pub trait Storage {
+    /// Ids of all runs, sorted
+    fn run_ids(&self) -> Result<Vec<String>>;
+    fn run_exists(&self, run_id: &str) -> Result<bool>;
+    /// Whether a state has been saved, i.e. the run got past initialization
+    fn has_state(&self, run_id: &str) -> Result<bool>;
+    fn has_final(&self, run_id: &str) -> Result<bool>;
+
+    /// Register a new run with its config; fails if the run already exists
+    fn init_run(&self, config: &RunConfig) -> Result<()>;
+    /// Overwrite the config of an existing run (e.g. after extending max_rounds)
+    fn save_config(&self, config: &RunConfig) -> Result<()>;
+    fn save_state(&self, state: &RunState) -> Result<()>;
+    fn append_event(&self, run_id: &str, event: &HistoryEvent) -> Result<()>;
+    fn save_final(&self, result: &FinalResult) -> Result<()>;
+    /// Drop the final result so a resumed run reads as in progress until it stops again
+    fn clear_final(&self, run_id: &str) -> Result<()>;
+    fn save_preferences(&self, run_id: &str, preferences: &Preferences) -> Result<()>;
+
+    fn load_config(&self, run_id: &str) -> Result<RunConfig>;
+    fn load_state(&self, run_id: &str) -> Result<RunState>;
+    /// Every event in order (empty if nothing was recorded yet)
+    fn load_history(&self, run_id: &str) -> Result<Vec<HistoryEvent>>;
+    /// Final result in either the current or the legacy `best` layout
+    fn load_final(&self, run_id: &str) -> Result<FinalResult>;
+    /// Preferences, or `None` if no tournament has been run yet
+    fn load_preferences(&self, run_id: &str) -> Result<Option<Preferences>>;
+
+    /// Directory holding the run directories, for backends that have one
+    fn runs_dir(&self) -> Option<&Path> {
+        None
+    }
+    /// Fingerprint of a run's state and final result that changes whenever
+    /// either is rewritten, or `None` if the backend cannot tell cheaply
+    fn run_stamp(&self, _run_id: &str) -> Option<String> {
+        None
+    }
+}
+
+/// File storage for run artifacts under `<base_dir>/<run_id>/`
 pub struct FileStorage {
     base_dir: PathBuf,
 }
 
-#[allow(dead_code)]
 impl FileStorage {
     pub fn new<P: AsRef<Path>>(base_dir: P) -> Self {
         Self {
@@ -16,7 +59,11 @@ impl FileStorage {
         }
     }
 
-    fn run_dir(&self, run_id: &str) -> PathBuf {
+    pub fn base_dir(&self) -> &Path {
+        &self.base_dir
+    }
+
+    pub fn run_dir(&self, run_id: &str) -> PathBuf {
         self.base_dir.join(run_id)
     }
 
@@ -36,37 +83,256 @@ impl FileStorage {
         self.run_dir(run_id).join("final.json")
     }
 
-    pub fn load_config(&self, run_id: &str) -> Result<serde_json::Value> {
-        let path = self.config_path(run_id);
-        let content = fs::read_to_string(&path)
-            .with_context(|| format!("Failed to read config: {:?}", path))?;
-        let config: serde_json::Value = serde_json::from_str(&content)?;
-        Ok(config)
+    pub fn preferences_path(&self, run_id: &str) -> PathBuf {
+        self.run_dir(run_id).join("preferences.json")
     }
 
-    pub fn load_state(&self, run_id: &str) -> Result<serde_json::Value> {
-        let path = self.state_path(run_id);
-        let content = fs::read_to_string(&path)
-            .with_context(|| format!("Failed to read state: {:?}", path))?;
-        let state: serde_json::Value = serde_json::from_str(&content)?;
-        Ok(state)
+    /// Take the advisory lock of a run, failing at once if another process holds it.
+    /// The lock is released when the returned guard is dropped.
+    pub fn lock_run(&self, run_id: &str) -> Result<RunLock> {
+        let path = self.run_dir(run_id).join(".lock");
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)
+            .with_context(|| format!("Failed to open lock: {:?}", path))?;
+        match file.try_lock() {
+            Ok(()) => Ok(RunLock { _file: file }),
+            Err(fs::TryLockError::WouldBlock) => anyhow::bail!(
+                "Run {} is locked by another evoidea process (resume, tournament or profile import)",
+                run_id
+            ),
+            Err(fs::TryLockError::Error(e)) => {
+                Err(e).with_context(|| format!("Failed to lock: {:?}", path))
+            }
+        }
+    }
+}
+
+/// Held advisory lock on a run directory (see `FileStorage::lock_run`)
+pub struct RunLock {
+    _file: fs::File,
+}
+
+fn file_stamp(path: &Path) -> String {
+    match fs::metadata(path) {
+        Ok(meta) => {
+            let modified = meta
+                .modified()
+                .ok()
+                .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                .map(|d| d.as_nanos())
+                .unwrap_or(0);
+            format!("{}@{}", meta.len(), modified)
+        }
+        Err(_) => "-".to_string(),
+    }
+}
+
+impl Storage for FileStorage {
+    fn runs_dir(&self) -> Option<&Path> {
+        Some(&self.base_dir)
+    }
+
+    /// Size and mtime of state.json and final.json
+    fn run_stamp(&self, run_id: &str) -> Option<String> {
+        Some(format!(
+            "state:{} final:{}",
+            file_stamp(&self.state_path(run_id)),
+            file_stamp(&self.final_path(run_id))
+        ))
+    }
+
+    fn load_config(&self, run_id: &str) -> Result<RunConfig> {
+        read_json(&self.config_path(run_id))
     }
 
-    pub fn load_final(&self, run_id: &str) -> Result<serde_json::Value> {
+    fn load_state(&self, run_id: &str) -> Result<RunState> {
+        read_json(&self.state_path(run_id))
+    }
+
+    /// Load final.json in either the current or the legacy `best` layout
+    fn load_final(&self, run_id: &str) -> Result<FinalResult> {
         let path = self.final_path(run_id);
+        let value: serde_json::Value = read_json(&path)?;
+        FinalResult::from_value(value).with_context(|| format!("Invalid {:?}", path))
+    }
+
+    /// Load preferences.json, or `None` if no tournament has been run yet
+    fn load_preferences(&self, run_id: &str) -> Result<Option<Preferences>> {
+        let path = self.preferences_path(run_id);
+        if !path.exists() {
+            return Ok(None);
+        }
+        read_json(&path).map(Some)
+    }
+
+    /// Ids of all run directories, sorted; empty if the runs directory does not exist
+    fn run_ids(&self) -> Result<Vec<String>> {
+        if !self.base_dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut ids = Vec::new();
+        for entry in fs::read_dir(&self.base_dir)
+            .with_context(|| format!("Failed to read: {:?}", self.base_dir))?
+        {
+            let path = entry?.path();
+            if let (true, Some(name)) = (path.is_dir(), path.file_name().and_then(|n| n.to_str())) {
+                ids.push(name.to_string());
+            }
+        }
+        ids.sort();
+        Ok(ids)
+    }
+
+    fn run_exists(&self, run_id: &str) -> Result<bool> {
+        Ok(self.run_dir(run_id).exists())
+    }
+
+    fn has_state(&self, run_id: &str) -> Result<bool> {
+        Ok(self.state_path(run_id).exists())
+    }
+
+    fn has_final(&self, run_id: &str) -> Result<bool> {
+        Ok(self.final_path(run_id).exists())
+    }
+
+    /// Create the run directory and write config.json
+    fn init_run(&self, config: &RunConfig) -> Result<()> {
+        let run_dir = self.run_dir(&config.run_id);
+        if run_dir.exists() {
+            anyhow::bail!("Run {} already exists", config.run_id);
+        }
+        fs::create_dir_all(&run_dir)
+            .with_context(|| format!("Failed to create run dir: {:?}", run_dir))?;
+        write_json(&self.config_path(&config.run_id), config)
+    }
+
+    /// Overwrite config.json of an existing run (e.g. after extending max_rounds)
+    fn save_config(&self, config: &RunConfig) -> Result<()> {
+        write_json(&self.config_path(&config.run_id), config)
+    }
+
+    fn save_state(&self, state: &RunState) -> Result<()> {
+        write_json(&self.state_path(&state.run_id), state)
+    }
+
+    /// Append one event to history.ndjson
+    fn append_event(&self, run_id: &str, event: &HistoryEvent) -> Result<()> {
+        let path = self.history_path(run_id);
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("Failed to open history: {:?}", path))?;
+        writeln!(file, "{}", serde_json::to_string(event)?)?;
+        file.sync_data()
+            .with_context(|| format!("Failed to sync history: {:?}", path))
+    }
+
+    /// Read every event from history.ndjson (empty if the file does not exist yet)
+    fn load_history(&self, run_id: &str) -> Result<Vec<HistoryEvent>> {
+        let path = self.history_path(run_id);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
         let content = fs::read_to_string(&path)
-            .with_context(|| format!("Failed to read final: {:?}", path))?;
-        let result: serde_json::Value = serde_json::from_str(&content)?;
-        Ok(result)
+            .with_context(|| format!("Failed to read history: {:?}", path))?;
+        content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                serde_json::from_str(line)
+                    .map_err(anyhow::Error::from)
+                    .and_then(crate::data::from_value)
+                    .with_context(|| format!("Invalid event on line {} of {:?}", i + 1, path))
+            })
+            .collect()
+    }
+
+    fn save_preferences(&self, run_id: &str, preferences: &Preferences) -> Result<()> {
+        write_json_with_backup(&self.preferences_path(run_id), preferences)
+    }
+
+    fn save_final(&self, result: &FinalResult) -> Result<()> {
+        write_json_with_backup(&self.final_path(&result.run_id), result)
+    }
+
+    /// Remove final.json so a resumed run reads as in progress until it stops again
+    fn clear_final(&self, run_id: &str) -> Result<()> {
+        let path = self.final_path(run_id);
+        if path.exists() {
+            fs::remove_file(&path).with_context(|| format!("Failed to remove: {:?}", path))?;
+        }
+        Ok(())
+    }
+}
+
+/// Read and deserialize a JSON artifact; errors name the file and the offending field
+fn read_json<T: serde::de::DeserializeOwned>(path: &Path) -> Result<T> {
+    let content =
+        fs::read_to_string(path).with_context(|| format!("Failed to read: {:?}", path))?;
+    let value: serde_json::Value =
+        serde_json::from_str(&content).with_context(|| format!("Invalid JSON in {:?}", path))?;
+    crate::data::from_value(value).with_context(|| format!("Invalid {:?}", path))
+}
+
+/// Write a working file (state.json, config.json), which is rewritten too
+/// often to be worth a backup
+fn write_json<T: serde::Serialize>(path: &Path, value: &T) -> Result<()> {
+    write_atomic(path, serde_json::to_string_pretty(value)?.as_bytes())
+}
+
+fn write_json_with_backup<T: serde::Serialize>(path: &Path, value: &T) -> Result<()> {
+    write_artifact(path, serde_json::to_string_pretty(value)?.as_bytes())
+}
+
+/// Replace a user-facing artifact (preferences, final result, exports)
+/// atomically (see `write_atomic`), keeping the previous content as
+/// `<name>.bak`. The backup is written atomically too, so a crash never
+/// leaves a torn one behind.
+pub fn write_artifact(path: &Path, contents: &[u8]) -> Result<()> {
+    if path.exists() {
+        let mut backup = path.as_os_str().to_owned();
+        backup.push(".bak");
+        let previous = fs::read(path).with_context(|| format!("Failed to back up: {:?}", path))?;
+        write_atomic(Path::new(&backup), &previous)?;
     }
+    write_atomic(path, contents)
+}
+
+/// Replace `path` so that readers see either the old or the new content, never a
+/// torn file: write a sibling temp file, fsync it and rename it over `path`
+pub fn write_atomic(path: &Path, contents: &[u8]) -> Result<()> {
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| anyhow::anyhow!("Invalid artifact path: {:?}", path))?;
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let tmp = dir.join(format!(".{}.{}.tmp", name, std::process::id()));
 
-    pub fn run_exists(&self, run_id: &str) -> bool {
-        self.run_dir(run_id).exists()
+    let write = || -> Result<()> {
+        let mut file = fs::File::create(&tmp)?;
+        file.write_all(contents)?;
+        file.sync_all()?;
+        Ok(())
+    };
+    if let Err(e) = write() {
+        let _ = fs::remove_file(&tmp);
+        return Err(e).with_context(|| format!("Failed to write: {:?}", path));
     }
 
-    pub fn has_final(&self, run_id: &str) -> bool {
-        self.final_path(run_id).exists()
+    fs::rename(&tmp, path).with_context(|| format!("Failed to write: {:?}", path))?;
+    // Persist the rename itself; not every platform can open a directory for syncing
+    if let Ok(dir) = fs::File::open(dir) {
+        let _ = dir.sync_all();
     }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -89,4 +355,76 @@ mod tests {
             PathBuf::from("runs/test-run/final.json")
         );
     }
+
+    #[test]
+    fn test_load_state_reports_file_and_field() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let storage = FileStorage::new(temp_dir.path());
+        fs::create_dir_all(storage.run_dir("run-1")).unwrap();
+        fs::write(
+            storage.state_path("run-1"),
+            r#"{"run_id": "run-1", "iteration": 1, "ideas": [{"id": "idea-1", "origin": "generated", "title": "T", "status": "lost"}]}"#,
+        )
+        .unwrap();
+
+        let err = format!("{:#}", storage.load_state("run-1").unwrap_err());
+        assert!(err.contains("state.json"), "{}", err);
+        assert!(err.contains("ideas[0].status"), "{}", err);
+    }
+
+    #[test]
+    fn test_write_artifact_keeps_backup_and_no_temp_files() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("preferences.json");
+
+        write_artifact(&path, b"first").unwrap();
+        assert!(!temp_dir.path().join("preferences.json.bak").exists());
+        write_artifact(&path, b"second").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "second");
+        assert_eq!(
+            fs::read_to_string(temp_dir.path().join("preferences.json.bak")).unwrap(),
+            "first"
+        );
+        let names: Vec<String> = fs::read_dir(temp_dir.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        assert!(names.iter().all(|n| !n.ends_with(".tmp")), "{:?}", names);
+    }
+
+    #[test]
+    fn test_only_user_facing_artifacts_get_backups() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let storage = FileStorage::new(temp_dir.path());
+        fs::create_dir_all(storage.run_dir("run-1")).unwrap();
+
+        let mut state = RunState::new("run-1");
+        storage.save_state(&state).unwrap();
+        state.iteration = 1;
+        storage.save_state(&state).unwrap();
+        storage
+            .save_preferences("run-1", &Preferences::default())
+            .unwrap();
+        storage
+            .save_preferences("run-1", &Preferences::default())
+            .unwrap();
+
+        let run_dir = storage.run_dir("run-1");
+        assert!(!run_dir.join("state.json.bak").exists());
+        assert!(run_dir.join("preferences.json.bak").exists());
+    }
+
+    #[test]
+    fn test_lock_run_is_exclusive_until_dropped() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let storage = FileStorage::new(temp_dir.path());
+        fs::create_dir_all(storage.run_dir("run-1")).unwrap();
+
+        let lock = storage.lock_run("run-1").unwrap();
+        let err = storage.lock_run("run-1").err().unwrap();
+        assert!(err.to_string().contains("is locked"), "{}", err);
+        drop(lock);
+        assert!(storage.lock_run("run-1").is_ok());
+    }
 }
@@ -0,0 +1,522 @@
+use anyhow::Result;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::data::{join_violations, Idea, IdeaStatus, RunState};
+use crate::replay;
+use crate::storage::Storage;
+
+const FORMATS: [&str; 4] = ["ascii", "mermaid", "dot", "svg"];
+
+/// Render evolution tree visualization
+pub fn render_tree(
+    storage: &dyn Storage,
+    run_id: &str,
+    format: &str,
+    iteration: Option<u32>,
+) -> Result<()> {
+    if !FORMATS.contains(&format) {
+        anyhow::bail!(
+            "Unknown format: {} (supported: {})",
+            format,
+            FORMATS.join(", ")
+        );
+    }
+
+    let state = match iteration {
+        Some(iteration) => replay::load_snapshot(storage, run_id, iteration)?,
+        None if storage.has_state(run_id)? => storage.load_state(run_id)?,
+        None => anyhow::bail!("Run {} not found", run_id),
+    };
+
+    if state.ideas.is_empty() {
+        println!("No ideas in run {}", run_id);
+        return Ok(());
+    }
+
+    let children_map = children_map(&state);
+    let roots: Vec<&Idea> = state
+        .ideas
+        .iter()
+        .filter(|idea| idea.parents.is_empty())
+        .collect();
+
+    match format {
+        "ascii" => render_ascii_tree(&roots, &children_map, run_id),
+        "mermaid" => render_mermaid_tree(&roots, &children_map, run_id),
+        "dot" => {
+            print!("{}", render_dot(&state));
+            Ok(())
+        }
+        "svg" => {
+            print!("{}", render_svg(&state));
+            Ok(())
+        }
+        _ => unreachable!("format checked above"),
+    }
+}
+
+/// Parent id -> children, in state order
+pub fn children_map(state: &RunState) -> HashMap<String, Vec<&Idea>> {
+    let mut children_map: HashMap<String, Vec<&Idea>> = HashMap::new();
+    for idea in &state.ideas {
+        for parent_id in &idea.parents {
+            children_map
+                .entry(parent_id.clone())
+                .or_default()
+                .push(idea);
+        }
+    }
+    children_map
+}
+
+fn render_ascii_tree(
+    roots: &[&Idea],
+    children_map: &HashMap<String, Vec<&Idea>>,
+    run_id: &str,
+) -> Result<()> {
+    println!("=== Evolution Tree: {} ===\n", run_id);
+
+    for root in roots {
+        print_idea_node(root, children_map, "", true);
+    }
+
+    // Legend
+    println!("\nLegend: [score] status title");
+    println!("  * = active, ~ = archived, x = eliminated");
+
+    Ok(())
+}
+
+fn print_idea_node(
+    idea: &Idea,
+    children_map: &HashMap<String, Vec<&Idea>>,
+    prefix: &str,
+    is_last: bool,
+) {
+    let status_char = match idea.status {
+        IdeaStatus::Active => "*",
+        IdeaStatus::Archived => "~",
+        IdeaStatus::Eliminated { .. } => "x",
+    };
+
+    let connector = if is_last { "└── " } else { "├── " };
+    let short_title: String = idea.title.chars().take(40).collect();
+    let title_display = if idea.title.chars().count() > 40 {
+        format!("{}...", short_title)
+    } else {
+        short_title
+    };
+
+    let violations = match &idea.status {
+        IdeaStatus::Eliminated { violations } if !violations.is_empty() => {
+            format!(" ({})", join_violations(violations))
+        }
+        _ => String::new(),
+    };
+
+    println!(
+        "{}{}{} [{:.1}] {} {}{}",
+        prefix,
+        connector,
+        status_char,
+        idea.overall_score.unwrap_or(0.0),
+        idea.id,
+        title_display,
+        violations
+    );
+
+    // Print children
+    if let Some(children) = children_map.get(&idea.id) {
+        let new_prefix = format!("{}{}", prefix, if is_last { "    " } else { "│   " });
+        for (i, child) in children.iter().enumerate() {
+            let child_is_last = i == children.len() - 1;
+            print_idea_node(child, children_map, &new_prefix, child_is_last);
+        }
+    }
+}
+
+fn render_mermaid_tree(
+    roots: &[&Idea],
+    children_map: &HashMap<String, Vec<&Idea>>,
+    run_id: &str,
+) -> Result<()> {
+    println!("```mermaid");
+    println!("flowchart TD");
+    println!(
+        "    subgraph {}[\"Evolution: {}\"]",
+        run_id.replace('-', "_"),
+        run_id
+    );
+
+    // Collect all nodes
+    let mut all_ideas: Vec<&Idea> = roots.to_vec();
+    for children in children_map.values() {
+        all_ideas.extend(children.iter());
+    }
+
+    // Print nodes with styling
+    for idea in &all_ideas {
+        let score = idea.overall_score.unwrap_or(0.0);
+        let short_title: String = idea.title.chars().take(25).collect();
+        let safe_id = idea.id.replace('-', "_");
+
+        let shape = match idea.status {
+            IdeaStatus::Active => format!("{}([\"{}\\n{:.1}\"])", safe_id, short_title, score),
+            IdeaStatus::Eliminated { .. } => {
+                format!("{}{{\"{}\\n{:.1}\"}}", safe_id, short_title, score)
+            }
+            IdeaStatus::Archived => format!("{}[\"{}\\n{:.1}\"]", safe_id, short_title, score),
+        };
+
+        println!("    {}", shape);
+    }
+
+    // Print edges
+    for (parent_id, children) in children_map {
+        let safe_parent = parent_id.replace('-', "_");
+        for child in children {
+            let safe_child = child.id.replace('-', "_");
+            println!("    {} --> {}", safe_parent, safe_child);
+        }
+    }
+
+    // Styling
+    println!("    end");
+    println!("    classDef active fill:#90EE90,stroke:#228B22");
+    println!("    classDef archived fill:#D3D3D3,stroke:#808080");
+    println!("    classDef eliminated fill:#FFB6C1,stroke:#DC143C");
+
+    // Apply classes
+    for idea in &all_ideas {
+        println!(
+            "    class {} {}",
+            idea.id.replace('-', "_"),
+            idea.status.as_str()
+        );
+    }
+
+    println!("```");
+
+    Ok(())
+}
+
+/// Fill and stroke colors per status, shared by the mermaid, DOT and SVG renderers
+fn status_colors(status: &IdeaStatus) -> (&'static str, &'static str) {
+    match status {
+        IdeaStatus::Active => ("#90EE90", "#228B22"),
+        IdeaStatus::Archived => ("#D3D3D3", "#808080"),
+        IdeaStatus::Eliminated { .. } => ("#FFB6C1", "#DC143C"),
+    }
+}
+
+fn short_title(title: &str, max: usize) -> String {
+    if title.chars().count() > max {
+        let short: String = title.chars().take(max - 3).collect();
+        format!("{}...", short)
+    } else {
+        title.to_string()
+    }
+}
+
+fn dot_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Graphviz graph: nodes colored by status and sized by score, edges labeled
+/// with the origin of the child
+pub fn render_dot(state: &RunState) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "digraph \"{}\" {{", dot_escape(&state.run_id));
+    let _ = writeln!(out, "    rankdir=TB;");
+    let _ = writeln!(
+        out,
+        "    node [shape=box, style=\"rounded,filled\", fontname=\"Helvetica\"];"
+    );
+    let _ = writeln!(out, "    edge [fontname=\"Helvetica\", fontsize=9];");
+
+    for idea in &state.ideas {
+        let score = idea.overall_score.unwrap_or(0.0);
+        let (fill, stroke) = status_colors(&idea.status);
+        let _ = writeln!(
+            out,
+            "    \"{}\" [label=\"{}\\n{}\\n{:.1}\", fillcolor=\"{}\", color=\"{}\", width={:.2}, fontsize={:.0}];",
+            dot_escape(&idea.id),
+            dot_escape(&idea.id),
+            dot_escape(&short_title(&idea.title, 30)),
+            score,
+            fill,
+            stroke,
+            1.5 + score.clamp(0.0, 10.0) * 0.15,
+            10.0 + score.clamp(0.0, 10.0) * 0.4
+        );
+    }
+    for idea in &state.ideas {
+        for parent in &idea.parents {
+            let _ = writeln!(
+                out,
+                "    \"{}\" -> \"{}\" [label=\"{}\"];",
+                dot_escape(parent),
+                dot_escape(&idea.id),
+                idea.origin.as_str()
+            );
+        }
+    }
+    out.push_str("}\n");
+    out
+}
+
+// SVG layout: one row per depth, fixed-width slots, boxes scaled by score
+const SLOT_WIDTH: f32 = 190.0;
+const ROW_HEIGHT: f32 = 110.0;
+const BOX_HEIGHT: f32 = 52.0;
+const MARGIN: f32 = 30.0;
+
+/// Depth of every idea: roots (or ideas whose parents are missing) are 0,
+/// children sit one row below their deepest parent
+fn depths(state: &RunState) -> HashMap<&str, usize> {
+    fn depth<'a>(
+        state: &'a RunState,
+        idea: &'a Idea,
+        memo: &mut HashMap<&'a str, usize>,
+        visiting: &mut Vec<&'a str>,
+    ) -> usize {
+        if let Some(&d) = memo.get(idea.id.as_str()) {
+            return d;
+        }
+        if visiting.contains(&idea.id.as_str()) {
+            return 0;
+        }
+        visiting.push(&idea.id);
+        let d = idea
+            .parents
+            .iter()
+            .filter_map(|id| state.idea(id))
+            .map(|parent| depth(state, parent, memo, visiting) + 1)
+            .max()
+            .unwrap_or(0);
+        visiting.pop();
+        memo.insert(&idea.id, d);
+        d
+    }
+
+    let mut memo = HashMap::new();
+    for idea in &state.ideas {
+        depth(state, idea, &mut memo, &mut Vec::new());
+    }
+    memo
+}
+
+/// Slot (column) and row of every idea. Rows are ordered by the mean slot of
+/// each idea's parents, which keeps most edges short without a full
+/// crossing-minimization pass.
+fn layout(state: &RunState) -> HashMap<&str, (f32, usize)> {
+    let depths = depths(state);
+    let rows = depths.values().copied().max().map_or(0, |d| d + 1);
+    let mut positions: HashMap<&str, (f32, usize)> = HashMap::new();
+
+    for row in 0..rows {
+        let mut ideas: Vec<(&Idea, f32)> = state
+            .ideas
+            .iter()
+            .enumerate()
+            .filter(|(_, idea)| depths[idea.id.as_str()] == row)
+            .map(|(i, idea)| {
+                let slots: Vec<f32> = idea
+                    .parents
+                    .iter()
+                    .filter_map(|id| positions.get(id.as_str()).map(|p| p.0))
+                    .collect();
+                let key = if slots.is_empty() {
+                    i as f32
+                } else {
+                    slots.iter().sum::<f32>() / slots.len() as f32
+                };
+                (idea, key)
+            })
+            .collect();
+        ideas.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+        for (slot, (idea, _)) in ideas.into_iter().enumerate() {
+            positions.insert(&idea.id, (slot as f32, row));
+        }
+    }
+
+    // Center every row on the widest one
+    let mut widths: HashMap<usize, usize> = HashMap::new();
+    for (_, row) in positions.values() {
+        *widths.entry(*row).or_default() += 1;
+    }
+    let widest = widths.values().copied().max().unwrap_or(0);
+    for (slot, row) in positions.values_mut() {
+        *slot += (widest - widths[row]) as f32 / 2.0;
+    }
+    positions
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Standalone SVG of the lineage, laid out without external tools
+pub fn render_svg(state: &RunState) -> String {
+    let positions = layout(state);
+    let slots = positions
+        .values()
+        .map(|p| p.0 + 1.0)
+        .fold(1.0_f32, f32::max);
+    let rows = positions.values().map(|p| p.1 + 1).max().unwrap_or(1);
+    let width = MARGIN * 2.0 + slots * SLOT_WIDTH;
+    let height = MARGIN * 2.0 + 20.0 + rows as f32 * ROW_HEIGHT;
+    let center = |id: &str| {
+        positions.get(id).map(|&(slot, row)| {
+            (
+                MARGIN + slot * SLOT_WIDTH + SLOT_WIDTH / 2.0,
+                MARGIN + 20.0 + row as f32 * ROW_HEIGHT + BOX_HEIGHT / 2.0,
+            )
+        })
+    };
+
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w:.0}" height="{h:.0}" viewBox="0 0 {w:.0} {h:.0}" font-family="Helvetica, Arial, sans-serif">"#,
+        w = width,
+        h = height
+    );
+    let _ = writeln!(
+        out,
+        r#"  <text x="{:.0}" y="{:.0}" font-size="14" font-weight="bold">Evolution: {}</text>"#,
+        MARGIN,
+        MARGIN,
+        xml_escape(&state.run_id)
+    );
+
+    for idea in &state.ideas {
+        let Some((x2, y2)) = center(&idea.id) else {
+            continue;
+        };
+        for parent in &idea.parents {
+            let Some((x1, y1)) = center(parent) else {
+                continue;
+            };
+            let (y1, y2) = (y1 + BOX_HEIGHT / 2.0, y2 - BOX_HEIGHT / 2.0);
+            let mid = (y1 + y2) / 2.0;
+            let _ = writeln!(
+                out,
+                r##"  <path d="M{x1:.1},{y1:.1} C{x1:.1},{mid:.1} {x2:.1},{mid:.1} {x2:.1},{y2:.1}" fill="none" stroke="#999"/>"##
+            );
+            let _ = writeln!(
+                out,
+                r##"  <text x="{:.1}" y="{:.1}" font-size="9" fill="#666" text-anchor="middle">{}</text>"##,
+                (x1 + x2) / 2.0,
+                mid,
+                idea.origin.as_str()
+            );
+        }
+    }
+
+    for idea in &state.ideas {
+        let Some((cx, cy)) = center(&idea.id) else {
+            continue;
+        };
+        let score = idea.overall_score.unwrap_or(0.0);
+        let box_width = SLOT_WIDTH * (0.6 + 0.03 * score.clamp(0.0, 10.0));
+        let (fill, stroke) = status_colors(&idea.status);
+        let _ = writeln!(
+            out,
+            r#"  <g><title>{}</title>"#,
+            xml_escape(&format!("{} {} [{:.2}]", idea.id, idea.title, score))
+        );
+        let _ = writeln!(
+            out,
+            r#"    <rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" rx="8" fill="{}" stroke="{}"/>"#,
+            cx - box_width / 2.0,
+            cy - BOX_HEIGHT / 2.0,
+            box_width,
+            BOX_HEIGHT,
+            fill,
+            stroke
+        );
+        let _ = writeln!(
+            out,
+            r#"    <text x="{cx:.1}" y="{:.1}" font-size="10" text-anchor="middle">{} [{:.1}]</text>"#,
+            cy - 6.0,
+            xml_escape(&idea.id),
+            score
+        );
+        let _ = writeln!(
+            out,
+            r#"    <text x="{cx:.1}" y="{:.1}" font-size="10" text-anchor="middle">{}</text>"#,
+            cy + 10.0,
+            xml_escape(&short_title(&idea.title, (box_width / 6.5) as usize))
+        );
+        out.push_str("  </g>\n");
+    }
+
+    out.push_str("</svg>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::FileStorage;
+    use tempfile::TempDir;
+
+    fn lineage_state() -> RunState {
+        serde_json::from_value(serde_json::json!({
+            "run_id": "run-1",
+            "iteration": 2,
+            "ideas": [
+                {"id": "idea-1", "origin": "generated", "title": "Tea & \"cakes\"", "status": "archived", "overall_score": 5.0},
+                {"id": "idea-2", "origin": "generated", "title": "B", "status": "eliminated", "overall_score": 3.0},
+                {"id": "idea-3", "origin": "crossover", "parents": ["idea-1", "idea-2"], "title": "AB", "status": "active", "overall_score": 7.5},
+                {"id": "idea-4", "origin": "refined", "parents": ["idea-3"], "title": "AB+", "status": "active"}
+            ]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_dot_colors_nodes_and_labels_edges() {
+        let dot = render_dot(&lineage_state());
+        assert!(dot.starts_with("digraph \"run-1\" {"));
+        assert!(dot.contains(
+            "\"idea-1\" [label=\"idea-1\\nTea & \\\"cakes\\\"\\n5.0\", fillcolor=\"#D3D3D3\""
+        ));
+        assert!(dot.contains("\"idea-2\" [label=\"idea-2\\nB\\n3.0\", fillcolor=\"#FFB6C1\""));
+        assert!(dot.contains("\"idea-1\" -> \"idea-3\" [label=\"crossover\"];"));
+        assert!(dot.contains("\"idea-3\" -> \"idea-4\" [label=\"refined\"];"));
+        assert!(dot.trim_end().ends_with('}'));
+    }
+
+    #[test]
+    fn test_svg_places_children_below_parents() {
+        let state = lineage_state();
+        let positions = layout(&state);
+        assert_eq!(positions["idea-1"].1, 0);
+        assert_eq!(positions["idea-3"].1, 1);
+        assert_eq!(positions["idea-4"].1, 2);
+        // A single child sits between its two parents
+        assert_eq!(positions["idea-3"].0, 0.5);
+
+        let svg = render_svg(&state);
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.trim_end().ends_with("</svg>"));
+        assert_eq!(svg.matches("<rect").count(), 4);
+        assert_eq!(svg.matches("<path").count(), 3);
+        assert!(svg.contains("Tea &amp; &quot;cakes&quot;"));
+        assert!(!svg.contains("Tea & "));
+    }
+
+    #[test]
+    fn test_unknown_format_is_rejected() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = FileStorage::new(temp_dir.path());
+        let err = render_tree(&storage, "missing-run", "png", None).unwrap_err();
+        assert!(err.to_string().contains("Unknown format: png"));
+    }
+}
@@ -0,0 +1,652 @@
+use anyhow::Result;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Gauge, List, ListItem, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use std::collections::HashSet;
+use std::io::IsTerminal;
+
+use crate::data::{Comparison, Idea, Outcome, Preferences, RunState};
+use crate::orchestrator::{self, PairwiseStep};
+use crate::ranking;
+use crate::storage::Storage;
+
+const HELP: &str = "[a/←] A  [b/→] B  [t] tie  [n] neither  [s] skip  [u] undo  [r] rationale  [c] scores  [q] quit";
+
+/// What a key press did to the tournament
+#[derive(Debug, PartialEq)]
+enum Action {
+    None,
+    /// Preferences changed and should be saved
+    Changed,
+    Quit,
+}
+
+/// Full-screen tournament: the pair being judged, the live Elo leaderboard
+/// and the progress towards the end of the tournament
+struct App<'a> {
+    state: &'a RunState,
+    ideas: &'a [&'a Idea],
+    preferences: &'a mut Preferences,
+    /// Pairwise mode stops once this many leading ideas are settled; `None`
+    /// asks every pair in order
+    top_k: Option<usize>,
+    skipped: HashSet<(String, String)>,
+    /// Pair on screen, `None` once the tournament is over
+    pair: Option<(String, String)>,
+    /// Top-k confidence when the pair was picked (pairwise mode)
+    confidence: f64,
+    show_scores: bool,
+    /// Hide every score and Elo rating and assign A and B at random
+    blind: bool,
+    /// Rationale being typed; `Some` while the input line is open
+    input: Option<String>,
+    /// Rationale attached to the next answer
+    rationale: Option<String>,
+    status: String,
+    /// Why the tournament ended, printed after leaving the full screen
+    finished: Option<String>,
+    /// Answers recorded in this session
+    recorded: usize,
+    /// Comparisons in preferences.json before this session's answers
+    session_start: usize,
+}
+
+impl<'a> App<'a> {
+    fn new(
+        state: &'a RunState,
+        ideas: &'a [&'a Idea],
+        preferences: &'a mut Preferences,
+        top_k: Option<usize>,
+        blind: bool,
+    ) -> Self {
+        let session_start = preferences.comparisons.len();
+        let mut app = Self {
+            state,
+            ideas,
+            preferences,
+            top_k,
+            skipped: HashSet::new(),
+            pair: None,
+            confidence: 0.0,
+            show_scores: false,
+            blind,
+            input: None,
+            rationale: None,
+            status: String::new(),
+            finished: None,
+            recorded: 0,
+            session_start,
+        };
+        app.advance();
+        app
+    }
+
+    /// Pick the next pair, or record why the tournament is over
+    fn advance(&mut self) {
+        let comparisons = &self.preferences.comparisons;
+        let Some(k) = self.top_k else {
+            self.pair = orchestrator::next_unanswered_pair(self.ideas, comparisons, &self.skipped)
+                .map(|pair| orchestrator::present(pair, self.blind));
+            if self.pair.is_none() {
+                self.finished = Some("All pairs compared!".to_string());
+            }
+            return;
+        };
+
+        let excluded: HashSet<(String, String)> = orchestrator::compared_pairs(comparisons)
+            .union(&self.skipped)
+            .cloned()
+            .collect();
+        match orchestrator::next_pairwise_step(self.ideas, self.state, comparisons, &excluded, k) {
+            PairwiseStep::Ask { pair, confidence } => {
+                self.pair = Some(orchestrator::present(pair, self.blind));
+                self.confidence = confidence;
+            }
+            PairwiseStep::Settled { top, confidence } => {
+                self.pair = None;
+                self.finished = Some(format!(
+                    "Top {} settled with {:.0}% confidence: {}",
+                    k,
+                    confidence * 100.0,
+                    top.join(", ")
+                ));
+            }
+            PairwiseStep::Exhausted if !self.skipped.is_empty() => {
+                self.status = format!("Back to the {} skipped pair(s)", self.skipped.len());
+                self.skipped.clear();
+                self.advance();
+            }
+            PairwiseStep::Exhausted => {
+                self.pair = None;
+                self.finished = Some("All pairs compared!".to_string());
+            }
+        }
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> Action {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            return Action::Quit;
+        }
+        if let Some(input) = &mut self.input {
+            match key.code {
+                KeyCode::Enter => {
+                    let text = input.trim().to_string();
+                    self.status = if text.is_empty() {
+                        "No rationale".to_string()
+                    } else {
+                        "Rationale will be saved with the next answer".to_string()
+                    };
+                    self.rationale = Some(text).filter(|t| !t.is_empty());
+                    self.input = None;
+                }
+                KeyCode::Esc => self.input = None,
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Char(c) => input.push(c),
+                _ => {}
+            }
+            return Action::None;
+        }
+
+        let Some((id_a, id_b)) = self.pair.clone() else {
+            return Action::Quit;
+        };
+        let comparison = match key.code {
+            KeyCode::Char('a') | KeyCode::Left => Comparison::decided(&id_a, &id_b, &id_a),
+            KeyCode::Char('b') | KeyCode::Right => Comparison::decided(&id_a, &id_b, &id_b),
+            KeyCode::Char('t') => Comparison::tie(&id_a, &id_b),
+            KeyCode::Char('n') => Comparison::neither(&id_a, &id_b),
+            KeyCode::Char('s') => {
+                self.skipped.insert(orchestrator::pair_key(&id_a, &id_b));
+                self.status = format!("Skipped {} vs {}", id_a, id_b);
+                self.advance();
+                return Action::None;
+            }
+            KeyCode::Char('u') => {
+                let Some(undone) = orchestrator::undo_last(self.preferences) else {
+                    self.status = "Nothing to undo".to_string();
+                    return Action::None;
+                };
+                if orchestrator::undid_session_answer(self.preferences, &mut self.session_start) {
+                    self.recorded -= 1;
+                }
+                self.status = format!(
+                    "Undid {} vs {} ({}); ratings recomputed",
+                    undone.idea_a,
+                    undone.idea_b,
+                    undone.describe()
+                );
+                self.advance();
+                return Action::Changed;
+            }
+            KeyCode::Char('r') => {
+                self.input = Some(self.rationale.take().unwrap_or_default());
+                return Action::None;
+            }
+            KeyCode::Char('c') if self.blind => {
+                self.status = "Scores stay hidden in blind mode".to_string();
+                return Action::None;
+            }
+            KeyCode::Char('c') => {
+                self.show_scores = !self.show_scores;
+                return Action::None;
+            }
+            KeyCode::Char('q') | KeyCode::Esc => return Action::Quit,
+            _ => return Action::None,
+        };
+
+        self.status = match comparison.outcome {
+            Outcome::Decided => format!("{} wins", comparison.winner),
+            Outcome::Tie => format!("Tie: {} = {}", id_a, id_b),
+            Outcome::Neither => format!("Neither acceptable; {} and {} flagged", id_a, id_b),
+        };
+        orchestrator::record_answer(
+            self.preferences,
+            comparison,
+            self.rationale.take().as_deref(),
+            self.blind,
+        );
+        self.recorded += 1;
+        self.advance();
+        Action::Changed
+    }
+
+    fn idea(&self, id: &str) -> Option<&'a Idea> {
+        self.ideas.iter().find(|idea| idea.id == id).copied()
+    }
+
+    fn elo(&self, id: &str) -> f64 {
+        *self.preferences.elo_ratings.get(id).unwrap_or(&1000.0)
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let [header, body, progress, footer] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Min(8),
+            Constraint::Length(3),
+            Constraint::Length(2),
+        ])
+        .areas(frame.area());
+        let [cards, board] =
+            Layout::horizontal([Constraint::Percentage(72), Constraint::Percentage(28)])
+                .areas(body);
+        let [left, right] =
+            Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .areas(cards);
+
+        let mut mode = match self.top_k {
+            Some(k) => format!("pairwise, top {}", k),
+            None => "every pair".to_string(),
+        };
+        if self.blind {
+            mode.push_str(", blind");
+        }
+        frame.render_widget(
+            Line::from(vec![
+                format!(" Tournament {} ", self.state.run_id).bold(),
+                format!("({}) · comparison {}", mode, self.recorded + 1).into(),
+            ]),
+            header,
+        );
+
+        if let Some((id_a, id_b)) = &self.pair {
+            for (label, id, area) in [("A", id_a, left), ("B", id_b, right)] {
+                if let Some(idea) = self.idea(id) {
+                    frame.render_widget(self.card(label, idea), area);
+                }
+            }
+        }
+        if self.blind {
+            frame.render_widget(
+                Paragraph::new("Hidden until the tournament ends".dark_gray())
+                    .block(Block::bordered().title(" Leaderboard ".bold())),
+                board,
+            );
+        } else {
+            frame.render_widget(self.leaderboard(), board);
+        }
+        frame.render_widget(self.progress(), progress);
+
+        let status = match &self.rationale {
+            Some(r) => format!("{}  (rationale: {})", self.status, r),
+            None => self.status.clone(),
+        };
+        let prompt = match &self.input {
+            Some(input) => Line::from(vec![
+                "Rationale: ".bold(),
+                format!("{}_", input).into(),
+                "  (Enter to keep, Esc to cancel)".dark_gray(),
+            ]),
+            None => Line::from(HELP.dark_gray()),
+        };
+        frame.render_widget(Paragraph::new(vec![Line::from(status), prompt]), footer);
+    }
+
+    /// Title, summary, the six facets and optionally the criterion scores
+    fn card(&self, label: &str, idea: &Idea) -> Paragraph<'static> {
+        let mut meta = format!("{} · {}", idea.id, idea.origin.as_str());
+        if !self.blind {
+            let score = idea
+                .overall_score
+                .map(|s| format!("{:.2}", s))
+                .unwrap_or_else(|| "-".to_string());
+            meta.push_str(&format!(
+                " · score {} · Elo {:.0}",
+                score,
+                self.elo(&idea.id)
+            ));
+        }
+        let mut lines = vec![
+            Line::from(idea.title.clone().bold()),
+            Line::from(meta.dark_gray()),
+            Line::default(),
+            Line::from(idea.summary.clone()),
+        ];
+        lines.push(Line::default());
+        for (name, text) in idea.facets.fields() {
+            let text = if text.trim().is_empty() { "-" } else { text };
+            lines.push(Line::from(vec![
+                format!("{}: ", name).cyan().bold(),
+                text.to_string().into(),
+            ]));
+        }
+        if let (true, Some(scores)) = (self.show_scores, &idea.scores) {
+            lines.push(Line::default());
+            lines.push(Line::from("scores".cyan().bold()));
+            for (name, value) in scores.criteria() {
+                lines.push(Line::from(format!("{:<16} {:>4.1}", name, value)));
+            }
+        }
+
+        let mut block = Block::bordered().title(format!(" [{}] ", label).bold());
+        if self.preferences.rejected().contains(idea.id.as_str()) {
+            block = block.title_bottom(" flagged: neither acceptable ".red());
+        }
+        Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false })
+    }
+
+    /// Eligible ideas by Elo; the pair on screen is highlighted
+    fn leaderboard(&self) -> List<'static> {
+        let rejected = self.preferences.rejected();
+        let mut ranked: Vec<(&Idea, f64)> = self
+            .ideas
+            .iter()
+            .map(|idea| (*idea, self.elo(&idea.id)))
+            .collect();
+        ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+
+        let on_screen = |id: &str| {
+            self.pair
+                .as_ref()
+                .is_some_and(|(a, b)| a.as_str() == id || b.as_str() == id)
+        };
+        let items: Vec<ListItem> = ranked
+            .iter()
+            .enumerate()
+            .map(|(rank, (idea, elo))| {
+                let flag = if rejected.contains(idea.id.as_str()) {
+                    " ✗"
+                } else {
+                    ""
+                };
+                let line = Line::from(vec![
+                    Span::raw(format!("{:>2}. {:>4.0} ", rank + 1, elo)),
+                    Span::raw(format!("{}{}", idea.title, flag)),
+                ]);
+                let style = if on_screen(&idea.id) {
+                    Style::new().fg(Color::Yellow).bold()
+                } else {
+                    Style::new()
+                };
+                ListItem::new(line).style(style)
+            })
+            .collect();
+        List::new(items).block(Block::bordered().title(" Leaderboard (Elo) ".bold()))
+    }
+
+    /// Top-k confidence towards the stopping point in pairwise mode, share of
+    /// pairs answered otherwise. The confidence comes from the fitted ratings,
+    /// so blind mode only counts answers.
+    fn progress(&self) -> Gauge<'static> {
+        let (ratio, label) = match self.top_k {
+            Some(k) if !self.blind => (
+                self.confidence / ranking::TOP_K_CONFIDENCE,
+                format!(
+                    "top {} confidence {:.0}% (stops at {:.0}%) · {} answered",
+                    k,
+                    self.confidence * 100.0,
+                    ranking::TOP_K_CONFIDENCE * 100.0,
+                    self.recorded
+                ),
+            ),
+            _ => {
+                let n = self.ideas.len();
+                let total = (n * n.saturating_sub(1) / 2).max(1);
+                let ids: HashSet<&str> = self.ideas.iter().map(|idea| idea.id.as_str()).collect();
+                let answered = orchestrator::compared_pairs(&self.preferences.comparisons)
+                    .iter()
+                    .filter(|(a, b)| ids.contains(a.as_str()) && ids.contains(b.as_str()))
+                    .count();
+                (
+                    answered as f64 / total as f64,
+                    format!("{} / {} pairs answered", answered, total),
+                )
+            }
+        };
+        Gauge::default()
+            .block(Block::bordered().title(" Progress ".bold()))
+            .gauge_style(Style::new().fg(Color::Green))
+            .ratio(ratio.clamp(0.0, 1.0))
+            .label(label)
+    }
+
+    fn event_loop(
+        &mut self,
+        terminal: &mut DefaultTerminal,
+        storage: &dyn Storage,
+        run_id: &str,
+    ) -> Result<()> {
+        while self.pair.is_some() {
+            terminal.draw(|frame| self.render(frame))?;
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match self.handle_key(key) {
+                Action::Changed => storage.save_preferences(run_id, self.preferences)?,
+                Action::Quit => break,
+                Action::None => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Run the tournament full-screen until it finishes or the user quits,
+/// saving preferences after every answer. Returns the number of answers
+/// recorded.
+pub fn run_tournament(
+    storage: &dyn Storage,
+    run_id: &str,
+    state: &RunState,
+    ideas: &[&Idea],
+    preferences: &mut Preferences,
+    top_k: Option<usize>,
+    blind: bool,
+) -> Result<usize> {
+    if !std::io::stdout().is_terminal() {
+        anyhow::bail!("--tui needs an interactive terminal");
+    }
+    let mut app = App::new(state, ideas, preferences, top_k, blind);
+    let mut terminal = ratatui::init();
+    let result = app.event_loop(&mut terminal, storage, run_id);
+    ratatui::restore();
+    result?;
+
+    if let Some(message) = &app.finished {
+        println!("{}\n", message);
+    }
+    Ok(app.recorded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    fn state() -> RunState {
+        let idea = |n: usize, score: f32| {
+            serde_json::json!({
+                "id": format!("idea-{}", n), "origin": "generated", "title": format!("Idea number {}", n),
+                "summary": format!("Summary of idea {}", n), "status": "active",
+                "facets": {"audience": format!("Audience {}", n), "monetization": "Subscription"},
+                "scores": {"feasibility": score, "speed_to_value": score, "differentiation": score, "market_size": score,
+                           "distribution": score, "moats": score, "risk": score, "clarity": score},
+                "overall_score": score
+            })
+        };
+        serde_json::from_value(serde_json::json!({
+            "run_id": "run-1",
+            "iteration": 1,
+            "ideas": [idea(1, 8.0), idea(2, 6.0), idea(3, 4.0)]
+        }))
+        .unwrap()
+    }
+
+    fn press(app: &mut App, code: KeyCode) -> Action {
+        app.handle_key(KeyEvent::from(code))
+    }
+
+    fn screen(app: &App) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(160, 48)).unwrap();
+        terminal.draw(|frame| app.render(frame)).unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..buffer.area.height)
+            .map(|y| {
+                (0..buffer.area.width)
+                    .map(|x| buffer[(x, y)].symbol())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn test_cards_show_summaries_facets_and_optional_scores() {
+        let state = state();
+        let ideas: Vec<&Idea> = state.ideas.iter().collect();
+        let mut preferences = Preferences::default();
+        let mut app = App::new(&state, &ideas, &mut preferences, None, false);
+
+        let text = screen(&app);
+        assert!(text.contains("[A]") && text.contains("[B]"));
+        assert!(text.contains("Summary of idea 1") && text.contains("Summary of idea 2"));
+        assert!(text.contains("Audience 2") && text.contains("risks"));
+        assert!(text.contains("Leaderboard (Elo)") && text.contains("Idea number 3"));
+        assert!(text.contains("0 / 3 pairs answered"));
+        assert!(!text.contains("feasibility"));
+
+        press(&mut app, KeyCode::Char('c'));
+        assert!(screen(&app).contains("feasibility"));
+    }
+
+    #[test]
+    fn test_keys_record_answers_and_undo() {
+        let state = state();
+        let ideas: Vec<&Idea> = state.ideas.iter().collect();
+        let mut preferences = Preferences::default();
+        let mut app = App::new(&state, &ideas, &mut preferences, None, false);
+
+        assert_eq!(press(&mut app, KeyCode::Left), Action::Changed);
+        assert_eq!(app.pair, Some(("idea-1".to_string(), "idea-3".to_string())));
+        assert!(app.preferences.elo_ratings["idea-1"] > 1000.0);
+
+        // Undo puts the pair back and rebuilds the ratings without it
+        assert_eq!(press(&mut app, KeyCode::Char('u')), Action::Changed);
+        assert_eq!(app.pair, Some(("idea-1".to_string(), "idea-2".to_string())));
+        assert!(app.preferences.comparisons.is_empty());
+        assert!(app
+            .preferences
+            .elo_ratings
+            .values()
+            .all(|elo| *elo == 1000.0));
+
+        press(&mut app, KeyCode::Char('b'));
+        press(&mut app, KeyCode::Char('s'));
+        assert_eq!(app.pair, Some(("idea-2".to_string(), "idea-3".to_string())));
+
+        // A rationale typed before answering is saved with the answer
+        press(&mut app, KeyCode::Char('r'));
+        for c in "same".chars() {
+            press(&mut app, KeyCode::Char(c));
+        }
+        assert_eq!(press(&mut app, KeyCode::Enter), Action::None);
+        assert_eq!(press(&mut app, KeyCode::Char('t')), Action::Changed);
+        assert!(app.pair.is_none());
+        assert_eq!(app.recorded, 2);
+
+        let comparisons = &app.preferences.comparisons;
+        assert_eq!(comparisons[0].winner, "idea-2");
+        assert_eq!(comparisons[1].outcome, Outcome::Tie);
+        assert_eq!(comparisons[1].rationale.as_deref(), Some("same"));
+        assert_eq!(press(&mut app, KeyCode::Char('q')), Action::Quit);
+    }
+
+    #[test]
+    fn test_undo_into_an_earlier_session_keeps_the_count() {
+        let state = state();
+        let ideas: Vec<&Idea> = state.ideas.iter().collect();
+        let mut preferences = Preferences::default();
+        orchestrator::append_comparison(
+            &mut preferences,
+            Comparison::decided("idea-1", "idea-2", "idea-1"),
+            None,
+        );
+        let mut app = App::new(&state, &ideas, &mut preferences, None, false);
+
+        press(&mut app, KeyCode::Char('a'));
+        assert_eq!(app.recorded, 1);
+        press(&mut app, KeyCode::Char('u'));
+        assert_eq!(app.recorded, 0);
+        // Undoing the previous session's answer is not one of ours to uncount
+        assert_eq!(press(&mut app, KeyCode::Char('u')), Action::Changed);
+        assert!(app.preferences.comparisons.is_empty());
+        assert_eq!(app.recorded, 0);
+        press(&mut app, KeyCode::Char('b'));
+        assert_eq!(app.recorded, 1);
+    }
+
+    #[test]
+    fn test_rationale_and_neither_are_recorded() {
+        let state = state();
+        let ideas: Vec<&Idea> = state.ideas.iter().collect();
+        let mut preferences = Preferences::default();
+        let mut app = App::new(&state, &ideas, &mut preferences, Some(1), false);
+        assert!(app.pair.is_some());
+
+        press(&mut app, KeyCode::Char('r'));
+        for c in "both weak".chars() {
+            press(&mut app, KeyCode::Char(c));
+        }
+        press(&mut app, KeyCode::Enter);
+        press(&mut app, KeyCode::Char('n'));
+
+        let comparison = &app.preferences.comparisons[0];
+        assert!(comparison.is_neither());
+        assert_eq!(comparison.rationale.as_deref(), Some("both weak"));
+        assert_eq!(app.preferences.rejected().len(), 2);
+        assert!(screen(&app).contains("✗"));
+    }
+
+    #[test]
+    fn test_blind_mode_hides_numbers_and_marks_answers() {
+        let state = state();
+        let ideas: Vec<&Idea> = state.ideas.iter().collect();
+        let mut preferences = Preferences::default();
+        let mut app = App::new(&state, &ideas, &mut preferences, None, true);
+
+        press(&mut app, KeyCode::Char('c'));
+        let text = screen(&app);
+        assert!(text.contains("Summary of idea 1") && text.contains("Summary of idea 2"));
+        for hidden in ["Elo 1000", "score 8.00", "feasibility", "Idea number 3"] {
+            assert!(!text.contains(hidden), "{} is visible", hidden);
+        }
+
+        press(&mut app, KeyCode::Char('a'));
+        let comparison = &app.preferences.comparisons[0];
+        assert!(comparison.blind);
+        assert_eq!(comparison.winner, comparison.idea_a);
+        assert_eq!(
+            orchestrator::pair_key(&comparison.idea_a, &comparison.idea_b),
+            orchestrator::pair_key("idea-1", "idea-2")
+        );
+    }
+
+    #[test]
+    fn test_blind_pairwise_progress_shows_only_answer_counts() {
+        let state = state();
+        let ideas: Vec<&Idea> = state.ideas.iter().collect();
+        let mut preferences = Preferences::default();
+        let mut app = App::new(&state, &ideas, &mut preferences, Some(1), false);
+        assert!(screen(&app).contains("confidence"));
+
+        app.blind = true;
+        press(&mut app, KeyCode::Char('a'));
+        let text = screen(&app);
+        assert!(
+            !text.contains("confidence") && !text.contains('%'),
+            "{}",
+            text
+        );
+        assert!(text.contains("1 / 3 pairs answered"), "{}", text);
+    }
+}
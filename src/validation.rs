@@ -0,0 +1,894 @@
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use crate::config::Constraints;
+use crate::data::{
+    join_violations, EventType, FinalResult, HistoryEvent, IdeaStatus, Origin, RunState,
+};
+use crate::llm::Stage;
+use crate::schema;
+use crate::similarity::{self, similar_active_ideas};
+use crate::storage::{FileStorage, Storage};
+
+/// Exit code when a run has at least one error
+pub const EXIT_ERRORS: i32 = 1;
+/// Exit code when a run has warnings but no errors (2 is taken by usage errors)
+pub const EXIT_WARNINGS: i32 = 3;
+
+/// Errors make a run invalid; warnings flag data worth a look
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+/// A single problem found while validating a run
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Finding {
+    pub check: &'static str,
+    pub severity: Severity,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub idea_id: Option<String>,
+    pub message: String,
+}
+
+impl Finding {
+    fn error(check: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            check,
+            severity: Severity::Error,
+            idea_id: None,
+            message: message.into(),
+        }
+    }
+
+    fn warning(check: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            ..Self::error(check, message)
+        }
+    }
+
+    fn idea(mut self, id: &str) -> Self {
+        self.idea_id = Some(id.to_string());
+        self
+    }
+}
+
+/// JSON form of a report, as printed by `validate --format json`
+#[derive(Serialize)]
+struct JsonReport<'a> {
+    run_id: &'a str,
+    status: &'static str,
+    findings: &'a [Finding],
+}
+
+/// Findings for one run, plus the "X: OK" lines printed in text mode
+#[derive(Debug)]
+pub struct Report {
+    pub run_id: String,
+    pub notes: Vec<String>,
+    pub findings: Vec<Finding>,
+}
+
+impl Report {
+    pub fn worst(&self) -> Option<Severity> {
+        self.findings.iter().map(|f| f.severity).max()
+    }
+
+    pub fn exit_code(&self) -> i32 {
+        exit_code(self.worst())
+    }
+
+    fn count(&self, severity: Severity) -> usize {
+        self.findings
+            .iter()
+            .filter(|f| f.severity == severity)
+            .count()
+    }
+
+    fn to_json(&self) -> JsonReport<'_> {
+        let status = match self.worst() {
+            Some(Severity::Error) => "errors",
+            Some(Severity::Warning) => "warnings",
+            None => "ok",
+        };
+        JsonReport {
+            run_id: &self.run_id,
+            status,
+            findings: &self.findings,
+        }
+    }
+
+    fn print_text(&self) {
+        for note in &self.notes {
+            println!("{}", note);
+        }
+
+        if self.findings.is_empty() {
+            println!("Invariants: OK");
+            return;
+        }
+        for (severity, label) in [(Severity::Error, "Errors"), (Severity::Warning, "Warnings")] {
+            let count = self.count(severity);
+            if count == 0 {
+                continue;
+            }
+            println!("{}: {} found", label, count);
+            for finding in self.findings.iter().filter(|f| f.severity == severity) {
+                println!("  - {}", finding.message);
+            }
+        }
+    }
+}
+
+fn exit_code(worst: Option<Severity>) -> i32 {
+    match worst {
+        Some(Severity::Error) => EXIT_ERRORS,
+        Some(Severity::Warning) => EXIT_WARNINGS,
+        None => 0,
+    }
+}
+
+fn check_format(format: &str) -> Result<()> {
+    match format {
+        "text" | "json" => Ok(()),
+        _ => anyhow::bail!("Unknown format: {} (supported: text, json)", format),
+    }
+}
+
+/// Validate one run and print the report; returns the process exit code
+pub fn validate_run(storage: &FileStorage, run_id: &str, format: &str) -> Result<i32> {
+    check_format(format)?;
+    let report = check_run(storage, run_id)?;
+
+    match format {
+        "json" => println!("{}", serde_json::to_string_pretty(&report.to_json())?),
+        _ => report.print_text(),
+    }
+
+    Ok(report.exit_code())
+}
+
+/// Validate every run under the runs directory; returns the worst exit code
+pub fn validate_all(storage: &FileStorage, format: &str) -> Result<i32> {
+    check_format(format)?;
+    let reports = storage
+        .run_ids()?
+        .iter()
+        .map(|run_id| check_run(storage, run_id))
+        .collect::<Result<Vec<_>>>()?;
+
+    match format {
+        "json" => {
+            let reports: Vec<_> = reports.iter().map(Report::to_json).collect();
+            println!("{}", serde_json::to_string_pretty(&reports)?);
+        }
+        _ => {
+            for report in &reports {
+                println!("== {} ==", report.run_id);
+                report.print_text();
+                println!();
+            }
+            let with = |severity| {
+                reports
+                    .iter()
+                    .filter(|r| r.worst() == Some(severity))
+                    .count()
+            };
+            println!(
+                "Validated {} runs: {} ok, {} with warnings, {} with errors",
+                reports.len(),
+                reports.iter().filter(|r| r.worst().is_none()).count(),
+                with(Severity::Warning),
+                with(Severity::Error)
+            );
+        }
+    }
+
+    Ok(exit_code(reports.iter().filter_map(Report::worst).max()))
+}
+
+/// Run every check against a run's artifacts
+pub fn check_run(storage: &FileStorage, run_id: &str) -> Result<Report> {
+    if !storage.run_exists(run_id)? {
+        anyhow::bail!("Run directory not found: {}", run_id);
+    }
+
+    let mut notes = Vec::new();
+    let mut findings = Vec::new();
+
+    // Validate config exists
+    let mut constraints = None;
+    if storage.config_path(run_id).exists() {
+        match storage.load_config(run_id) {
+            Ok(config) => {
+                let truncated: String = config.prompt.chars().take(30).collect();
+                notes.push(format!("Config: OK (prompt: {}...)", truncated));
+                constraints = Some(config.constraints);
+            }
+            Err(e) => findings.push(Finding::error("config", format!("{:#}", e))),
+        }
+    } else {
+        findings.push(Finding::error("config", "Config: MISSING"));
+    }
+
+    let mut loaded_state = None;
+
+    // Validate state
+    if storage.state_path(run_id).exists() {
+        match storage.load_state(run_id) {
+            Ok(state) => {
+                notes.push(format!(
+                    "State: OK (iteration: {}, ideas: {})",
+                    state.iteration,
+                    state.ideas.len()
+                ));
+
+                let eliminated = state.eliminated_ideas().count();
+                if eliminated > 0 {
+                    notes.push(format!("Eliminated: {} ideas", eliminated));
+                }
+
+                findings.extend(validate_state_idea_invariants(&state));
+                if let Some(constraints) = &constraints {
+                    findings.extend(validate_constraints(&state, constraints));
+                }
+                findings.extend(validate_similarity(&state));
+                loaded_state = Some(state);
+            }
+            Err(e) => findings.push(Finding::error("state", format!("{:#}", e))),
+        }
+    } else {
+        findings.push(Finding::error("state", "State: MISSING"));
+    }
+
+    let mut loaded_history = None;
+
+    // Validate history
+    if storage.history_path(run_id).exists() {
+        match storage.load_history(run_id) {
+            Ok(events) => {
+                notes.push(format!("History: OK ({} events)", events.len()));
+                loaded_history = Some(events);
+            }
+            Err(e) => findings.push(Finding::error("history", format!("{:#}", e))),
+        }
+    } else {
+        findings.push(Finding::error("history", "History: MISSING"));
+    }
+
+    let mut loaded_final = None;
+
+    // Validate final if exists
+    if storage.has_final(run_id)? {
+        match storage.load_final(run_id) {
+            Ok(result) => {
+                notes.push(format!("Final: OK (best: {})", result.best_idea.title));
+                let best = loaded_state
+                    .as_ref()
+                    .and_then(|s| s.idea(&result.best_idea.id));
+                if best.is_some_and(|idea| idea.status.is_eliminated()) {
+                    findings.push(
+                        Finding::error(
+                            "final",
+                            format!("Final best idea {} is eliminated", result.best_idea.id),
+                        )
+                        .idea(&result.best_idea.id),
+                    );
+                }
+                loaded_final = Some(result);
+            }
+            Err(e) => findings.push(Finding::error("final", format!("{:#}", e))),
+        }
+        if read_value(&storage.final_path(run_id)).is_some_and(|v| v.get("best").is_some()) {
+            findings.push(Finding::warning(
+                "final",
+                "final.json uses the legacy best/runners_up layout",
+            ));
+        }
+    } else {
+        notes.push("Final: NOT YET (run in progress)".to_string());
+    }
+
+    if let Some(events) = &loaded_history {
+        findings.extend(validate_history(
+            events,
+            loaded_state.as_ref(),
+            loaded_final.as_ref(),
+        ));
+    }
+
+    let schema_findings = validate_schemas(storage, run_id)?;
+    if schema_findings.is_empty() {
+        notes.push("Schemas: OK".to_string());
+    }
+    findings.extend(schema_findings);
+
+    Ok(Report {
+        run_id: run_id.to_string(),
+        notes,
+        findings,
+    })
+}
+
+/// Check final.json, each idea in state.json and the stage outputs recorded in
+/// history.ndjson against the bundled schemas. Files that do not parse are
+/// skipped here; the typed loaders already report them.
+fn validate_schemas(storage: &FileStorage, run_id: &str) -> Result<Vec<Finding>> {
+    let mut findings = Vec::new();
+    let mut report = |file: &str, idea_id: Option<&str>, violations: Vec<schema::Violation>| {
+        for violation in violations {
+            let finding = Finding::error("schema", format!("{}: {}", file, violation));
+            findings.push(match idea_id {
+                Some(id) => finding.idea(id),
+                None => finding,
+            });
+        }
+    };
+
+    if let Some(result) = read_value(&storage.final_path(run_id)) {
+        // Skill-written runs use the legacy `best`/`runners_up` layout
+        let name = if result.get("best").is_some() {
+            "final"
+        } else {
+            "run-final"
+        };
+        report(
+            "final.json",
+            None,
+            schema::validate(&schema::bundled(name)?, &result),
+        );
+    }
+
+    if let Some(state) = read_value(&storage.state_path(run_id)) {
+        let idea_schema = schema::bundled("idea")?;
+        let ideas = state.get("ideas").and_then(|i| i.as_array());
+        for (i, idea) in ideas.into_iter().flatten().enumerate() {
+            let violations = schema::validate(&idea_schema, idea)
+                .into_iter()
+                .map(|v| schema::Violation {
+                    path: format!("/ideas/{}{}", i, v.path),
+                    ..v
+                })
+                .collect();
+            let idea_id = idea.get("id").and_then(|id| id.as_str());
+            report("state.json", idea_id, violations);
+        }
+    }
+
+    if let Ok(raw) = fs::read_to_string(storage.history_path(run_id)) {
+        for (line_no, line) in raw.lines().enumerate() {
+            let Ok(event) = serde_json::from_str::<HistoryEvent>(line) else {
+                continue;
+            };
+            let output = event.payload.get("output");
+            if let (Some(stage), Some(output)) = (Stage::for_event(event.event_type), output) {
+                let violations = schema::validate(&schema::bundled(stage.name())?, output)
+                    .into_iter()
+                    .map(|v| schema::Violation {
+                        path: format!("/payload/output{}", v.path),
+                        ..v
+                    })
+                    .collect();
+                report(
+                    &format!(
+                        "history.ndjson line {} ({})",
+                        line_no + 1,
+                        event.event_type.as_str()
+                    ),
+                    None,
+                    violations,
+                );
+            }
+        }
+    }
+
+    Ok(findings)
+}
+
+fn read_value(path: &Path) -> Option<serde_json::Value> {
+    serde_json::from_str(&fs::read_to_string(path).ok()?).ok()
+}
+
+/// Check history.ndjson integrity: timestamps and iterations never go backwards,
+/// every idea in state.json was created by an event, and the closing `stopped`
+/// event agrees with final.json
+fn validate_history(
+    events: &[HistoryEvent],
+    state: Option<&RunState>,
+    result: Option<&FinalResult>,
+) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let mut previous: Option<(usize, chrono::DateTime<chrono::FixedOffset>, u32)> = None;
+
+    for (i, event) in events.iter().enumerate() {
+        let line = i + 1;
+        let ts = match chrono::DateTime::parse_from_rfc3339(&event.ts) {
+            Ok(ts) => ts,
+            Err(e) => {
+                findings.push(Finding::error(
+                    "history",
+                    format!(
+                        "history.ndjson line {}: invalid ts {:?}: {}",
+                        line, event.ts, e
+                    ),
+                ));
+                continue;
+            }
+        };
+        if let Some((prev_line, prev_ts, prev_iteration)) = previous {
+            if ts < prev_ts {
+                findings.push(Finding::error(
+                    "history",
+                    format!(
+                        "history.ndjson line {}: ts {} is earlier than line {}",
+                        line, event.ts, prev_line
+                    ),
+                ));
+            }
+            if event.iteration < prev_iteration {
+                findings.push(Finding::error(
+                    "history",
+                    format!(
+                        "history.ndjson line {}: iteration {} goes back from {}",
+                        line, event.iteration, prev_iteration
+                    ),
+                ));
+            }
+        }
+        previous = Some((line, ts, event.iteration));
+    }
+
+    if let Some(state) = state {
+        let created: HashSet<&str> = events.iter().flat_map(|e| e.created_ids()).collect();
+        for idea in state
+            .ideas
+            .iter()
+            .filter(|i| !created.contains(i.id.as_str()))
+        {
+            findings.push(
+                Finding::error(
+                    "history",
+                    format!("Idea {} has no creation event in history.ndjson", idea.id),
+                )
+                .idea(&idea.id),
+            );
+        }
+    }
+
+    let last = events.last();
+    match (result, last) {
+        (Some(result), Some(event)) if event.event_type == EventType::Stopped => {
+            let field = |key: &str| event.payload.get(key).and_then(|v| v.as_str());
+            // Legacy final.json has no stop_reason/iterations_completed to compare
+            let mut mismatches = Vec::new();
+            if !result.stop_reason.is_empty()
+                && field("reason") != Some(result.stop_reason.as_str())
+            {
+                mismatches.push(format!(
+                    "reason {:?} vs stop_reason {:?}",
+                    field("reason").unwrap_or(""),
+                    result.stop_reason
+                ));
+            }
+            if field("best_idea_id") != Some(result.best_idea.id.as_str()) {
+                mismatches.push(format!(
+                    "best_idea_id {:?} vs best_idea {:?}",
+                    field("best_idea_id").unwrap_or(""),
+                    result.best_idea.id
+                ));
+            }
+            if result.iterations_completed > 0 && event.iteration != result.iterations_completed {
+                mismatches.push(format!(
+                    "iteration {} vs iterations_completed {}",
+                    event.iteration, result.iterations_completed
+                ));
+            }
+            if !mismatches.is_empty() {
+                findings.push(Finding::error(
+                    "history",
+                    format!(
+                        "stopped event does not match final.json: {}",
+                        mismatches.join("; ")
+                    ),
+                ));
+            }
+        }
+        (Some(_), _) => findings.push(Finding::error(
+            "history",
+            "final.json exists but history.ndjson does not end with a stopped event",
+        )),
+        (None, Some(event)) if event.event_type == EventType::Stopped => {
+            findings.push(Finding::warning(
+                "history",
+                "history.ndjson ends with a stopped event but final.json is missing",
+            ))
+        }
+        (None, _) => {}
+    }
+
+    findings
+}
+
+pub(crate) fn validate_state_idea_invariants(state: &RunState) -> Vec<Finding> {
+    let mut findings = Vec::new();
+
+    for idea in &state.ideas {
+        let has_parents = !idea.parents.is_empty();
+
+        match idea.origin {
+            Origin::Generated if has_parents => {
+                findings.push(
+                    Finding::error(
+                        "lineage",
+                        format!("Idea {} (generated) has parents", idea.id),
+                    )
+                    .idea(&idea.id),
+                );
+            }
+            Origin::Refined | Origin::Crossover | Origin::Mutated if !has_parents => {
+                findings.push(
+                    Finding::error(
+                        "lineage",
+                        format!("Idea {} ({}) has no parents", idea.id, idea.origin.as_str()),
+                    )
+                    .idea(&idea.id),
+                );
+            }
+            _ => {}
+        }
+
+        if let IdeaStatus::Eliminated { violations } = &idea.status {
+            if violations.is_empty() {
+                findings.push(
+                    Finding::warning(
+                        "eliminated",
+                        format!("Idea {} (eliminated) has no recorded violations", idea.id),
+                    )
+                    .idea(&idea.id),
+                );
+            }
+            if state.best_idea_id.as_deref() == Some(idea.id.as_str()) {
+                findings.push(
+                    Finding::error(
+                        "eliminated",
+                        format!("Idea {} (eliminated) is the best idea", idea.id),
+                    )
+                    .idea(&idea.id),
+                );
+            }
+        }
+
+        // Active ideas should always be scored (tournament/profile export depends on it).
+        if idea.status == IdeaStatus::Active {
+            if idea.scores.is_none() {
+                findings.push(
+                    Finding::error(
+                        "scores",
+                        format!("Idea {} (active) has missing/invalid scores", idea.id),
+                    )
+                    .idea(&idea.id),
+                );
+            }
+            if idea.overall_score.is_none() {
+                findings.push(
+                    Finding::error(
+                        "scores",
+                        format!(
+                            "Idea {} (active) has missing/invalid overall_score",
+                            idea.id
+                        ),
+                    )
+                    .idea(&idea.id),
+                );
+            }
+        }
+    }
+
+    findings
+}
+
+/// Check that no active idea breaks the constraints in config.json
+fn validate_constraints(state: &RunState, constraints: &Constraints) -> Vec<Finding> {
+    state
+        .active_ideas()
+        .filter_map(|idea| {
+            let violations = constraints.check(idea);
+            (!violations.is_empty()).then(|| {
+                Finding::error(
+                    "constraints",
+                    format!(
+                        "Idea {} (active) violates constraints: {}",
+                        idea.id,
+                        join_violations(&violations)
+                    ),
+                )
+                .idea(&idea.id)
+            })
+        })
+        .collect()
+}
+
+/// Warn about active ideas that are near-duplicates of each other; each pair
+/// costs a wasted tournament comparison
+fn validate_similarity(state: &RunState) -> Vec<Finding> {
+    similar_active_ideas(state, similarity::DEFAULT_THRESHOLD)
+        .into_iter()
+        .map(|(a, b, sim)| {
+            Finding::warning(
+                "similarity",
+                format!(
+                    "Active ideas {} and {} are near-duplicates (similarity {:.2}); see `evoidea dedup`",
+                    a.id, b.id, sim
+                ),
+            )
+            .idea(&b.id)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RunConfig;
+
+    fn state_from(mut value: serde_json::Value) -> RunState {
+        value["run_id"] = "run-test".into();
+        value["iteration"] = 1.into();
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn test_config() -> RunConfig {
+        RunConfig {
+            run_id: "run-test".to_string(),
+            prompt: "Developer tools".to_string(),
+            max_rounds: 1,
+            ..Default::default()
+        }
+    }
+
+    fn messages(findings: &[Finding]) -> Vec<&str> {
+        findings.iter().map(|f| f.message.as_str()).collect()
+    }
+
+    #[test]
+    fn test_validate_state_invariants_flags_unscored_active_ideas() {
+        let state = state_from(serde_json::json!({
+            "ideas": [
+                {
+                    "id": "idea-1",
+                    "origin": "refined",
+                    "parents": ["idea-0"],
+                    "title": "Refined",
+                    "status": "active"
+                }
+            ]
+        }));
+
+        let errors = validate_state_idea_invariants(&state);
+        assert!(errors
+            .iter()
+            .any(|e| e.message.contains("missing/invalid scores")));
+        assert!(errors
+            .iter()
+            .any(|e| e.message.contains("missing/invalid overall_score")));
+        assert!(errors
+            .iter()
+            .all(|e| e.check == "scores" && e.idea_id.as_deref() == Some("idea-1")));
+    }
+
+    #[test]
+    fn test_validate_state_invariants_accepts_scored_active_ideas() {
+        let state = state_from(serde_json::json!({
+            "ideas": [
+                {
+                    "id": "idea-1",
+                    "origin": "generated",
+                    "parents": [],
+                    "title": "Generated",
+                    "status": "active",
+                    "scores": {"feasibility": 5, "speed_to_value": 5, "differentiation": 5, "market_size": 5, "distribution": 5, "moats": 5, "risk": 5, "clarity": 5},
+                    "overall_score": 5.0
+                }
+            ]
+        }));
+
+        let errors = validate_state_idea_invariants(&state);
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn test_validate_state_invariants_flags_eliminated_best_without_violations() {
+        let mut state = state_from(serde_json::json!({
+            "ideas": [
+                {
+                    "id": "idea-1",
+                    "origin": "generated",
+                    "title": "Crypto wallet",
+                    "status": "eliminated",
+                    "overall_score": 0.0
+                }
+            ]
+        }));
+        state.best_idea_id = Some("idea-1".to_string());
+
+        let errors = validate_state_idea_invariants(&state);
+        assert!(errors.iter().any(
+            |e| e.severity == Severity::Warning && e.message.contains("no recorded violations")
+        ));
+        assert!(errors
+            .iter()
+            .any(|e| e.severity == Severity::Error && e.message.contains("is the best idea")));
+    }
+
+    #[test]
+    fn test_validate_constraints_flags_active_violators() {
+        let state = state_from(serde_json::json!({
+            "ideas": [
+                {
+                    "id": "idea-1",
+                    "origin": "generated",
+                    "title": "Crypto wallet",
+                    "status": "active"
+                }
+            ]
+        }));
+        let constraints = Constraints {
+            forbidden: vec!["crypto".to_string()],
+            must_include: vec![],
+        };
+
+        let errors = validate_constraints(&state, &constraints);
+        assert_eq!(
+            messages(&errors),
+            vec!["Idea idea-1 (active) violates constraints: forbidden: mentions 'crypto'"]
+        );
+        assert_eq!(errors[0].idea_id.as_deref(), Some("idea-1"));
+    }
+
+    #[test]
+    fn test_validate_similarity_warns_about_active_duplicates() {
+        let idea = |id: &str, title: &str, status: &str| {
+            serde_json::json!({
+                "id": id,
+                "origin": "generated",
+                "title": title,
+                "summary": format!("{} for small teams", title),
+                "status": status
+            })
+        };
+        let state = state_from(serde_json::json!({
+            "ideas": [
+                idea("idea-1", "Git issue tracker", "active"),
+                idea("idea-2", "Git issue tracker", "active"),
+                idea("idea-3", "Git issue tracker", "archived"),
+                idea("idea-4", "Meal planner", "active")
+            ]
+        }));
+
+        let warnings = validate_similarity(&state);
+        assert_eq!(
+            messages(&warnings),
+            vec!["Active ideas idea-1 and idea-2 are near-duplicates (similarity 1.00); see `evoidea dedup`"]
+        );
+        assert_eq!(warnings[0].severity, Severity::Warning);
+    }
+
+    #[test]
+    fn test_validate_schemas_reports_pointers_per_file() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let storage = FileStorage::new(temp_dir.path());
+        crate::evolution::evolve(test_config(), &storage).unwrap();
+        assert!(validate_schemas(&storage, "run-test").unwrap().is_empty());
+
+        let path = storage.state_path("run-test");
+        let mut state: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        state["ideas"][1]["overall_score"] = serde_json::json!(12);
+        fs::write(&path, state.to_string()).unwrap();
+
+        let path = storage.history_path("run-test");
+        let history = fs::read_to_string(&path).unwrap();
+        let mut lines: Vec<String> = history.lines().map(String::from).collect();
+        let mut event: serde_json::Value = serde_json::from_str(&lines[0]).unwrap();
+        event["payload"]["output"]["ideas"][0]["title"] = serde_json::json!(7);
+        lines[0] = event.to_string();
+        fs::write(&path, lines.join("\n")).unwrap();
+
+        let errors = validate_schemas(&storage, "run-test").unwrap();
+        assert_eq!(
+            messages(&errors),
+            vec![
+                "state.json: /ideas/1/overall_score: 12 is greater than maximum 10",
+                "history.ndjson line 1 (generated): /payload/output/ideas/0/title: expected string, found number",
+            ]
+        );
+        assert_eq!(errors[0].idea_id.as_deref(), Some("idea-002"));
+        assert_eq!(
+            check_run(&storage, "run-test").unwrap().exit_code(),
+            EXIT_ERRORS
+        );
+    }
+
+    #[test]
+    fn test_validate_history_checks_order_creation_and_stop() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let storage = FileStorage::new(temp_dir.path());
+        let config = RunConfig {
+            max_rounds: 2,
+            ..test_config()
+        };
+        let result = crate::evolution::evolve(config, &storage).unwrap();
+        let state = storage.load_state("run-test").unwrap();
+        let mut events = storage.load_history("run-test").unwrap();
+        assert!(validate_history(&events, Some(&state), Some(&result)).is_empty());
+
+        // Drop the generated event, rewind a timestamp and the iteration
+        assert_eq!(events.remove(0).event_type, EventType::Generated);
+        events[1].ts = "2000-01-01T00:00:00+00:00".to_string();
+        events[2].iteration = 0;
+        let mut other = result.clone();
+        other.stop_reason = "threshold".to_string();
+
+        let findings = validate_history(&events, Some(&state), Some(&other));
+        let found = messages(&findings);
+        assert!(found.contains(&"history.ndjson line 3: iteration 0 goes back from 1"));
+        assert!(found
+            .iter()
+            .any(|m| m.starts_with("history.ndjson line 2: ts") && m.ends_with("line 1")));
+        assert!(found.contains(&"Idea idea-001 has no creation event in history.ndjson"));
+        assert!(found.contains(
+            &"stopped event does not match final.json: reason \"max_rounds\" vs stop_reason \"threshold\""
+        ));
+
+        events.pop();
+        let findings = validate_history(&events, None, Some(&result));
+        assert_eq!(
+            messages(&findings).last(),
+            Some(&"final.json exists but history.ndjson does not end with a stopped event")
+        );
+    }
+
+    #[test]
+    fn test_legacy_final_is_a_warning_in_the_json_report() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let storage = FileStorage::new(temp_dir.path());
+        let result = crate::evolution::evolve(test_config(), &storage).unwrap();
+
+        let best = result.best_idea;
+        let legacy = serde_json::json!({
+            "run_id": "run-test",
+            "best": {
+                "idea_id": best.id,
+                "title": best.title,
+                "summary": best.summary,
+                "facets": best.facets,
+                "scores": best.scores,
+                "overall_score": best.overall_score,
+                "why_won": ["Strongest scores", "Clear audience"]
+            },
+            "runners_up": []
+        });
+        fs::write(storage.final_path("run-test"), legacy.to_string()).unwrap();
+
+        let report = check_run(&storage, "run-test").unwrap();
+        assert_eq!(report.exit_code(), EXIT_WARNINGS);
+        assert_eq!(
+            serde_json::to_value(report.to_json()).unwrap(),
+            serde_json::json!({
+                "run_id": "run-test",
+                "status": "warnings",
+                "findings": [{
+                    "check": "final",
+                    "severity": "warning",
+                    "message": "final.json uses the legacy best/runners_up layout"
+                }]
+            })
+        );
+    }
+}